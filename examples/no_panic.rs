@@ -0,0 +1,37 @@
+//! Exercises the operations that never panic, so that building this in
+//! release mode with the `no-panic` feature checks them:
+//!
+//! ```text
+//! cargo build --release --example no_panic --features no-panic
+//! ```
+
+use core::mem::MaybeUninit;
+
+use fullhouse::{Deque, DequeBase, DequeView, Storage};
+
+fn exercise<S: Storage<u32>>(deque: &mut DequeBase<u32, S>, n: usize) -> u32 {
+    let mut sum = 0;
+    for i in 0..n as u32 * 20 {
+        let _ = deque.push_back(i);
+        let _ = deque.push_front(i);
+        if let Some(x) = deque.get_mut(n) {
+            *x += 1;
+        }
+        sum += deque.get(n + 1).copied().unwrap_or(0);
+        sum += deque.front().copied().unwrap_or(0) + deque.back().copied().unwrap_or(0);
+        if i % 3 == 0 {
+            sum += deque.pop_front().unwrap_or(0);
+            sum += deque.pop_back().unwrap_or(0);
+        }
+    }
+    sum + (deque.len() + deque.capacity()) as u32
+}
+
+fn main() {
+    let mut inline: Deque<u32, 7> = Deque::new();
+    let mut buf = [MaybeUninit::uninit(); 5];
+    let mut view = DequeView::new(&mut buf);
+    // Unknown at compile time, so nothing gets constant-folded away.
+    let n = std::env::args().count();
+    println!("{}", exercise(&mut inline, n) + exercise(&mut view, n));
+}
@@ -0,0 +1,50 @@
+/* C API for fullhouse byte deques. Requires the `ffi` feature. */
+
+#ifndef FULLHOUSE_H
+#define FULLHOUSE_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* An opaque handle to a byte deque. Allocate it anywhere, then initialize it
+ * with fullhouse_ring_init before use. */
+typedef struct fullhouse_ring {
+    uintptr_t opaque[5];
+} fullhouse_ring;
+
+/* Initializes `ring` as an empty deque using the `len` bytes at `buf` as
+ * storage. `buf` must stay valid for as long as the ring is used. It may be
+ * NULL if `len` is 0. */
+void fullhouse_ring_init(fullhouse_ring *ring, uint8_t *buf, size_t len);
+
+/* Appends as many of the `len` bytes at `data` as fit, returning the number
+ * of bytes appended. `data` may be NULL if `len` is 0. */
+size_t fullhouse_ring_write(fullhouse_ring *ring, const uint8_t *data, size_t len);
+
+/* Removes up to `len` bytes from the front of the ring into `out`, returning
+ * the number of bytes removed. `out` may be NULL if `len` is 0. */
+size_t fullhouse_ring_read(fullhouse_ring *ring, uint8_t *out, size_t len);
+
+/* Copies up to `len` bytes from the front of the ring into `out` without
+ * removing them, returning the number of bytes copied. `out` may be NULL if
+ * `len` is 0. */
+size_t fullhouse_ring_peek(fullhouse_ring *ring, uint8_t *out, size_t len);
+
+/* The number of bytes in the ring. */
+size_t fullhouse_ring_len(fullhouse_ring *ring);
+
+/* The maximum number of bytes the ring can hold. */
+size_t fullhouse_ring_capacity(fullhouse_ring *ring);
+
+/* Removes all bytes from the ring. */
+void fullhouse_ring_clear(fullhouse_ring *ring);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* FULLHOUSE_H */
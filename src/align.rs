@@ -0,0 +1,144 @@
+//! Storage with a guaranteed minimum alignment.
+
+use core::{
+    marker::PhantomData,
+    mem::MaybeUninit,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{DequeBase, Storage};
+
+/// Alignment marker types for [`Aligned`].
+///
+/// Each type `A<n>` is a zero-sized type with an alignment of `n` bytes.
+pub mod marker {
+    /// 4-byte alignment.
+    #[repr(align(4))]
+    pub struct A4;
+
+    /// 8-byte alignment.
+    #[repr(align(8))]
+    pub struct A8;
+
+    /// 16-byte alignment.
+    #[repr(align(16))]
+    pub struct A16;
+
+    /// 32-byte alignment, e.g. a Cortex-M7 cache line.
+    #[repr(align(32))]
+    pub struct A32;
+
+    /// 64-byte alignment.
+    #[repr(align(64))]
+    pub struct A64;
+}
+
+/// A value of type `T` aligned to at least the alignment of `A`.
+///
+/// `A` is one of the zero-sized [marker] types, since Rust does not allow the
+/// alignment of a type to be set by a const generic parameter. The wrapper
+/// dereferences to the inner value.
+///
+/// When the inner value is an array of `MaybeUninit<T>`, the wrapper can be
+/// used as deque [`Storage`], e.g. through the [`AlignedDeque`] alias.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::align::{marker::A32, Aligned};
+///
+/// let buf: Aligned<A32, [u8; 64]> = Aligned::new([0; 64]);
+/// assert_eq!(buf.as_ptr() as usize % 32, 0);
+/// ```
+#[repr(C)]
+pub struct Aligned<A, T: ?Sized> {
+    /// Zero-sized field that raises the alignment of the struct.
+    _align: [A; 0],
+    value: T,
+}
+
+impl<A, T> Aligned<A, T> {
+    /// Wraps a value.
+    pub const fn new(value: T) -> Self {
+        Self { _align: [], value }
+    }
+
+    /// Unwraps the value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<A, T: ?Sized> Deref for Aligned<A, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<A, T: ?Sized> DerefMut for Aligned<A, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+// Safety: The array is always the same length, and moves its contents along
+// with it.
+unsafe impl<A, T, const N: usize> Storage<T> for Aligned<A, [MaybeUninit<T>; N]> {
+    fn as_slice(&self) -> &[MaybeUninit<T>] {
+        &self.value
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [MaybeUninit<T>] {
+        &mut self.value
+    }
+
+    fn capacity(&self) -> usize {
+        N
+    }
+}
+
+/// A fixed-capacity deque with inline storage aligned to the alignment of
+/// `A`.
+///
+/// The first slot of the storage is aligned, which is what DMA engines and
+/// cache maintenance usually require of the buffer as a whole.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::align::{marker::A32, AlignedDeque};
+///
+/// let mut deque: AlignedDeque<u8, A32, 64> = AlignedDeque::new();
+/// deque.push_back(1).unwrap();
+/// assert_eq!(deque.get(0).unwrap() as *const u8 as usize % 32, 0);
+/// ```
+pub type AlignedDeque<T, A, const CAPACITY: usize> =
+    DequeBase<T, Aligned<A, [MaybeUninit<T>; CAPACITY]>>;
+
+impl<T, A, const CAPACITY: usize> AlignedDeque<T, A, CAPACITY> {
+    /// Creates an empty deque.
+    pub const fn new() -> Self {
+        Self {
+            start: 0,
+            end: 0,
+            len: 0,
+
+            // Safety: The value inside the outermost MaybeUninit must be valid.
+            // - A value of `[MaybeUninit<T>; N]` is valid because a value of
+            //  `MaybeUninit<T>` is always valid (even if the inner value
+            //  isn't).
+            storage: Aligned::new(unsafe {
+                MaybeUninit::<[MaybeUninit<T>; CAPACITY]>::uninit().assume_init()
+            }),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T, A, const CAPACITY: usize> Default for AlignedDeque<T, A, CAPACITY> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,623 @@
+//! A deque that async tasks can wait on.
+
+use core::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+use critical_section::Mutex;
+
+use crate::Deque;
+
+/// A task waiting on one end of an [`AsyncDeque`].
+struct Waiter {
+    id: u32,
+
+    /// `None` if the waiting future was dropped.
+    waker: Option<Waker>,
+}
+
+/// The tasks waiting on one end of an [`AsyncDeque`], in the order they
+/// started waiting.
+struct WaitQueue<const W: usize> {
+    waiters: Deque<Waiter, W>,
+
+    /// The number of tasks that were woken, and have not polled since.
+    ///
+    /// Each of these is entitled to one element (or one slot), so new tasks
+    /// may not take it from them.
+    granted: usize,
+}
+
+impl<const W: usize> WaitQueue<W> {
+    const fn new() -> Self {
+        Self {
+            waiters: Deque::new(),
+            granted: 0,
+        }
+    }
+
+    /// Returns `true` if no tasks are waiting.
+    fn is_empty(&mut self) -> bool {
+        // Remove dropped waiters, so they don't hold up new tasks.
+        while let Some(Waiter { waker: None, .. }) = self.waiters.front() {
+            self.waiters.pop_front();
+        }
+        self.waiters.is_empty()
+    }
+
+    /// Returns the waiter with the given ID, if it has not been woken.
+    fn find(&mut self, id: u32) -> Option<&mut Waiter> {
+        (0..self.waiters.len())
+            .find(|&i| self.waiters.get(i).map_or(false, |w| w.id == id))
+            .and_then(move |i| self.waiters.get_mut(i))
+    }
+
+    /// Wakes the task that has been waiting the longest.
+    fn wake_one(&mut self) {
+        while let Some(waiter) = self.waiters.pop_front() {
+            if let Some(waker) = waiter.waker {
+                self.granted += 1;
+                waker.wake();
+                return;
+            }
+        }
+    }
+
+    /// Polls on behalf of the future holding `ticket`.
+    ///
+    /// `available` is the number of elements (or slots) that could be
+    /// taken, and `take` takes one. If nothing can be taken, the task is
+    /// queued, and `ticket` records its place.
+    fn poll<R>(
+        &mut self,
+        cx: &mut Context<'_>,
+        ticket: &mut Option<u32>,
+        next_id: &mut u32,
+        available: usize,
+        take: impl FnOnce() -> Option<R>,
+    ) -> Poll<R> {
+        let mut front = false;
+        if let Some(id) = *ticket {
+            if let Some(waiter) = self.find(id) {
+                // Still waiting for its turn.
+                match &waiter.waker {
+                    Some(waker) if waker.will_wake(cx.waker()) => {}
+                    _ => waiter.waker = Some(cx.waker().clone()),
+                }
+                return Poll::Pending;
+            }
+            // Woken, so it's this task's turn.
+            self.granted -= 1;
+            *ticket = None;
+            front = true;
+            if let Some(result) = take() {
+                return Poll::Ready(result);
+            }
+        } else if self.is_empty() && available > self.granted {
+            if let Some(result) = take() {
+                return Poll::Ready(result);
+            }
+        }
+
+        let waiter = Waiter {
+            id: *next_id,
+            waker: Some(cx.waker().clone()),
+        };
+        // A task that was woken but lost its element keeps its place.
+        let queued = if front {
+            self.waiters.push_front(waiter)
+        } else {
+            self.waiters.push_back(waiter)
+        };
+        if queued.is_ok() {
+            *ticket = Some(*next_id);
+            *next_id = next_id.wrapping_add(1);
+        } else {
+            // Too many waiting tasks; poll again later.
+            cx.waker().wake_by_ref();
+        }
+        Poll::Pending
+    }
+
+    /// Removes the future holding `ticket` from the queue, when it is
+    /// dropped.
+    fn cancel(&mut self, ticket: Option<u32>) {
+        let id = match ticket {
+            Some(id) => id,
+            None => return,
+        };
+        if let Some(waiter) = self.find(id) {
+            waiter.waker = None;
+        } else {
+            // It was woken, but won't take its turn, so pass it on.
+            self.granted -= 1;
+            self.wake_one();
+        }
+    }
+}
+
+/// Registers `cx` to be woken by [`wake_all`].
+pub(crate) fn watch<const W: usize>(watchers: &mut Deque<Waker, W>, cx: &mut Context<'_>) {
+    for i in 0..watchers.len() {
+        if watchers.get(i).map_or(false, |w| w.will_wake(cx.waker())) {
+            return;
+        }
+    }
+    if watchers.push_back(cx.waker().clone()).is_err() {
+        // Too many watching tasks; poll again later.
+        cx.waker().wake_by_ref();
+    }
+}
+
+pub(crate) fn wake_all<const W: usize>(watchers: &mut Deque<Waker, W>) {
+    while let Some(waker) = watchers.pop_front() {
+        waker.wake();
+    }
+}
+
+struct State<T, const N: usize, const W: usize> {
+    deque: Deque<T, N>,
+    pushers: WaitQueue<W>,
+    poppers: WaitQueue<W>,
+    next_id: u32,
+
+    /// Tasks waiting for the deque to not be empty.
+    not_empty: Deque<Waker, W>,
+
+    /// Tasks waiting for the deque to not be full.
+    not_full: Deque<Waker, W>,
+}
+
+impl<T, const N: usize, const W: usize> State<T, N, W> {
+    /// Wakes the tasks that can make progress, after the deque changed.
+    fn notify(&mut self) {
+        let len = self.deque.len();
+        while len > self.poppers.granted && !self.poppers.is_empty() {
+            self.poppers.wake_one();
+        }
+        while N - len > self.pushers.granted && !self.pushers.is_empty() {
+            self.pushers.wake_one();
+        }
+        if len > 0 {
+            wake_all(&mut self.not_empty);
+        }
+        if len < N {
+            wake_all(&mut self.not_full);
+        }
+    }
+}
+
+/// A [`Deque`] shared between async tasks, where pushing waits for space and
+/// popping waits for data.
+///
+/// Waiting tasks are served in the order they started waiting. Up to `W`
+/// tasks can wait on each end; more than that still works, but the extra
+/// tasks poll repeatedly instead of sleeping until it's their turn.
+///
+/// Access to the deque is guarded by a [`critical_section`], so the
+/// non-blocking methods can also be used from interrupt handlers.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() {
+/// use fullhouse::AsyncDeque;
+///
+/// static QUEUE: AsyncDeque<u32, 8> = AsyncDeque::new();
+///
+/// // In the producer task:
+/// QUEUE.push_back(1).await;
+///
+/// // In the consumer task:
+/// let value = QUEUE.pop_front().await;
+/// # }
+/// ```
+pub struct AsyncDeque<T, const N: usize, const W: usize = 4> {
+    state: Mutex<RefCell<State<T, N, W>>>,
+}
+
+impl<T, const N: usize, const W: usize> AsyncDeque<T, N, W> {
+    /// Creates an empty deque.
+    pub const fn new() -> Self {
+        Self {
+            state: Mutex::new(RefCell::new(State {
+                deque: Deque::new(),
+                pushers: WaitQueue::new(),
+                poppers: WaitQueue::new(),
+                next_id: 0,
+                not_empty: Deque::new(),
+                not_full: Deque::new(),
+            })),
+        }
+    }
+
+    fn with_state<R>(&self, f: impl FnOnce(&mut State<T, N, W>) -> R) -> R {
+        critical_section::with(|cs| f(&mut self.state.borrow_ref_mut(cs)))
+    }
+
+    /// The maximum number of elements the deque can hold.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// The number of elements in the deque.
+    pub fn len(&self) -> usize {
+        self.with_state(|state| state.deque.len())
+    }
+
+    /// Returns `true` if the deque contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.with_state(|state| state.deque.is_empty())
+    }
+
+    /// Returns `true` if the deque is at capacity.
+    pub fn is_full(&self) -> bool {
+        self.with_state(|state| state.deque.is_full())
+    }
+
+    /// Appends an element to the deque without waiting.
+    ///
+    /// Returns `Err(value)` if the deque is full, or if the free space is
+    /// reserved for tasks that are waiting to push.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::AsyncDeque;
+    ///
+    /// let queue: AsyncDeque<u8, 1> = AsyncDeque::new();
+    /// assert_eq!(queue.try_push_back(1), Ok(()));
+    /// assert_eq!(queue.try_push_back(2), Err(2));
+    /// ```
+    pub fn try_push_back(&self, value: T) -> Result<(), T> {
+        self.with_state(|state| {
+            let free = N - state.deque.len();
+            if !state.pushers.is_empty() || free <= state.pushers.granted {
+                return Err(value);
+            }
+            state.deque.push_back(value)?;
+            state.notify();
+            Ok(())
+        })
+    }
+
+    /// Removes the first element without waiting.
+    ///
+    /// Returns `None` if the deque is empty, or if its elements are reserved
+    /// for tasks that are waiting to pop.
+    pub fn try_pop_front(&self) -> Option<T> {
+        self.with_state(|state| {
+            let len = state.deque.len();
+            if !state.poppers.is_empty() || len <= state.poppers.granted {
+                return None;
+            }
+            let value = state.deque.pop_front()?;
+            state.notify();
+            Some(value)
+        })
+    }
+
+    /// Calls `f` with exclusive access to the deque, inside a single
+    /// critical section.
+    ///
+    /// This is for operating on many elements at once, e.g. draining the
+    /// deque after [`wait_not_empty`](Self::wait_not_empty). Waiting tasks
+    /// are woken afterwards as needed, but the order in which they wait is
+    /// not respected inside `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::AsyncDeque;
+    ///
+    /// let queue: AsyncDeque<u8, 8> = AsyncDeque::new();
+    /// queue.try_push_back(1).unwrap();
+    /// queue.try_push_back(2).unwrap();
+    ///
+    /// let mut batch = [0; 8];
+    /// let len = queue.with(|d| d.pop_front_slice(&mut batch));
+    /// assert_eq!(&batch[..len], &[1, 2]);
+    /// ```
+    pub fn with<R>(&self, f: impl FnOnce(&mut Deque<T, N>) -> R) -> R {
+        self.with_state(|state| {
+            let result = f(&mut state.deque);
+            state.notify();
+            result
+        })
+    }
+
+    /// Waits until the deque is not empty, without removing anything.
+    ///
+    /// Another task may remove the elements before this task runs, so the
+    /// deque may be empty again by then.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() {
+    /// use fullhouse::AsyncDeque;
+    ///
+    /// static EVENTS: AsyncDeque<u16, 32> = AsyncDeque::new();
+    ///
+    /// loop {
+    ///     EVENTS.wait_not_empty().await;
+    ///     EVENTS.with(|events| {
+    ///         while let Some(event) = events.pop_front() {
+    ///             // Handle the event.
+    ///         }
+    ///     });
+    /// }
+    /// # }
+    /// ```
+    pub fn wait_not_empty(&self) -> WaitNotEmpty<'_, T, N, W> {
+        WaitNotEmpty { deque: self }
+    }
+
+    /// Registers the task in `cx` to be woken the next time an element is
+    /// added, even if the deque already has elements.
+    ///
+    /// Unlike [`wait_not_empty`](Self::wait_not_empty), this also registers
+    /// when every element is reserved for a waiting popper, so a task that
+    /// [`try_pop_front`](Self::try_pop_front) turned away isn't missed.
+    pub(crate) fn register_not_empty(&self, cx: &mut Context<'_>) {
+        self.with_state(|state| watch(&mut state.not_empty, cx));
+    }
+
+    /// Waits until the deque is not full, without adding anything.
+    ///
+    /// Another task may fill the deque before this task runs, so it may be
+    /// full again by then.
+    pub fn wait_not_full(&self) -> WaitNotFull<'_, T, N, W> {
+        WaitNotFull { deque: self }
+    }
+
+    /// Appends an element to the deque, waiting until there is space.
+    pub fn push_back(&self, value: T) -> PushBack<'_, T, N, W> {
+        PushBack {
+            slot: self.push_slot(),
+            value: Some(value),
+        }
+    }
+
+    /// Removes the first element from the deque, waiting until there is one.
+    pub fn pop_front(&self) -> PopFront<'_, T, N, W> {
+        PopFront {
+            slot: self.pop_slot(),
+        }
+    }
+
+    /// Creates a slot for a task to wait for space in, for implementing
+    /// futures by hand.
+    ///
+    /// Most code should use [`push_back`](Self::push_back) instead.
+    pub fn push_slot(&self) -> PushSlot<'_, T, N, W> {
+        PushSlot {
+            deque: self,
+            ticket: None,
+        }
+    }
+
+    /// Creates a slot for a task to wait for elements in, for implementing
+    /// futures by hand.
+    ///
+    /// Most code should use [`pop_front`](Self::pop_front) instead.
+    pub fn pop_slot(&self) -> PopSlot<'_, T, N, W> {
+        PopSlot {
+            deque: self,
+            ticket: None,
+        }
+    }
+}
+
+impl<T, const N: usize, const W: usize> Default for AsyncDeque<T, N, W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A task's place in the queue of tasks waiting to push to an
+/// [`AsyncDeque`].
+///
+/// This is the building block of [`PushBack`], for when the waiting needs to
+/// be composed by hand, e.g. in a custom select loop. Dropping the slot
+/// gives up its place in the queue.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() {
+/// use core::future::poll_fn;
+/// use fullhouse::AsyncDeque;
+///
+/// static QUEUE: AsyncDeque<u8, 4> = AsyncDeque::new();
+///
+/// let mut slot = QUEUE.push_slot();
+/// let mut value = Some(42);
+/// poll_fn(|cx| slot.poll_push(cx, &mut value)).await;
+/// # }
+/// ```
+pub struct PushSlot<'a, T, const N: usize, const W: usize = 4> {
+    deque: &'a AsyncDeque<T, N, W>,
+
+    /// The place in the queue of waiting tasks.
+    ticket: Option<u32>,
+}
+
+impl<T, const N: usize, const W: usize> PushSlot<'_, T, N, W> {
+    /// Attempts to append the element in `value` to the deque, registering
+    /// the task in `cx` to be woken when it is its turn if there is no
+    /// space.
+    ///
+    /// On success, `value` is left as `None`. Between polls, `value` may
+    /// be replaced with another element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is `None`.
+    pub fn poll_push(&mut self, cx: &mut Context<'_>, value: &mut Option<T>) -> Poll<()> {
+        let ticket = &mut self.ticket;
+        self.deque.with_state(|state| {
+            let free = N - state.deque.len();
+            let deque = &mut state.deque;
+            let poll = state
+                .pushers
+                .poll(cx, ticket, &mut state.next_id, free, || {
+                    let v = value.take().expect("no value to push");
+                    match deque.push_back(v) {
+                        Ok(()) => Some(()),
+                        Err(v) => {
+                            *value = Some(v);
+                            None
+                        }
+                    }
+                });
+            if poll.is_ready() {
+                state.notify();
+            }
+            poll
+        })
+    }
+}
+
+impl<T, const N: usize, const W: usize> Drop for PushSlot<'_, T, N, W> {
+    fn drop(&mut self) {
+        if self.ticket.is_some() {
+            let ticket = self.ticket;
+            self.deque.with_state(|state| state.pushers.cancel(ticket));
+        }
+    }
+}
+
+/// A task's place in the queue of tasks waiting to pop from an
+/// [`AsyncDeque`].
+///
+/// This is the building block of [`PopFront`], for when the waiting needs to
+/// be composed by hand. Dropping the slot gives up its place in the queue.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() {
+/// use core::future::poll_fn;
+/// use fullhouse::AsyncDeque;
+///
+/// static QUEUE: AsyncDeque<u8, 4> = AsyncDeque::new();
+///
+/// let mut slot = QUEUE.pop_slot();
+/// let value = poll_fn(|cx| slot.poll_pop(cx)).await;
+/// # }
+/// ```
+pub struct PopSlot<'a, T, const N: usize, const W: usize = 4> {
+    deque: &'a AsyncDeque<T, N, W>,
+
+    /// The place in the queue of waiting tasks.
+    ticket: Option<u32>,
+}
+
+impl<T, const N: usize, const W: usize> PopSlot<'_, T, N, W> {
+    /// Attempts to remove the first element from the deque, registering the
+    /// task in `cx` to be woken when it is its turn if there is none.
+    pub fn poll_pop(&mut self, cx: &mut Context<'_>) -> Poll<T> {
+        let ticket = &mut self.ticket;
+        self.deque.with_state(|state| {
+            let len = state.deque.len();
+            let deque = &mut state.deque;
+            let poll = state
+                .poppers
+                .poll(cx, ticket, &mut state.next_id, len, || deque.pop_front());
+            if poll.is_ready() {
+                state.notify();
+            }
+            poll
+        })
+    }
+}
+
+impl<T, const N: usize, const W: usize> Drop for PopSlot<'_, T, N, W> {
+    fn drop(&mut self) {
+        if self.ticket.is_some() {
+            let ticket = self.ticket;
+            self.deque.with_state(|state| state.poppers.cancel(ticket));
+        }
+    }
+}
+
+/// Future returned by [`AsyncDeque::push_back`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct PushBack<'a, T, const N: usize, const W: usize> {
+    slot: PushSlot<'a, T, N, W>,
+
+    /// `None` once it has been pushed.
+    value: Option<T>,
+}
+
+// The value is never pinned.
+impl<T, const N: usize, const W: usize> Unpin for PushBack<'_, T, N, W> {}
+
+impl<T, const N: usize, const W: usize> Future for PushBack<'_, T, N, W> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        this.slot.poll_push(cx, &mut this.value)
+    }
+}
+
+/// Future returned by [`AsyncDeque::pop_front`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct PopFront<'a, T, const N: usize, const W: usize> {
+    slot: PopSlot<'a, T, N, W>,
+}
+
+impl<T, const N: usize, const W: usize> Future for PopFront<'_, T, N, W> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.get_mut().slot.poll_pop(cx)
+    }
+}
+
+/// Future returned by [`AsyncDeque::wait_not_empty`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct WaitNotEmpty<'a, T, const N: usize, const W: usize> {
+    deque: &'a AsyncDeque<T, N, W>,
+}
+
+impl<T, const N: usize, const W: usize> Future for WaitNotEmpty<'_, T, N, W> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.deque.with_state(|state| {
+            if state.deque.is_empty() {
+                watch(&mut state.not_empty, cx);
+                Poll::Pending
+            } else {
+                Poll::Ready(())
+            }
+        })
+    }
+}
+
+/// Future returned by [`AsyncDeque::wait_not_full`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct WaitNotFull<'a, T, const N: usize, const W: usize> {
+    deque: &'a AsyncDeque<T, N, W>,
+}
+
+impl<T, const N: usize, const W: usize> Future for WaitNotFull<'_, T, N, W> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.deque.with_state(|state| {
+            if state.deque.is_full() {
+                watch(&mut state.not_full, cx);
+                Poll::Pending
+            } else {
+                Poll::Ready(())
+            }
+        })
+    }
+}
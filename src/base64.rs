@@ -0,0 +1,117 @@
+//! Streaming base64 encoding into byte deques.
+//!
+//! Base64 encodes groups of 3 bytes as 4 characters, so data that arrives in
+//! arbitrary chunks can't be encoded one chunk at a time. An [`Encoder`]
+//! holds on to the bytes of an incomplete group until the next chunk, and
+//! adds the padding when the data is [finished](Encoder::finish).
+
+use crate::{DequeBase, Storage};
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+const PAD: u8 = b'=';
+
+/// The destination does not have room for the encoded output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Full;
+
+/// Encodes a stream of bytes as base64 (with the standard alphabet and
+/// padding) into byte deques.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::{base64::Encoder, Deque};
+///
+/// let mut console: Deque<u8, 16> = Deque::new();
+/// let mut encoder = Encoder::new();
+/// encoder.encode(b"he", &mut console);
+/// encoder.encode(b"llo", &mut console);
+/// encoder.finish(&mut console).unwrap();
+///
+/// let mut out = [0; 16];
+/// let len = console.pop_front_slice(&mut out);
+/// assert_eq!(&out[..len], b"aGVsbG8=");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Encoder {
+    /// The bytes of the current, incomplete group.
+    pending: [u8; 2],
+    pending_len: usize,
+}
+
+impl Encoder {
+    /// Creates an encoder at the start of a stream.
+    pub const fn new() -> Self {
+        Self {
+            pending: [0; 2],
+            pending_len: 0,
+        }
+    }
+
+    /// Encodes `data`, appending the output to `dst`, and returns the number
+    /// of bytes of `data` that were consumed.
+    ///
+    /// The last bytes may be held by the encoder until the next call
+    /// completes their group. If `dst` fills up, fewer bytes are consumed;
+    /// pass the rest again once it has room.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::{base64::Encoder, Deque};
+    ///
+    /// let mut console: Deque<u8, 4> = Deque::new();
+    /// let mut encoder = Encoder::new();
+    /// assert_eq!(encoder.encode(b"abcdef", &mut console), 5);
+    /// assert_eq!(console.as_slices().0, b"YWJj");
+    /// ```
+    pub fn encode<S: Storage<u8>>(&mut self, data: &[u8], dst: &mut DequeBase<u8, S>) -> usize {
+        for (count, &byte) in data.iter().enumerate() {
+            if self.pending_len < 2 {
+                self.pending[self.pending_len] = byte;
+                self.pending_len += 1;
+                continue;
+            }
+            if dst.capacity() - dst.len() < 4 {
+                return count;
+            }
+            let [a, b] = self.pending;
+            push_group(dst, [a, b, byte], 4);
+            self.pending_len = 0;
+        }
+        data.len()
+    }
+
+    /// Encodes the remaining bytes, with padding, and resets the encoder
+    /// for a new stream.
+    ///
+    /// Returns an error if `dst` does not have room for the output, in which
+    /// case nothing is written and the encoder is unchanged.
+    pub fn finish<S: Storage<u8>>(&mut self, dst: &mut DequeBase<u8, S>) -> Result<(), Full> {
+        if self.pending_len == 0 {
+            return Ok(());
+        }
+        if dst.capacity() - dst.len() < 4 {
+            return Err(Full);
+        }
+        let [a, b] = self.pending;
+        let b = if self.pending_len == 2 { b } else { 0 };
+        push_group(dst, [a, b, 0], self.pending_len + 1);
+        for _ in self.pending_len + 1..4 {
+            let _ = dst.push_back(PAD);
+        }
+        self.pending_len = 0;
+        Ok(())
+    }
+}
+
+/// Appends the first `len` characters encoding `group`. The caller checks
+/// that there is room.
+fn push_group<S: Storage<u8>>(dst: &mut DequeBase<u8, S>, group: [u8; 3], len: usize) {
+    let bits = u32::from(group[0]) << 16 | u32::from(group[1]) << 8 | u32::from(group[2]);
+    for i in 0..len {
+        let index = (bits >> (18 - 6 * i)) & 0x3f;
+        let _ = dst.push_back(ALPHABET[index as usize]);
+    }
+}
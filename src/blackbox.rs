@@ -0,0 +1,235 @@
+//! Capturing panic messages in RAM that survives a reset.
+
+use core::{fmt, mem::MaybeUninit, panic::PanicInfo};
+
+use crate::persistent::{AttachState, PersistentDeque};
+
+/// A log of the most recent panic message, kept in a [`PersistentDeque`] so it
+/// can be read back after the device reboots.
+///
+/// Most applications will want to use the [`panic_blackbox!`] macro, which
+/// places a `PanicLog` in a non-initialized section and installs a panic
+/// handler that records to it. After the reset, the same static is attached
+/// again to read out the message.
+///
+/// Messages longer than `N` bytes are truncated.
+///
+/// [`panic_blackbox!`]: crate::panic_blackbox
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::blackbox::PanicLog;
+///
+/// let mut log: PanicLog<64> = PanicLog::new();
+/// log.record_args(format_args!("panicked at {}:{}", "main.rs", 42));
+///
+/// let mut buf = [0; 64];
+/// let len = log.read(&mut buf);
+/// assert_eq!(&buf[..len], b"panicked at main.rs:42");
+/// ```
+#[repr(transparent)]
+pub struct PanicLog<const N: usize> {
+    bytes: PersistentDeque<u8, N>,
+}
+
+impl<const N: usize> PanicLog<N> {
+    /// Creates an empty log.
+    ///
+    /// A log that should survive a reset must be obtained with
+    /// [`attach`](Self::attach) instead.
+    pub const fn new() -> Self {
+        Self {
+            bytes: PersistentDeque::new(),
+        }
+    }
+
+    /// Attaches to a log in memory that may have been left by a previous
+    /// boot.
+    ///
+    /// See [`PersistentDeque::attach`] for how the memory is validated.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`PersistentDeque::attach`].
+    pub unsafe fn attach(slot: &mut MaybeUninit<Self>) -> (&mut Self, AttachState) {
+        // Safety: `PanicLog` is a transparent wrapper around the deque, and
+        // the caller upholds the requirements of `PersistentDeque::attach`.
+        let slot =
+            &mut *(slot as *mut MaybeUninit<Self>).cast::<MaybeUninit<PersistentDeque<u8, N>>>();
+        let (bytes, state) = PersistentDeque::attach(slot);
+        (
+            &mut *(bytes as *mut PersistentDeque<u8, N>).cast::<Self>(),
+            state,
+        )
+    }
+
+    /// Attaches to a log that may have been left by a previous boot, and
+    /// moves its message into `buf`, returning the number of bytes written.
+    ///
+    /// Unlike [`attach`](Self::attach), this doesn't return a reference to
+    /// the log, so it is the way to read a log that a panic handler (like
+    /// the one installed by [`panic_blackbox!`](crate::panic_blackbox))
+    /// writes to: a reference held while code that can panic runs would
+    /// alias the handler's. If `buf` is shorter than the message, the rest
+    /// is discarded.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`PersistentDeque::attach`], and `slot` must be valid for
+    /// reads and writes, with nothing else accessing the log during the
+    /// call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::mem::MaybeUninit;
+    /// use fullhouse::{blackbox::PanicLog, persistent::AttachState};
+    ///
+    /// let mut slot = MaybeUninit::new(PanicLog::<32>::new());
+    /// // Safety: `slot` is initialized and not otherwise in use.
+    /// let (log, _) = unsafe { PanicLog::attach(&mut slot) };
+    /// log.record_args(format_args!("stack overflow"));
+    ///
+    /// let mut buf = [0; 32];
+    /// let (len, state) = unsafe { PanicLog::take(&mut slot, &mut buf) };
+    /// assert_eq!((&buf[..len], state), (&b"stack overflow"[..], AttachState::Restored));
+    /// ```
+    pub unsafe fn take(slot: *mut MaybeUninit<Self>, buf: &mut [u8]) -> (usize, AttachState) {
+        // Safety: The caller guarantees exclusive access for the duration of
+        // the call, and the reference doesn't outlive it.
+        let (log, state) = Self::attach(&mut *slot);
+        let len = log.read(buf);
+        log.clear();
+        (len, state)
+    }
+
+    /// Returns `true` if no message is stored.
+    pub const fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// The length of the stored message in bytes.
+    pub const fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Replaces the stored message with the one from a panic.
+    ///
+    /// The message has the same format as the standard library's panic
+    /// message, including the location of the panic.
+    pub fn record(&mut self, info: &PanicInfo<'_>) {
+        self.record_args(format_args!("{}", info));
+    }
+
+    /// Replaces the stored message with the given formatted message.
+    ///
+    /// This can also be used to record other fatal conditions, such as hard
+    /// faults.
+    pub fn record_args(&mut self, args: fmt::Arguments<'_>) {
+        self.bytes.clear();
+        // Truncation is not an error, so the result is always `Ok`.
+        let _ = fmt::Write::write_fmt(&mut Truncating(&mut self.bytes), args);
+    }
+
+    /// Moves the stored message into `buf`, returning the number of bytes
+    /// written.
+    ///
+    /// If `buf` is shorter than the message, the rest of the message is kept
+    /// for the next call.
+    pub fn read(&mut self, buf: &mut [u8]) -> usize {
+        for (count, slot) in buf.iter_mut().enumerate() {
+            match self.bytes.pop_front() {
+                Some(byte) => *slot = byte,
+                None => return count,
+            }
+        }
+        buf.len()
+    }
+
+    /// Discards the stored message.
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+    }
+}
+
+impl<const N: usize> Default for PanicLog<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writes as much of the formatted text as fits, and silently drops the
+/// rest.
+struct Truncating<'a, const N: usize>(&'a mut PersistentDeque<u8, N>);
+
+impl<const N: usize> fmt::Write for Truncating<'_, N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for &byte in s.as_bytes() {
+            if self.0.push_back(byte).is_err() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Installs a panic handler that records the panic message in a
+/// [`PanicLog`] kept in a non-initialized memory section.
+///
+/// `panic_blackbox!(NAME: N, ".section", after)` declares a static named
+/// `NAME` holding a `PanicLog<N>` in the given section, and a
+/// `#[panic_handler]` that records the message and then calls `after`, a
+/// `fn() -> !` that usually resets the device.
+///
+/// After the reset, read out the message with [`PanicLog::take`]. The
+/// section must not be initialized by the startup code (e.g. a `NOLOAD`
+/// section in the linker script).
+///
+/// The handler attaches to the log when it runs, so the rest of the program
+/// must not hold a reference to it (e.g. one returned by
+/// [`PanicLog::attach`]) across any code that can panic.
+///
+/// # Examples
+///
+/// ```ignore
+/// use core::ptr::addr_of_mut;
+/// use fullhouse::{blackbox::PanicLog, panic_blackbox};
+///
+/// panic_blackbox!(PANIC_LOG: 256, ".uninit.panic", cortex_m::peripheral::SCB::sys_reset);
+///
+/// #[entry]
+/// fn main() -> ! {
+///     let mut buf = [0; 256];
+///     // Safety: The panic handler is the only other user of `PANIC_LOG`,
+///     // and it has not run yet in this boot.
+///     let (len, _) = unsafe { PanicLog::take(addr_of_mut!(PANIC_LOG), &mut buf) };
+///     if len > 0 {
+///         report_previous_panic(&buf[..len]);
+///     }
+///     // ...
+/// }
+/// ```
+#[macro_export]
+macro_rules! panic_blackbox {
+    ($name:ident: $capacity:expr, $section:literal, $after:expr) => {
+        #[link_section = $section]
+        static mut $name: ::core::mem::MaybeUninit<$crate::blackbox::PanicLog<$capacity>> =
+            ::core::mem::MaybeUninit::uninit();
+
+        #[panic_handler]
+        fn panic(info: &::core::panic::PanicInfo<'_>) -> ! {
+            // Safety: The panic handler does not return, so no other code
+            // will access the log while this reference exists (a nested panic
+            // would be a bug in the formatting machinery), and the rest of the
+            // program doesn't hold a reference to it while it can panic.
+            let (log, _) = unsafe {
+                $crate::blackbox::PanicLog::attach(&mut *::core::ptr::addr_of_mut!($name))
+            };
+            log.record(info);
+
+            let after: fn() -> ! = $after;
+            after()
+        }
+    };
+}
@@ -0,0 +1,108 @@
+//! Heap-allocated deques.
+
+use alloc::{
+    alloc::{alloc, handle_alloc_error, Layout},
+    boxed::Box,
+};
+use core::mem::MaybeUninit;
+
+use crate::{Deque, DequeBase, Storage};
+
+/// A fixed-capacity deque whose buffer is allocated on the heap.
+///
+/// The capacity is chosen at runtime, but like [`Deque`](crate::Deque), it
+/// never changes afterwards: pushing to a full `BoxDeque` fails instead of
+/// reallocating. This makes it a drop-in stand-in for `Deque` in host-side
+/// code that does not want to carry the capacity as a const generic.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::BoxDeque;
+///
+/// let mut deque: BoxDeque<u32> = BoxDeque::with_capacity(2);
+/// deque.push_back(1).unwrap();
+/// deque.push_back(2).unwrap();
+/// assert_eq!(deque.push_back(3), Err(3));
+/// ```
+pub type BoxDeque<T> = DequeBase<T, Box<[MaybeUninit<T>]>>;
+
+impl<T> BoxDeque<T> {
+    /// Creates an empty deque that can hold `capacity` elements.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let storage = core::iter::repeat_with(MaybeUninit::uninit)
+            .take(capacity)
+            .collect();
+        Self::from_storage(storage)
+    }
+}
+
+impl<T: Clone> Clone for BoxDeque<T> {
+    fn clone(&self) -> Self {
+        let mut deque = Self::with_capacity(self.capacity());
+        deque.clone_from_deque(self);
+        deque
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        if self.capacity() == source.capacity() {
+            self.clone_from_deque(source);
+        } else {
+            *self = source.clone();
+        }
+    }
+}
+
+impl<T, const CAPACITY: usize> Deque<T, CAPACITY> {
+    /// Creates an empty deque directly on the heap.
+    ///
+    /// `Box::new(Deque::new())` may construct the whole deque on the stack
+    /// before moving it into the allocation, which can overflow small stacks
+    /// when the capacity is large. This allocates first and initializes the
+    /// deque in place, so the storage array is never materialized on the
+    /// stack.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut deque = Deque::<[u8; 1024], 64>::new_boxed();
+    /// deque.push_back([0; 1024]).unwrap();
+    /// assert_eq!(deque.len(), 1);
+    /// ```
+    pub fn new_boxed() -> Box<Self> {
+        let layout = Layout::new::<Self>();
+
+        // Safety: The layout has a non-zero size, because the deque always
+        // contains its index fields.
+        let ptr = unsafe { alloc(layout) } as *mut Self;
+        if ptr.is_null() {
+            handle_alloc_error(layout);
+        }
+
+        // Safety:
+        // - `ptr` is valid for writes and aligned for `Self`, since it was
+        //   just allocated with `Self`'s layout.
+        // - `init_in_place` initializes all fields that need it, so the value
+        //   is valid for `Box` to take ownership of.
+        // - The allocation was made by the global allocator with the layout
+        //   of `Self`, as `Box::from_raw` requires.
+        unsafe {
+            Self::init_in_place(ptr);
+            Box::from_raw(ptr)
+        }
+    }
+}
+
+// Safety: The box always points to the same allocation, which is moved along
+// with it, and boxed slices cannot be resized.
+unsafe impl<T> Storage<T> for Box<[MaybeUninit<T>]> {
+    fn as_slice(&self) -> &[MaybeUninit<T>] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [MaybeUninit<T>] {
+        self
+    }
+}
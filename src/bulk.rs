@@ -0,0 +1,589 @@
+//! Operations on many elements at once.
+//!
+//! These mirror the slice-based API of smoltcp's `RingBuffer`
+//! (`enqueue_slice`, `dequeue_slice`, `read_allocated`, `dequeue_many_with`,
+//! and `enqueue_many_with` via spare capacity), so that code written against
+//! socket buffers can use a deque instead.
+
+use core::{mem::MaybeUninit, ptr, slice};
+
+use crate::{Deque, DequeBase, Storage};
+
+impl<T: Copy, S: Storage<T>> DequeBase<T, S> {
+    /// Appends as many elements from `data` as fit, returning how many were
+    /// appended.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<u8, 4> = Deque::new();
+    /// assert_eq!(d.push_back_slice(b"abc"), 3);
+    /// assert_eq!(d.push_back_slice(b"def"), 1);
+    /// assert_eq!(d.as_slices().0, b"abcd");
+    /// ```
+    pub fn push_back_slice(&mut self, data: &[T]) -> usize {
+        let count = data.len().min(self.capacity() - self.len);
+        // Safety: `data` holds at least `count` values, which are `Copy`, and
+        // can't overlap the storage, which is borrowed mutably.
+        unsafe { self.copy_to_back(data.as_ptr(), count) };
+        count
+    }
+
+    /// Removes elements from the front of the deque into `buf`, returning
+    /// how many were removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<u8, 4> = Deque::new();
+    /// d.push_back_slice(b"abc");
+    /// let mut buf = [0; 2];
+    /// assert_eq!(d.pop_front_slice(&mut buf), 2);
+    /// assert_eq!(&buf, b"ab");
+    /// assert_eq!(d.len(), 1);
+    /// ```
+    pub fn pop_front_slice(&mut self, buf: &mut [T]) -> usize {
+        let count = self.read_at(0, buf);
+        // `T: Copy`, so the copied elements don't need to be dropped.
+        if count > 0 {
+            let (first, second) = self.front_ranges(count);
+            self.start = self.wrap_add(self.start, count);
+            self.len -= count;
+            self.check_invariants();
+            self.scrub(first);
+            self.scrub(second);
+        }
+        count
+    }
+
+    /// Copies elements starting at index `offset` into `buf` without removing
+    /// them, returning how many were copied.
+    ///
+    /// This is how a TCP transmit buffer re-reads unacknowledged data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<u8, 8> = Deque::new();
+    /// d.push_back_slice(b"abcdef");
+    /// let mut buf = [0; 8];
+    /// assert_eq!(d.read_at(4, &mut buf), 2);
+    /// assert_eq!(&buf[..2], b"ef");
+    /// assert_eq!(d.len(), 6);
+    ///
+    /// // Reading at or past the end copies nothing.
+    /// assert_eq!(d.read_at(6, &mut buf), 0);
+    /// assert_eq!(d.read_at(100, &mut buf), 0);
+    /// ```
+    pub fn read_at(&self, offset: usize, buf: &mut [T]) -> usize {
+        let count = buf.len().min(self.len.saturating_sub(offset));
+        // Safety: `buf` has room for `count` elements, and can't overlap the
+        // storage, which is borrowed.
+        unsafe { self.copy_out(offset, buf.as_mut_ptr(), count) };
+        count
+    }
+
+    /// Copies the first `K` elements into an array, or returns `None` if
+    /// there are fewer than `K`.
+    ///
+    /// Unlike [`first_chunk`](Self::first_chunk), this works even if the
+    /// elements wrap around the end of the storage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut rx: Deque<u8, 8> = Deque::new();
+    /// rx.push_back_slice(&[0x02, 0x10, 0x00, 0xff]);
+    ///
+    /// // A fixed-size frame header:
+    /// let [kind, len_lo, len_hi] = rx.first_n::<3>().unwrap();
+    /// assert_eq!((kind, u16::from_le_bytes([len_lo, len_hi])), (2, 16));
+    /// assert_eq!(rx.first_n::<5>(), None);
+    /// ```
+    pub fn first_n<const K: usize>(&self) -> Option<[T; K]> {
+        if self.len < K {
+            return None;
+        }
+        let mut array = MaybeUninit::<[T; K]>::uninit();
+        // Safety: There are at least `K` elements to copy into the array,
+        // which initializes all of it.
+        unsafe {
+            self.copy_out(0, array.as_mut_ptr().cast(), K);
+            Some(array.assume_init())
+        }
+    }
+
+    /// Copies the last `K` elements into an array, or returns `None` if
+    /// there are fewer than `K`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<u8, 4> = Deque::new();
+    /// d.push_back_slice(b"abc");
+    /// assert_eq!(d.last_n::<2>(), Some(*b"bc"));
+    /// ```
+    pub fn last_n<const K: usize>(&self) -> Option<[T; K]> {
+        let offset = self.len.checked_sub(K)?;
+        let mut array = MaybeUninit::<[T; K]>::uninit();
+        // Safety: There are exactly `K` elements after `offset` to copy into
+        // the array, which initializes all of it.
+        unsafe {
+            self.copy_out(offset, array.as_mut_ptr().cast(), K);
+            Some(array.assume_init())
+        }
+    }
+
+    /// Replaces the contents of the deque with a copy of `source`'s.
+    ///
+    /// The elements are copied a contiguous region at a time, and the old
+    /// elements don't need to be dropped, so this is much faster than
+    /// cloning one element at a time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `source` has more elements than the deque can hold.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut a: Deque<u8, 8> = Deque::new();
+    /// a.push_back_slice(b"abc");
+    ///
+    /// let mut b: Deque<u8, 4> = Deque::new();
+    /// b.push_back_slice(b"xyz");
+    /// b.copy_from(&a);
+    /// assert_eq!(b.as_slices().0, b"abc");
+    /// ```
+    pub fn copy_from<S2: Storage<T>>(&mut self, source: &DequeBase<T, S2>) {
+        assert!(
+            source.len() <= self.capacity(),
+            "source does not fit in the deque"
+        );
+        // `T: Copy`, so the old elements don't need to be dropped.
+        let (first, second) = self.ranges();
+        self.scrub(first);
+        self.scrub(second);
+        self.start = 0;
+        self.end = 0;
+        self.len = 0;
+        let (first, second) = source.as_slices();
+        self.push_back_slice(first);
+        self.push_back_slice(second);
+    }
+}
+
+impl<S: Storage<u8>> DequeBase<u8, S> {
+    /// Copies the first `K` bytes into an array without removing them, or
+    /// returns `None` if fewer than `K` bytes have arrived.
+    ///
+    /// This is [`first_n`](Self::first_n) for byte streams, for parsers that
+    /// need to look at a fixed-size header before deciding what to do.
+    pub fn peek_exact<const K: usize>(&self) -> Option<[u8; K]> {
+        self.first_n()
+    }
+
+    /// Removes the first `K` bytes and returns them as an array, or returns
+    /// `None` and removes nothing if fewer than `K` bytes have arrived.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut rx: Deque<u8, 16> = Deque::new();
+    /// rx.push_back_slice(&[0x01, 0x00, 0x2a]);
+    ///
+    /// // A record is a 2-byte tag followed by a 4-byte value.
+    /// assert_eq!(rx.peek_exact::<2>(), Some([0x01, 0x00]));
+    /// assert_eq!(rx.take_exact::<6>(), None);
+    /// assert_eq!(rx.len(), 3);
+    ///
+    /// rx.push_back_slice(&[0x00, 0x00, 0x00]);
+    /// let record = rx.take_exact::<6>().unwrap();
+    /// assert_eq!(u32::from_le_bytes([record[2], record[3], record[4], record[5]]), 42);
+    /// assert!(rx.is_empty());
+    /// ```
+    pub fn take_exact<const K: usize>(&mut self) -> Option<[u8; K]> {
+        let bytes = self.first_n()?;
+        self.advance_front(K);
+        Some(bytes)
+    }
+}
+
+impl<T: Clone, S: Storage<T>> DequeBase<T, S> {
+    /// Makes the contents of the deque equal to `source`'s, reusing the
+    /// existing elements with [`Clone::clone_from`] where possible.
+    ///
+    /// `source` must fit in the deque.
+    pub(crate) fn clone_from_deque<S2: Storage<T>>(&mut self, source: &DequeBase<T, S2>) {
+        debug_assert!(source.len() <= self.capacity());
+        while self.len() > source.len() {
+            self.pop_back();
+        }
+        let common = self.len();
+        for i in 0..common {
+            self[i].clone_from(&source[i]);
+        }
+        for i in common..source.len() {
+            // There is room, checked by the caller.
+            let _ = self.push_back(source[i].clone());
+        }
+    }
+}
+
+impl<T, S: Storage<T>> DequeBase<T, S> {
+    /// Appends `count` values by copying them from `data`.
+    ///
+    /// # Safety
+    ///
+    /// `data` must be valid for reads of `count` values, which must not
+    /// overlap the storage, and there must be room for them. The deque takes
+    /// ownership of the values, so unless `T: Copy`, the caller must not use
+    /// or drop them afterwards.
+    unsafe fn copy_to_back(&mut self, data: *const T, count: usize) {
+        let (first, second) = self.free_ranges();
+        let split = count.min(first.len());
+        let base = self.as_mut_ptr();
+        // Safety: The first `split` values go at the start of the first free
+        // range, and the rest (if any) at the start of the second, so all of
+        // them are written in bounds to free slots.
+        ptr::copy_nonoverlapping(data, base.add(first.start), split);
+        ptr::copy_nonoverlapping(data.add(split), base.add(second.start), count - split);
+        if count > 0 {
+            self.end = self.wrap_add(self.end, count);
+            self.len += count;
+            self.check_invariants();
+        }
+    }
+
+    /// Returns a reference to the first `K` elements as an array, or `None`
+    /// if there are fewer than `K` or they wrap around the end of the
+    /// storage.
+    ///
+    /// Use [`first_n`](Self::first_n) to copy them regardless, or
+    /// [`make_contiguous`](Self::make_contiguous) first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<u16, 4> = Deque::new();
+    /// d.push_back_slice(&[1, 2, 3]);
+    /// assert_eq!(d.first_chunk::<2>(), Some(&[1, 2]));
+    /// assert_eq!(d.first_chunk::<4>(), None);
+    /// ```
+    pub fn first_chunk<const K: usize>(&self) -> Option<&[T; K]> {
+        self.as_slices().0.get(..K)?.try_into().ok()
+    }
+
+    /// Returns a reference to the last `K` elements as an array, or `None`
+    /// if there are fewer than `K` or they wrap around the end of the
+    /// storage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<u16, 4> = Deque::new();
+    /// d.push_back_slice(&[1, 2, 3]);
+    /// assert_eq!(d.last_chunk::<2>(), Some(&[2, 3]));
+    /// ```
+    pub fn last_chunk<const K: usize>(&self) -> Option<&[T; K]> {
+        let (front, back) = self.as_slices();
+        let tail = if back.is_empty() { front } else { back };
+        tail.get(tail.len().checked_sub(K)?..)?.try_into().ok()
+    }
+
+    /// Copies `count` elements starting at index `offset` to `dst`.
+    ///
+    /// # Safety
+    ///
+    /// Unless `count` is zero, there must be at least `offset + count`
+    /// elements. `dst` must be valid for writes of `count` elements and not
+    /// overlap the storage. Unless `T: Copy`, the copies must be forgotten or
+    /// the originals removed without being dropped.
+    pub(crate) unsafe fn copy_out(&self, offset: usize, dst: *mut T, count: usize) {
+        if count == 0 {
+            // `offset` may be past the end, where the pointer arithmetic below
+            // would leave the storage.
+            return;
+        }
+        let (first, second) = self.ranges();
+        // Skip `offset` elements, first from the first range, then the
+        // second.
+        let first_start = first.start + offset.min(first.len());
+        let second_start = second.start + offset.saturating_sub(first.len());
+        let split = count.min(first.end - first_start);
+        let base = self.as_ptr();
+        // Safety: The first `split` elements come from the first range after
+        // skipping, and the rest from the second range, so all of them are
+        // valid elements.
+        ptr::copy_nonoverlapping(base.add(first_start), dst, split);
+        ptr::copy_nonoverlapping(base.add(second_start), dst.add(split), count - split);
+    }
+
+    /// Moves all the elements to the back of `dst`, which must have room for
+    /// them.
+    pub(crate) fn move_all_to<S2: Storage<T>>(&mut self, dst: &mut DequeBase<T, S2>) {
+        assert!(
+            self.len <= dst.capacity() - dst.len,
+            "elements do not fit in the destination"
+        );
+        let (first, second) = self.ranges();
+        let base = self.as_ptr();
+        // Safety: The ranges hold the valid values, which fit in `dst` and are
+        // forgotten below, so each is only owned by `dst`.
+        unsafe {
+            dst.copy_to_back(base.add(first.start), first.len());
+            dst.copy_to_back(base.add(second.start), second.len());
+        }
+        self.forget_all();
+    }
+
+    /// Removes all the elements without dropping them, once they have been
+    /// moved out.
+    pub(crate) fn forget_all(&mut self) {
+        let (first, second) = self.ranges();
+        self.start = 0;
+        self.end = 0;
+        self.len = 0;
+        self.scrub(first);
+        self.scrub(second);
+    }
+
+    /// Calls `f` with the first contiguous slice of elements, and removes as
+    /// many elements from the front as it returns.
+    ///
+    /// The slice may be shorter than [`len`](Self::len) when the contents
+    /// wrap around the end of the storage; call again to get the rest. The
+    /// count returned by `f` is clamped to the length of the slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<u8, 8> = Deque::new();
+    /// d.push_back_slice(b"hello");
+    ///
+    /// let (n, first) = d.pop_front_with(|data| (2, data[0]));
+    /// assert_eq!((n, first), (2, b'h'));
+    /// assert_eq!(d.as_slices().0, b"llo");
+    /// ```
+    pub fn pop_front_with<R>(&mut self, f: impl FnOnce(&mut [T]) -> (usize, R)) -> (usize, R) {
+        let front = self.as_mut_slices().0;
+        let available = front.len();
+        let (count, result) = f(front);
+        let count = count.min(available);
+        self.advance_front(count);
+        (count, result)
+    }
+
+    /// Calls `f` with the contiguous slices of elements in order, and removes
+    /// as many elements from the front as it reports consumed.
+    ///
+    /// `f` is called with the first slice, and, if it consumed all of it and
+    /// the contents wrap around the end of the storage, again with the
+    /// second. Counts larger than the slice are clamped to its length.
+    /// Returns the total number of elements removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut rx: Deque<u8, 8> = Deque::new();
+    /// rx.push_back_slice(&[0; 6]);
+    /// rx.advance_front(6);
+    /// rx.push_back_slice(b"abcdef");
+    ///
+    /// // The contents wrap, so this takes two calls.
+    /// let mut sent = Vec::new();
+    /// let consumed = rx.consume(|data| {
+    ///     sent.extend_from_slice(data);
+    ///     data.len()
+    /// });
+    /// assert_eq!(consumed, 6);
+    /// assert_eq!(sent, b"abcdef");
+    /// assert!(rx.is_empty());
+    /// ```
+    pub fn consume(&mut self, mut f: impl FnMut(&[T]) -> usize) -> usize {
+        let (front, back) = self.as_slices();
+        let mut count = f(front).min(front.len());
+        if count == front.len() && !back.is_empty() {
+            count += f(back).min(back.len());
+        }
+        self.advance_front(count);
+        count
+    }
+
+    /// Returns the first contiguous region of free space after the back of
+    /// the deque.
+    ///
+    /// Write elements into it (e.g. with a DMA transfer), then make them part
+    /// of the deque with [`advance_back`](Self::advance_back). The region may
+    /// be shorter than the free space when it wraps around the end of the
+    /// storage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<u8, 8> = Deque::new();
+    /// let spare = d.spare_capacity_mut();
+    /// spare[0].write(1);
+    /// spare[1].write(2);
+    ///
+    /// // Safety: The first two elements were just initialized.
+    /// unsafe { d.advance_back(2) };
+    /// assert_eq!(d.as_slices().0, &[1, 2]);
+    /// ```
+    pub fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        if self.is_empty() {
+            // Offer the whole storage.
+            self.start = 0;
+            self.end = 0;
+        }
+        let (free, _) = self.free_ranges();
+        // Safety: `free` is a range of slots in the storage, which may hold
+        // anything as `MaybeUninit`s.
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr().add(free.start).cast(), free.len()) }
+    }
+
+    /// Adds `count` elements that were written into
+    /// [`spare_capacity_mut`](Self::spare_capacity_mut) or
+    /// [`free_segments`](Self::free_segments) to the back of the deque.
+    ///
+    /// # Safety
+    ///
+    /// The first `count` elements of the slice returned by
+    /// `spare_capacity_mut`, or of the segments returned by `free_segments`
+    /// (in order), must have been initialized, and `count` must not exceed
+    /// their total length.
+    pub unsafe fn advance_back(&mut self, count: usize) {
+        if count > 0 {
+            self.end = self.wrap_add(self.end, count);
+            self.len += count;
+            self.check_invariants();
+        }
+    }
+}
+
+/// Fails to compile if deques of capacities `A` and `B` don't fit in one of
+/// capacity `M`.
+struct AssertFits<const A: usize, const B: usize, const M: usize>;
+
+impl<const A: usize, const B: usize, const M: usize> AssertFits<A, B, M> {
+    const OK: () = assert!(A + B <= M, "the combined capacity does not fit");
+}
+
+impl<T, const N: usize> Deque<T, N> {
+    /// Moves the elements of this deque and then `other` into a new deque.
+    ///
+    /// The new capacity `M` must be at least `N + N2`, which is checked at
+    /// compile time. See [`try_concat`](Self::try_concat) for a smaller
+    /// capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut uart: Deque<u8, 4> = Deque::new();
+    /// uart.push_back_slice(b"ab");
+    /// let mut spi: Deque<u8, 8> = Deque::new();
+    /// spi.push_back_slice(b"cd");
+    ///
+    /// let merged: Deque<u8, 12> = uart.concat(spi);
+    /// assert_eq!(merged.as_slices().0, b"abcd");
+    /// ```
+    ///
+    /// A capacity that could be too small is rejected:
+    ///
+    /// ```compile_fail
+    /// use fullhouse::Deque;
+    ///
+    /// let a: Deque<u8, 4> = Deque::new();
+    /// let b: Deque<u8, 8> = Deque::new();
+    /// let merged: Deque<u8, 10> = a.concat(b);
+    /// ```
+    pub fn concat<const N2: usize, const M: usize>(self, other: Deque<T, N2>) -> Deque<T, M> {
+        let () = AssertFits::<N, N2, M>::OK;
+        match self.try_concat(other) {
+            Ok(merged) => merged,
+            Err(_) => unreachable!(),
+        }
+    }
+
+    /// Moves the elements into a deque of a different capacity, or returns
+    /// this deque unchanged if they don't fit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<u32, 16> = Deque::new();
+    /// d.push_back(1).unwrap();
+    /// d.push_back(2).unwrap();
+    ///
+    /// let small: Deque<u32, 2> = d.try_migrate().ok().unwrap();
+    /// assert!(small.iter().eq(&[1, 2]));
+    ///
+    /// let too_small = small.try_migrate::<1>();
+    /// assert_eq!(too_small.err().unwrap().len(), 2);
+    /// ```
+    pub fn try_migrate<const M: usize>(mut self) -> Result<Deque<T, M>, Self> {
+        if self.len() > M {
+            return Err(self);
+        }
+        let mut migrated = Deque::new();
+        self.move_all_to(&mut migrated);
+        Ok(migrated)
+    }
+
+    /// Moves the elements of this deque and then `other` into a new deque,
+    /// or returns both unchanged if they don't fit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut a: Deque<u8, 8> = Deque::new();
+    /// a.push_back_slice(b"abc");
+    /// let mut b: Deque<u8, 8> = Deque::new();
+    /// b.push_back_slice(b"de");
+    ///
+    /// let merged: Deque<u8, 5> = a.try_concat(b).ok().unwrap();
+    /// assert_eq!(merged.as_slices().0, b"abcde");
+    /// ```
+    pub fn try_concat<const N2: usize, const M: usize>(
+        mut self,
+        mut other: Deque<T, N2>,
+    ) -> Result<Deque<T, M>, (Self, Deque<T, N2>)> {
+        if self.len() + other.len() > M {
+            return Err((self, other));
+        }
+        let mut merged = Deque::new();
+        self.move_all_to(&mut merged);
+        other.move_all_to(&mut merged);
+        Ok(merged)
+    }
+}
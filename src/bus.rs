@@ -0,0 +1,186 @@
+//! A publish/subscribe event bus with a fixed number of subscribers.
+
+use crate::Deque;
+
+/// The number of topics: topic IDs are in `0..TOPICS`.
+pub const TOPICS: u8 = 32;
+
+/// What [`EventBus::publish`] does when a subscriber's queue is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Drop the new event, keeping the ones already queued.
+    DropNewest,
+
+    /// Drop the oldest queued event to make room for the new one.
+    DropOldest,
+}
+
+/// Identifies a subscriber of an [`EventBus`].
+///
+/// Returned by [`EventBus::subscribe`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriberId(usize);
+
+struct Subscriber<T, const N: usize> {
+    /// A bit for each topic subscribed to.
+    topics: u32,
+    overflow: Overflow,
+    queue: Deque<T, N>,
+    dropped: usize,
+}
+
+/// A publish/subscribe bus with up to `S` subscribers, each with a queue of
+/// `N` events.
+///
+/// Subscribers choose which [topics](TOPICS) they want, and each event
+/// [published](Self::publish) on a topic is cloned into the queue of every
+/// subscriber to it. When a subscriber falls behind and its queue fills up,
+/// its [`Overflow`] policy decides which event is lost, and the loss is
+/// counted so it can [find out](Self::take_dropped).
+///
+/// The bus is a plain data structure; to share it between tasks or
+/// interrupt handlers, put it in a mutex such as
+/// `critical_section::Mutex<RefCell<_>>`.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::bus::{EventBus, Overflow};
+///
+/// const BUTTON: u8 = 0;
+/// const SENSOR: u8 = 1;
+///
+/// let mut bus: EventBus<u32, 4, 2> = EventBus::new();
+/// let ui = bus.subscribe(&[BUTTON, SENSOR], Overflow::DropOldest).unwrap();
+/// let logger = bus.subscribe(&[SENSOR], Overflow::DropNewest).unwrap();
+///
+/// assert_eq!(bus.publish(BUTTON, 1), 1);
+/// assert_eq!(bus.publish(SENSOR, 20), 2);
+/// assert_eq!(bus.publish(SENSOR, 21), 2);
+///
+/// // The UI's queue was full, so it lost its oldest event.
+/// assert_eq!(bus.receive(ui), Some(20));
+/// assert_eq!(bus.receive(ui), Some(21));
+/// assert_eq!(bus.take_dropped(ui), 1);
+///
+/// assert_eq!(bus.receive(logger), Some(20));
+/// assert_eq!(bus.receive(logger), Some(21));
+/// assert_eq!(bus.receive(logger), None);
+/// ```
+pub struct EventBus<T, const S: usize, const N: usize> {
+    subscribers: [Option<Subscriber<T, N>>; S],
+}
+
+impl<T: Clone, const S: usize, const N: usize> EventBus<T, S, N> {
+    /// Creates a bus with no subscribers.
+    pub fn new() -> Self {
+        Self {
+            subscribers: [(); S].map(|_| None),
+        }
+    }
+
+    /// Adds a subscriber to the given topics, or returns `None` if there are
+    /// already `S` subscribers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the topics is not less than [`TOPICS`].
+    pub fn subscribe(&mut self, topics: &[u8], overflow: Overflow) -> Option<SubscriberId> {
+        let mut mask = 0;
+        for &topic in topics {
+            assert!(topic < TOPICS, "topic out of range");
+            mask |= 1 << topic;
+        }
+        let index = self.subscribers.iter().position(Option::is_none)?;
+        self.subscribers[index] = Some(Subscriber {
+            topics: mask,
+            overflow,
+            queue: Deque::new(),
+            dropped: 0,
+        });
+        Some(SubscriberId(index))
+    }
+
+    /// Removes a subscriber, dropping any events still in its queue.
+    ///
+    /// Its ID may be handed out again by [`subscribe`](Self::subscribe).
+    pub fn unsubscribe(&mut self, id: SubscriberId) {
+        self.subscribers[id.0] = None;
+    }
+
+    /// Sends `event` to every subscriber to `topic`, returning the number of
+    /// subscribers it was queued for.
+    ///
+    /// Subscribers whose queue is full and whose policy is
+    /// [`DropNewest`](Overflow::DropNewest) don't get the event, and aren't
+    /// counted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `topic` is not less than [`TOPICS`].
+    pub fn publish(&mut self, topic: u8, event: T) -> usize {
+        assert!(topic < TOPICS, "topic out of range");
+        let mut delivered = 0;
+        for subscriber in self.subscribers.iter_mut().flatten() {
+            if subscriber.topics & (1 << topic) == 0 {
+                continue;
+            }
+            if subscriber.queue.is_full() {
+                subscriber.dropped += 1;
+                match subscriber.overflow {
+                    Overflow::DropNewest => continue,
+                    Overflow::DropOldest => {
+                        subscriber.queue.pop_front();
+                    }
+                }
+            }
+            if subscriber.queue.push_back(event.clone()).is_ok() {
+                delivered += 1;
+            }
+        }
+        delivered
+    }
+
+    fn subscriber(&mut self, id: SubscriberId) -> &mut Subscriber<T, N> {
+        self.subscribers[id.0].as_mut().expect("not subscribed")
+    }
+
+    /// Removes the oldest event from a subscriber's queue and returns it, or
+    /// `None` if the queue is empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` was unsubscribed.
+    pub fn receive(&mut self, id: SubscriberId) -> Option<T> {
+        self.subscriber(id).queue.pop_front()
+    }
+
+    /// The number of events waiting in a subscriber's queue.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` was unsubscribed.
+    pub fn pending(&self, id: SubscriberId) -> usize {
+        self.subscribers[id.0]
+            .as_ref()
+            .expect("not subscribed")
+            .queue
+            .len()
+    }
+
+    /// Returns the number of events a subscriber has lost because its queue
+    /// was full since the last call, and resets it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` was unsubscribed.
+    pub fn take_dropped(&mut self, id: SubscriberId) -> usize {
+        core::mem::replace(&mut self.subscriber(id).dropped, 0)
+    }
+}
+
+impl<T: Clone, const S: usize, const N: usize> Default for EventBus<T, S, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
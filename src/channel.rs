@@ -0,0 +1,287 @@
+//! A bounded multi-producer, single-consumer channel.
+//!
+//! A [`Channel`] lives in a `static`, and is [split](Channel::split) once
+//! into a [`Receiver`] and a [`Sender`], which can be cloned for each task or
+//! interrupt handler that sends. The channel notices when either side goes
+//! away: sending fails once the receiver is dropped, and receiving fails
+//! once all senders are dropped and the queued messages are used up.
+
+use core::{
+    cell::RefCell,
+    task::{Context, Waker},
+};
+
+use critical_section::Mutex;
+
+use crate::Deque;
+
+/// An error from [`Sender::try_send`], holding the message that wasn't sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrySendError<T> {
+    /// The channel is full.
+    Full(T),
+
+    /// The receiver was dropped.
+    Disconnected(T),
+}
+
+/// An error from [`Receiver::try_recv`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// The channel is empty.
+    Empty,
+
+    /// The channel is empty, and all senders were dropped.
+    Disconnected,
+}
+
+/// An error from [`Receiver::recv_timeout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecvTimeoutError {
+    /// The wait function gave up.
+    Timeout,
+
+    /// The channel is empty, and all senders were dropped.
+    Disconnected,
+}
+
+struct State<T, const N: usize> {
+    queue: Deque<T, N>,
+    senders: usize,
+    receiver: bool,
+    split: bool,
+
+    /// The task polling the receiver, woken when a message is sent or the
+    /// last sender is dropped.
+    waker: Option<Waker>,
+}
+
+impl<T, const N: usize> State<T, N> {
+    fn wake_receiver(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// A channel holding up to `N` messages.
+///
+/// Access to the queue is guarded by a [`critical_section`], so both sides
+/// can be used from interrupt handlers.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::channel::{Channel, TryRecvError};
+///
+/// static CHANNEL: Channel<u32, 4> = Channel::new();
+///
+/// let (tx, mut rx) = CHANNEL.split().unwrap();
+/// let tx2 = tx.clone();
+/// tx.try_send(1).unwrap();
+/// tx2.try_send(2).unwrap();
+///
+/// assert_eq!(rx.try_recv(), Ok(1));
+/// drop((tx, tx2));
+/// assert_eq!(rx.try_recv(), Ok(2));
+/// assert_eq!(rx.try_recv(), Err(TryRecvError::Disconnected));
+/// ```
+pub struct Channel<T, const N: usize> {
+    state: Mutex<RefCell<State<T, N>>>,
+}
+
+impl<T, const N: usize> Channel<T, N> {
+    /// Creates an empty channel.
+    pub const fn new() -> Self {
+        Self {
+            state: Mutex::new(RefCell::new(State {
+                queue: Deque::new(),
+                senders: 0,
+                receiver: false,
+                split: false,
+                waker: None,
+            })),
+        }
+    }
+
+    fn with_state<R>(&self, f: impl FnOnce(&mut State<T, N>) -> R) -> R {
+        critical_section::with(|cs| f(&mut self.state.borrow_ref_mut(cs)))
+    }
+
+    /// Returns the sending and receiving sides of the channel, or `None` if
+    /// it has already been split.
+    pub fn split(&self) -> Option<(Sender<'_, T, N>, Receiver<'_, T, N>)> {
+        let first = self.with_state(|state| {
+            if state.split {
+                return false;
+            }
+            state.split = true;
+            state.senders = 1;
+            state.receiver = true;
+            true
+        });
+        if first {
+            Some((Sender { channel: self }, Receiver { channel: self }))
+        } else {
+            None
+        }
+    }
+}
+
+impl<T, const N: usize> Default for Channel<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The sending side of a [`Channel`].
+///
+/// Clone it to send from several places.
+pub struct Sender<'a, T, const N: usize> {
+    channel: &'a Channel<T, N>,
+}
+
+impl<T, const N: usize> Sender<'_, T, N> {
+    /// Sends a message without waiting.
+    pub fn try_send(&self, message: T) -> Result<(), TrySendError<T>> {
+        self.channel.with_state(|state| {
+            if !state.receiver {
+                return Err(TrySendError::Disconnected(message));
+            }
+            state.queue.push_back(message).map_err(TrySendError::Full)?;
+            state.wake_receiver();
+            Ok(())
+        })
+    }
+
+    /// Returns `true` if the receiver was dropped.
+    pub fn is_disconnected(&self) -> bool {
+        self.channel.with_state(|state| !state.receiver)
+    }
+}
+
+impl<T, const N: usize> Clone for Sender<'_, T, N> {
+    fn clone(&self) -> Self {
+        self.channel.with_state(|state| state.senders += 1);
+        Self {
+            channel: self.channel,
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for Sender<'_, T, N> {
+    fn drop(&mut self) {
+        self.channel.with_state(|state| {
+            state.senders -= 1;
+            if state.senders == 0 {
+                state.wake_receiver();
+            }
+        });
+    }
+}
+
+/// The receiving side of a [`Channel`].
+pub struct Receiver<'a, T, const N: usize> {
+    channel: &'a Channel<T, N>,
+}
+
+impl<T, const N: usize> Receiver<'_, T, N> {
+    /// Receives a message without waiting.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        self.channel
+            .with_state(|state| match state.queue.pop_front() {
+                Some(message) => Ok(message),
+                None if state.senders == 0 => Err(TryRecvError::Disconnected),
+                None => Err(TryRecvError::Empty),
+            })
+    }
+
+    /// Like [`try_recv`](Self::try_recv), but if the channel is empty,
+    /// registers the task in `cx` to be woken when a message is sent or the
+    /// last sender is dropped.
+    pub(crate) fn poll_recv(&mut self, cx: &mut Context<'_>) -> Result<T, TryRecvError> {
+        self.channel
+            .with_state(|state| match state.queue.pop_front() {
+                Some(message) => Ok(message),
+                None if state.senders == 0 => Err(TryRecvError::Disconnected),
+                None => {
+                    match &state.waker {
+                        Some(waker) if waker.will_wake(cx.waker()) => {}
+                        _ => state.waker = Some(cx.waker().clone()),
+                    }
+                    Err(TryRecvError::Empty)
+                }
+            })
+    }
+
+    /// Receives a message, calling `wait` between attempts until one
+    /// arrives, or returns `None` if all senders were dropped.
+    ///
+    /// `wait` is called outside the critical section, so it can sleep until
+    /// the next interrupt, e.g. with `cortex_m::asm::wfi`.
+    pub fn recv(&mut self, mut wait: impl FnMut()) -> Option<T> {
+        self.recv_timeout(|| {
+            wait();
+            true
+        })
+        .ok()
+    }
+
+    /// Receives a message, calling `wait` between attempts until one
+    /// arrives, or `wait` returns `false`.
+    ///
+    /// `wait` is where a timeout is checked, e.g. against a hardware timer,
+    /// and it's called outside the critical section.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::channel::{Channel, RecvTimeoutError};
+    ///
+    /// static CHANNEL: Channel<u8, 4> = Channel::new();
+    /// let (_tx, mut rx) = CHANNEL.split().unwrap();
+    ///
+    /// let mut polls = 0;
+    /// let result = rx.recv_timeout(|| {
+    ///     polls += 1;
+    ///     polls < 3
+    /// });
+    /// assert_eq!(result, Err(RecvTimeoutError::Timeout));
+    /// ```
+    pub fn recv_timeout(&mut self, mut wait: impl FnMut() -> bool) -> Result<T, RecvTimeoutError> {
+        loop {
+            match self.try_recv() {
+                Ok(message) => return Ok(message),
+                Err(TryRecvError::Disconnected) => return Err(RecvTimeoutError::Disconnected),
+                Err(TryRecvError::Empty) => {
+                    if !wait() {
+                        return Err(RecvTimeoutError::Timeout);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if all senders were dropped.
+    ///
+    /// There may still be messages to receive.
+    pub fn is_disconnected(&self) -> bool {
+        self.channel.with_state(|state| state.senders == 0)
+    }
+
+    /// The number of messages waiting to be received.
+    pub fn len(&self) -> usize {
+        self.channel.with_state(|state| state.queue.len())
+    }
+
+    /// Returns `true` if there are no messages waiting.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T, const N: usize> Drop for Receiver<'_, T, N> {
+    fn drop(&mut self) {
+        self.channel.with_state(|state| state.receiver = false);
+    }
+}
@@ -0,0 +1,264 @@
+//! A growable byte buffer made of fixed-size segments.
+
+use alloc::collections::VecDeque;
+use core::mem::MaybeUninit;
+
+use crate::BoxDeque;
+
+/// A growable byte buffer that stores its contents in a list of fixed-size
+/// segments instead of one contiguous allocation.
+///
+/// Writing never fails: when the last segment is full, another is
+/// allocated, and segments are freed as they are emptied from the front. So
+/// buffering a multi-megabyte stream never needs a multi-megabyte block of
+/// free memory, and never copies the data already buffered, unlike a `Vec`
+/// or `VecDeque` that doubles its allocation.
+///
+/// The methods mirror those of [`Deque<u8, N>`](crate::Deque), except that
+/// writes can't run out of room.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::ChunkedBuffer;
+///
+/// let mut buffer = ChunkedBuffer::new(4);
+/// buffer.push_back_slice(b"hello, world");
+/// assert_eq!(buffer.len(), 12);
+/// assert_eq!(buffer.segment_count(), 3);
+///
+/// let mut out = [0; 7];
+/// assert_eq!(buffer.pop_front_slice(&mut out), 7);
+/// assert_eq!(&out, b"hello, ");
+/// assert_eq!(buffer.segment_count(), 2);
+/// ```
+pub struct ChunkedBuffer {
+    /// The segments holding the data, in order. Only the first and last
+    /// segments may be partially filled, and only the last may be empty (if
+    /// it's the only one).
+    segments: VecDeque<BoxDeque<u8>>,
+
+    /// An emptied segment kept around for reuse, so a buffer that hovers
+    /// around a segment boundary doesn't allocate and free on every write.
+    spare: Option<BoxDeque<u8>>,
+
+    segment_size: usize,
+    len: usize,
+}
+
+impl ChunkedBuffer {
+    /// Creates an empty buffer that allocates `segment_size` bytes at a
+    /// time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `segment_size` is zero.
+    pub fn new(segment_size: usize) -> Self {
+        assert!(segment_size > 0, "segment size must not be zero");
+        Self {
+            segments: VecDeque::new(),
+            spare: None,
+            segment_size,
+            len: 0,
+        }
+    }
+
+    /// The number of bytes in the buffer.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the buffer is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The size of each segment.
+    pub fn segment_size(&self) -> usize {
+        self.segment_size
+    }
+
+    /// The number of segments currently allocated for the data.
+    pub fn segment_count(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Removes all bytes, and frees all but one segment.
+    pub fn clear(&mut self) {
+        while let Some(segment) = self.segments.pop_back() {
+            self.retire(segment);
+        }
+        self.len = 0;
+    }
+
+    /// Returns the last segment, adding one if there are none or it's full.
+    fn back_with_room(&mut self) -> &mut BoxDeque<u8> {
+        if self
+            .segments
+            .back()
+            .map_or(true, |segment| segment.is_full())
+        {
+            let segment = self
+                .spare
+                .take()
+                .unwrap_or_else(|| BoxDeque::with_capacity(self.segment_size));
+            self.segments.push_back(segment);
+        }
+        // There is at least one segment, pushed above if necessary.
+        self.segments.back_mut().unwrap()
+    }
+
+    /// Keeps an emptied segment as the spare, or frees it if there already is
+    /// one.
+    fn retire(&mut self, mut segment: BoxDeque<u8>) {
+        if self.spare.is_none() {
+            segment.clear();
+            self.spare = Some(segment);
+        }
+    }
+
+    /// Frees the first segment if it's empty and isn't the last one.
+    fn trim_front(&mut self) {
+        if self.segments.len() > 1 && self.segments[0].is_empty() {
+            let segment = self.segments.pop_front().unwrap();
+            self.retire(segment);
+        }
+    }
+
+    /// Appends all of `data` to the buffer, allocating segments as needed.
+    pub fn push_back_slice(&mut self, mut data: &[u8]) {
+        self.len += data.len();
+        while !data.is_empty() {
+            let count = self.back_with_room().push_back_slice(data);
+            data = &data[count..];
+        }
+    }
+
+    /// Removes bytes from the front of the buffer into `buf`, returning how
+    /// many were removed.
+    pub fn pop_front_slice(&mut self, buf: &mut [u8]) -> usize {
+        let mut count = 0;
+        while count < buf.len() {
+            let popped = match self.segments.front_mut() {
+                Some(segment) => segment.pop_front_slice(&mut buf[count..]),
+                None => 0,
+            };
+            if popped == 0 {
+                break;
+            }
+            count += popped;
+            self.trim_front();
+        }
+        self.len -= count;
+        count
+    }
+
+    /// Copies bytes starting at index `offset` into `buf` without removing
+    /// them, returning how many were copied.
+    pub fn read_at(&self, mut offset: usize, buf: &mut [u8]) -> usize {
+        let mut count = 0;
+        for segment in &self.segments {
+            if count == buf.len() {
+                break;
+            }
+            if offset >= segment.len() {
+                offset -= segment.len();
+                continue;
+            }
+            count += segment.read_at(offset, &mut buf[count..]);
+            offset = 0;
+        }
+        count
+    }
+
+    /// Removes the first `count` bytes (or all of them, if there are fewer).
+    pub fn advance_front(&mut self, count: usize) {
+        let mut remaining = count.min(self.len);
+        self.len -= remaining;
+        while remaining > 0 {
+            let segment = &mut self.segments[0];
+            let step = remaining.min(segment.len());
+            segment.advance_front(step);
+            remaining -= step;
+            self.trim_front();
+        }
+    }
+
+    /// Returns an iterator over the contiguous slices of the buffer, in
+    /// order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::ChunkedBuffer;
+    ///
+    /// let mut buffer = ChunkedBuffer::new(4);
+    /// buffer.push_back_slice(b"abcdef");
+    /// assert!(buffer.slices().eq([&b"abcd"[..], b"ef"]));
+    /// ```
+    pub fn slices(&self) -> impl Iterator<Item = &[u8]> + '_ {
+        self.segments
+            .iter()
+            .flat_map(|segment| {
+                let (first, second) = segment.as_slices();
+                [first, second]
+            })
+            .filter(|slice| !slice.is_empty())
+    }
+
+    /// Calls `f` with the first contiguous slice of bytes, and removes as
+    /// many bytes from the front as it returns.
+    ///
+    /// See [`DequeBase::pop_front_with`](crate::DequeBase::pop_front_with).
+    pub fn pop_front_with<R>(&mut self, f: impl FnOnce(&mut [u8]) -> (usize, R)) -> (usize, R) {
+        let result = match self.segments.front_mut() {
+            Some(segment) => segment.pop_front_with(f),
+            None => (0, f(&mut []).1),
+        };
+        self.len -= result.0;
+        self.trim_front();
+        result
+    }
+
+    /// Returns a contiguous region of free space after the back of the
+    /// buffer, allocating a segment if there is none.
+    ///
+    /// Write bytes into it (e.g. with a DMA transfer or a `read` call), then
+    /// make them part of the buffer with
+    /// [`advance_back`](Self::advance_back). The region is never empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::ChunkedBuffer;
+    ///
+    /// let mut buffer = ChunkedBuffer::new(16);
+    /// let spare = buffer.spare_capacity_mut();
+    /// spare[0].write(b'a');
+    ///
+    /// // Safety: The first byte was just initialized.
+    /// unsafe { buffer.advance_back(1) };
+    /// assert_eq!(buffer.len(), 1);
+    /// ```
+    pub fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        self.back_with_room().spare_capacity_mut()
+    }
+
+    /// Adds `count` bytes that were written into
+    /// [`spare_capacity_mut`](Self::spare_capacity_mut) to the back of the
+    /// buffer.
+    ///
+    /// # Safety
+    ///
+    /// The first `count` bytes of the slice returned by the last call to
+    /// `spare_capacity_mut` must have been initialized, and `count` must not
+    /// exceed its length.
+    pub unsafe fn advance_back(&mut self, count: usize) {
+        // The spare capacity came from the last segment, so the caller's
+        // promise carries over to it.
+        if let Some(segment) = self.segments.back_mut() {
+            segment.advance_back(count);
+            self.len += count;
+        }
+    }
+}
@@ -0,0 +1,208 @@
+//! COBS framing over byte deques.
+//!
+//! [Consistent Overhead Byte Stuffing][cobs] encodes a frame so that it
+//! contains no zero bytes, which makes a zero byte usable as the frame
+//! delimiter. The overhead is at most one byte in 254, plus the delimiter.
+//!
+//! [`encode`] and [`encode_from`] append an encoded, delimited frame to a
+//! byte deque, e.g. the transmit buffer of a UART. [`next_frame`] finds the
+//! next complete frame in a receive buffer and decodes it in place, without
+//! copying it out of the deque.
+//!
+//! [cobs]: https://en.wikipedia.org/wiki/Consistent_Overhead_Byte_Stuffing
+//!
+//! # Examples
+//!
+//! ```
+//! use fullhouse::{cobs, Deque};
+//!
+//! let mut wire: Deque<u8, 32> = Deque::new();
+//! cobs::encode(&[0x11, 0x00, 0x22], &mut wire).unwrap();
+//! cobs::encode(&[0x33], &mut wire).unwrap();
+//!
+//! let frame = cobs::next_frame(&mut wire).unwrap().unwrap();
+//! assert_eq!(&*frame, &[0x11, 0x00, 0x22]);
+//! drop(frame);
+//!
+//! let frame = cobs::next_frame(&mut wire).unwrap().unwrap();
+//! assert_eq!(&*frame, &[0x33]);
+//! drop(frame);
+//!
+//! assert!(cobs::next_frame(&mut wire).is_none());
+//! ```
+
+use core::ops::Range;
+
+use crate::{frames::next_decoded_frame, DequeBase, Storage};
+
+pub use crate::frames::DecodedFrame as Frame;
+
+/// The byte that ends each frame.
+pub const DELIMITER: u8 = 0x00;
+
+/// An error from encoding or decoding COBS frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CobsError {
+    /// The destination does not have room for the encoded frame. Nothing was
+    /// written.
+    Full,
+
+    /// A frame was not validly encoded. It has been removed from the deque.
+    Malformed,
+
+    /// The deque filled up without a delimiter, so the frame is longer than
+    /// the deque can hold. The partial frame has been removed from the deque.
+    TooLong,
+}
+
+/// Encodes `data` as one frame, including the delimiter, and appends it to
+/// `dst`.
+pub fn encode<S: Storage<u8>>(data: &[u8], dst: &mut DequeBase<u8, S>) -> Result<(), CobsError> {
+    encode_bytes(data.len(), |i| data[i], dst)
+}
+
+/// Encodes the whole contents of `src` as one frame, including the
+/// delimiter, and appends it to `dst`.
+///
+/// On success, `src` is left empty. If `dst` does not have room for the
+/// frame, both deques are left unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::{cobs, Deque};
+///
+/// let mut message: Deque<u8, 8> = Deque::new();
+/// message.push_back(0x00).unwrap();
+/// message.push_back(0x42).unwrap();
+///
+/// let mut wire: Deque<u8, 8> = Deque::new();
+/// cobs::encode_from(&mut message, &mut wire).unwrap();
+/// assert!(message.is_empty());
+/// assert_eq!(wire.as_slices().0, &[0x01, 0x02, 0x42, 0x00]);
+/// ```
+pub fn encode_from<S: Storage<u8>, D: Storage<u8>>(
+    src: &mut DequeBase<u8, S>,
+    dst: &mut DequeBase<u8, D>,
+) -> Result<(), CobsError> {
+    let (front, back) = src.as_slices();
+    encode_bytes(
+        src.len(),
+        |i| match front.get(i) {
+            Some(&byte) => byte,
+            None => back[i - front.len()],
+        },
+        dst,
+    )?;
+    src.clear();
+    Ok(())
+}
+
+/// Encodes the `len` bytes given by `byte(0..len)`.
+fn encode_bytes<S: Storage<u8>>(
+    len: usize,
+    byte: impl Fn(usize) -> u8,
+    dst: &mut DequeBase<u8, S>,
+) -> Result<(), CobsError> {
+    // Check the exact encoded size first, so a frame is never partially
+    // written.
+    let mut encoded_len = 1;
+    for_each_block(len, &byte, |block| encoded_len += 1 + block.len());
+    if encoded_len > dst.capacity() - dst.len() {
+        return Err(CobsError::Full);
+    }
+
+    // There is enough space for all of the pushes, checked above.
+    for_each_block(len, &byte, |block| {
+        let _ = dst.push_back(block.len() as u8 + 1);
+        for i in block {
+            let _ = dst.push_back(byte(i));
+        }
+    });
+    let _ = dst.push_back(DELIMITER);
+    Ok(())
+}
+
+/// Splits `0..len` into the ranges of bytes that make up each COBS block.
+///
+/// Each block is encoded as its length plus one, followed by its bytes. The
+/// zero byte that ends a block (if any) is implied.
+fn for_each_block(len: usize, byte: impl Fn(usize) -> u8, mut f: impl FnMut(Range<usize>)) {
+    let mut start = 0;
+    loop {
+        let mut end = start;
+        while end < len && end - start < 254 && byte(end) != 0 {
+            end += 1;
+        }
+        f(start..end);
+
+        if end == len {
+            break;
+        } else if end - start == 254 {
+            // A full block has no implied zero.
+            start = end;
+        } else {
+            // Skip the zero; if it was the last byte, the loop emits an
+            // empty block for it.
+            start = end + 1;
+        }
+    }
+}
+
+/// Decodes the next complete frame at the front of `deque`.
+///
+/// Returns `None` if the deque does not contain a complete frame yet. Empty
+/// frames (consecutive delimiters) are skipped.
+///
+/// The frame is decoded in place and borrowed from the deque; it is removed
+/// from the deque when the returned [`Frame`] is dropped. If the encoded
+/// frame wraps around the end of the deque's storage, the contents are moved
+/// to make it contiguous first.
+///
+/// If the deque is full and contains no delimiter, its contents are
+/// discarded and [`CobsError::TooLong`] is returned, so that reception can
+/// resynchronize at the next delimiter.
+pub fn next_frame<S: Storage<u8>>(
+    deque: &mut DequeBase<u8, S>,
+) -> Option<Result<Frame<'_, S>, CobsError>> {
+    next_decoded_frame(
+        deque,
+        DELIMITER,
+        decode_in_place,
+        CobsError::TooLong,
+        CobsError::Malformed,
+    )
+}
+
+/// Decodes a COBS-encoded frame without its delimiter, writing the result
+/// to the start of the same buffer.
+///
+/// Returns the length of the decoded data, or `None` if the frame is
+/// malformed.
+fn decode_in_place(buf: &mut [u8]) -> Option<usize> {
+    let mut read = 0;
+    let mut write = 0;
+    while read < buf.len() {
+        let code = buf[read] as usize;
+        if code == 0 {
+            return None;
+        }
+        read += 1;
+
+        let end = read + code - 1;
+        if end > buf.len() {
+            return None;
+        }
+        // The decoded data never gets ahead of the encoded data, because
+        // every block drops its code byte.
+        buf.copy_within(read..end, write);
+        write += end - read;
+        read = end;
+
+        if code != 0xFF && read < buf.len() {
+            buf[write] = 0;
+            write += 1;
+        }
+    }
+    Some(write)
+}
@@ -0,0 +1,322 @@
+//! A deque with two index words instead of three.
+
+use core::{
+    mem::{self, MaybeUninit},
+    ops::{Index, IndexMut, Range},
+};
+
+use crate::{drop_ranges, scrub_range};
+
+/// A fixed-capacity deque that stores only the position of its first element
+/// and its length.
+///
+/// This has the basic pushing, popping and indexing methods of
+/// [`Deque`](crate::Deque), but saves one word of RAM per deque:
+/// [`Deque`](crate::Deque) also stores the position after the last element,
+/// which is derived here from the other two whenever it is needed. That
+/// costs an addition and a compare on every push and pop at the back, which
+/// is a good trade on parts with only a few KiB of RAM and many small
+/// queues.
+///
+/// [`Pow2Deque`](crate::Pow2Deque) also uses two words, and is faster, but
+/// requires a power-of-two capacity.
+///
+/// The `zero-on-pop` and `poison-on-pop` features scrub popped slots the same
+/// way as for [`Deque`](crate::Deque), and `debug-invariants` checks the
+/// indexes after every push and pop.
+///
+/// # Examples
+///
+/// ```
+/// use core::mem::size_of;
+/// use fullhouse::{CompactDeque, Deque};
+///
+/// let mut deque: CompactDeque<u8, 12> = CompactDeque::new();
+/// deque.push_back(1).unwrap();
+/// deque.push_front(0).unwrap();
+/// assert_eq!(deque.pop_front(), Some(0));
+/// assert_eq!(deque.pop_front(), Some(1));
+///
+/// assert!(size_of::<CompactDeque<u8, 12>>() < size_of::<Deque<u8, 12>>());
+/// ```
+pub struct CompactDeque<T, const CAPACITY: usize> {
+    /// Index of the first element in `data`, always in `0..CAPACITY` (or
+    /// zero if `CAPACITY` is zero).
+    start: usize,
+
+    /// The number of elements, always in `0..=CAPACITY`.
+    len: usize,
+
+    /// A circular buffer. Elements are stored at the `len` indexes starting
+    /// at `start`, wrapping around the end.
+    data: [MaybeUninit<T>; CAPACITY],
+}
+
+impl<T, const CAPACITY: usize> CompactDeque<T, CAPACITY> {
+    /// Creates an empty deque.
+    pub const fn new() -> Self {
+        Self {
+            start: 0,
+            len: 0,
+
+            // Safety: The value inside the outermost MaybeUninit must be valid.
+            // - A value of `[MaybeUninit<T>; N]` is valid because a value of
+            //  `MaybeUninit<T>` is always valid (even if the inner value
+            //  isn't).
+            data: unsafe { MaybeUninit::<[MaybeUninit<T>; CAPACITY]>::uninit().assume_init() },
+        }
+    }
+
+    /// The maximum number of elements this deque can hold.
+    pub const fn capacity(&self) -> usize {
+        CAPACITY
+    }
+
+    /// The number of elements currently in the deque.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the deque is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the deque is full.
+    pub const fn is_full(&self) -> bool {
+        self.len == CAPACITY
+    }
+
+    /// Clears the deque, removing all values.
+    pub fn clear(&mut self) {
+        let (first, second) = self.ranges();
+
+        // Reset region state:
+        self.start = 0;
+        self.len = 0;
+        self.check_invariants();
+
+        let base = self.data.as_mut_ptr().cast::<T>();
+        if mem::needs_drop::<T>() {
+            // Safety: The values in the MaybeUninits must be valid.
+            // - ranges() returns the ranges of valid values, which don't
+            //   overlap.
+            //
+            // Postcondition: The values in the MaybeUninits are invalidated.
+            // - The region is reset to empty before dropping, so later code
+            //   (including panics) will not assume that this data is valid.
+            unsafe { drop_ranges(base, first.clone(), second.clone()) };
+        }
+        // Safety: The ranges are in `data`, and no longer hold values.
+        unsafe {
+            scrub_range(base, first);
+            scrub_range(base, second);
+        }
+    }
+
+    /// Ranges of indexes of valid values in `data`. Together, in order, they
+    /// span the logical contents from the front to the back.
+    fn ranges(&self) -> (Range<usize>, Range<usize>) {
+        let pre_wrap_size = CAPACITY - self.start;
+        if self.len <= pre_wrap_size {
+            (self.start..self.start + self.len, 0..0)
+        } else {
+            (self.start..CAPACITY, 0..self.len - pre_wrap_size)
+        }
+    }
+
+    /// Takes the value out of the slot at `idx` in `data`, and scrubs it.
+    ///
+    /// # Safety
+    ///
+    /// The slot must hold a valid value, which must not be used again.
+    unsafe fn take(&mut self, idx: usize) -> T {
+        let value = self.data[idx].assume_init_read();
+        scrub_range(self.data.as_mut_ptr().cast::<T>(), idx..idx + 1);
+        value
+    }
+
+    /// Panics if the indexes are out of bounds, with the
+    /// `debug-invariants` feature.
+    #[inline]
+    fn check_invariants(&self) {
+        #[cfg(feature = "debug-invariants")]
+        {
+            assert!(self.len <= CAPACITY, "length exceeds capacity");
+            assert!(
+                self.start < CAPACITY || self.start == 0,
+                "start index out of bounds"
+            );
+        }
+    }
+
+    /// The index in `data` of the element at `offset` from the front, for
+    /// an `offset` of at most `CAPACITY`.
+    fn data_index(&self, offset: usize) -> usize {
+        // Compare against the distance to the end, so the sum can't
+        // overflow.
+        let pre_wrap_size = CAPACITY - self.start;
+        if offset < pre_wrap_size {
+            self.start + offset
+        } else {
+            offset - pre_wrap_size
+        }
+    }
+
+    /// Prepends an element to the deque.
+    pub fn push_front(&mut self, value: T) -> Result<(), T> {
+        if self.is_full() {
+            Err(value)
+        } else {
+            let new_start = if self.start == 0 {
+                CAPACITY - 1
+            } else {
+                self.start - 1
+            };
+            self.data[new_start].write(value);
+            self.start = new_start;
+            self.len += 1;
+            self.check_invariants();
+            Ok(())
+        }
+    }
+
+    /// Appends an element to the back of the deque.
+    pub fn push_back(&mut self, value: T) -> Result<(), T> {
+        if self.is_full() {
+            Err(value)
+        } else {
+            let end = self.data_index(self.len);
+            self.data[end].write(value);
+            self.len += 1;
+            self.check_invariants();
+            Ok(())
+        }
+    }
+
+    /// Removes the first element and returns it, or `None` if the deque is
+    /// empty.
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            let old_start = self.start;
+            self.start = self.data_index(1);
+            self.len -= 1;
+            self.check_invariants();
+
+            // Safety: The value in the MaybeUninit must be valid.
+            // - At the start of the function, it was in the valid region of the
+            //   `data` array, and is not otherwise accessed in this function.
+            //
+            // Postcondition: The value in the MaybeUninit is invalidated (it
+            // has been moved).
+            // - The region has already been shrunk, so later code (including
+            //   panics) will not assume that this index is valid.
+            Some(unsafe { self.take(old_start) })
+        }
+    }
+
+    /// Removes the last element from the deque and returns it, or `None` if
+    /// it is empty.
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            self.len -= 1;
+            let idx = self.data_index(self.len);
+            self.check_invariants();
+
+            // Safety: The value in the MaybeUninit must be valid.
+            // - At the start of the function, it was in the valid region of the
+            //   `data` array, and is not otherwise accessed in this function.
+            //
+            // Postcondition: The value in the MaybeUninit is invalidated (it
+            // has been moved).
+            // - The region has already been shrunk, so later code (including
+            //   panics) will not assume that this index is valid.
+            Some(unsafe { self.take(idx) })
+        }
+    }
+
+    /// Provides a reference to the element at the given index.
+    ///
+    /// Element at index 0 is at the front of the queue.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len {
+            let idx = self.data_index(index);
+
+            // Safety: The value in the MaybeUninit must be valid.
+            // - `index < len`, so `idx` is in the valid region.
+            Some(unsafe { self.data[idx].assume_init_ref() })
+        } else {
+            None
+        }
+    }
+
+    /// Provides a mutable reference to the element at the given index.
+    ///
+    /// Element at index 0 is at the front of the queue.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.len {
+            let idx = self.data_index(index);
+
+            // Safety: The value in the MaybeUninit must be valid.
+            // - `index < len`, so `idx` is in the valid region.
+            Some(unsafe { self.data[idx].assume_init_mut() })
+        } else {
+            None
+        }
+    }
+
+    /// Provides a reference to the front element, or `None` if the deque is
+    /// empty.
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// Provides a mutable reference to the front element, or `None` if the
+    /// deque is empty.
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(0)
+    }
+
+    /// Provides a reference to the back element, or `None` if the deque is
+    /// empty.
+    pub fn back(&self) -> Option<&T> {
+        self.get(self.len.wrapping_sub(1))
+    }
+
+    /// Provides a mutable reference to the back element, or `None` if the
+    /// deque is empty.
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(self.len.wrapping_sub(1))
+    }
+}
+
+impl<T, const CAPACITY: usize> Default for CompactDeque<T, CAPACITY> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const CAPACITY: usize> Drop for CompactDeque<T, CAPACITY> {
+    fn drop(&mut self) {
+        // Drops any elements still in the deque:
+        self.clear();
+    }
+}
+
+impl<T, const CAPACITY: usize> Index<usize> for CompactDeque<T, CAPACITY> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("Out of bounds access")
+    }
+}
+
+impl<T, const CAPACITY: usize> IndexMut<usize> for CompactDeque<T, CAPACITY> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).expect("Out of bounds access")
+    }
+}
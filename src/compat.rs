@@ -0,0 +1,356 @@
+//! A drop-in stand-in for `VecDeque`, for porting code to `no_std`.
+
+use core::{
+    fmt,
+    iter::FusedIterator,
+    ops::{Deref, DerefMut},
+};
+
+use crate::Deque;
+
+/// A [`Deque`] with the method names and signatures of the standard
+/// library's `VecDeque`, which panics where `VecDeque` would grow.
+///
+/// This is for porting code written against `VecDeque` by swapping the type
+/// (and picking a capacity), while prototyping. Once the code works, prefer
+/// [`Deque`] itself, so running out of space is handled instead of
+/// panicking.
+///
+/// Methods `VecDeque` and [`Deque`] share, like `pop_front`, `len` and
+/// `iter`, are reached through [`Deref`] to the underlying [`Deque`].
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::compat::VecDeque;
+///
+/// let mut deque: VecDeque<u32, 4> = VecDeque::new();
+/// deque.push_back(2);
+/// deque.push_front(1);
+/// deque.extend([3, 4]);
+/// assert_eq!(deque, [1, 2, 3, 4]);
+///
+/// assert_eq!(deque.pop_front(), Some(1));
+/// deque.truncate(1);
+/// assert_eq!(deque[0], 2);
+/// ```
+///
+/// Overflowing panics:
+///
+/// ```should_panic
+/// use fullhouse::compat::VecDeque;
+///
+/// let mut deque: VecDeque<u32, 1> = VecDeque::new();
+/// deque.push_back(1);
+/// deque.push_back(2);
+/// ```
+pub struct VecDeque<T, const N: usize> {
+    inner: Deque<T, N>,
+}
+
+impl<T, const N: usize> VecDeque<T, N> {
+    /// Creates an empty deque.
+    pub const fn new() -> Self {
+        Self {
+            inner: Deque::new(),
+        }
+    }
+
+    /// Unwraps the underlying [`Deque`].
+    pub fn into_inner(self) -> Deque<T, N> {
+        self.inner
+    }
+
+    /// Appends an element to the back of the deque.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the deque is full.
+    #[track_caller]
+    pub fn push_back(&mut self, value: T) {
+        if self.inner.push_back(value).is_err() {
+            full();
+        }
+    }
+
+    /// Prepends an element to the front of the deque.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the deque is full.
+    #[track_caller]
+    pub fn push_front(&mut self, value: T) {
+        if self.inner.push_front(value).is_err() {
+            full();
+        }
+    }
+
+    /// Inserts an element at `index`, shifting the elements after it back.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the length, or if the deque is
+    /// full.
+    #[track_caller]
+    pub fn insert(&mut self, index: usize, value: T) {
+        if self.inner.insert(index, value).is_err() {
+            full();
+        }
+    }
+
+    /// Moves all the elements of `other` to the back of the deque, leaving
+    /// `other` empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the elements don't fit, in which case neither deque is
+    /// changed.
+    #[track_caller]
+    pub fn append<const M: usize>(&mut self, other: &mut VecDeque<T, M>) {
+        if other.len() > N - self.len() {
+            full();
+        }
+        while let Some(value) = other.inner.pop_front() {
+            self.push_back(value);
+        }
+    }
+
+    /// Removes the elements after the first `len`.
+    ///
+    /// Does nothing if the deque is already no longer than `len`.
+    pub fn truncate(&mut self, len: usize) {
+        while self.inner.len() > len {
+            self.inner.pop_back();
+        }
+    }
+
+    /// Resizes the deque to `new_len`, removing elements from the back or
+    /// appending clones of `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_len` is greater than the capacity.
+    #[track_caller]
+    pub fn resize(&mut self, new_len: usize, value: T)
+    where
+        T: Clone,
+    {
+        self.resize_with(new_len, || value.clone());
+    }
+
+    /// Resizes the deque to `new_len`, removing elements from the back or
+    /// appending values returned by `f`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_len` is greater than the capacity.
+    #[track_caller]
+    pub fn resize_with(&mut self, new_len: usize, mut f: impl FnMut() -> T) {
+        if new_len > N {
+            full();
+        }
+        self.truncate(new_len);
+        while self.inner.len() < new_len {
+            self.push_back(f());
+        }
+    }
+
+    /// Swaps the elements at indexes `i` and `j`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds.
+    #[track_caller]
+    pub fn swap(&mut self, i: usize, j: usize) {
+        let len = self.inner.len();
+        assert!(i < len && j < len, "swap index out of bounds");
+        let (i, j) = (i.min(j), i.max(j));
+        let (front, back) = self.inner.as_mut_slices();
+        let split = front.len();
+        if j < split {
+            front.swap(i, j);
+        } else if i >= split {
+            back.swap(i - split, j - split);
+        } else {
+            core::mem::swap(&mut front[i], &mut back[j - split]);
+        }
+    }
+
+    /// Returns `true` if the deque contains an element equal to `value`.
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.inner.iter().any(|x| x == value)
+    }
+}
+
+#[cold]
+#[track_caller]
+fn full() -> ! {
+    panic!("deque is full")
+}
+
+impl<T, const N: usize> Deref for VecDeque<T, N> {
+    type Target = Deque<T, N>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T, const N: usize> DerefMut for VecDeque<T, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<T, const N: usize> From<Deque<T, N>> for VecDeque<T, N> {
+    fn from(inner: Deque<T, N>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<T, const N: usize> Default for VecDeque<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for VecDeque<T, N> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for VecDeque<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.inner.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, const N: usize, const M: usize> PartialEq<VecDeque<T, M>> for VecDeque<T, N> {
+    fn eq(&self, other: &VecDeque<T, M>) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for VecDeque<T, N> {}
+
+impl<T: PartialEq, const N: usize, const M: usize> PartialEq<[T; M]> for VecDeque<T, N> {
+    fn eq(&self, other: &[T; M]) -> bool {
+        self.len() == M && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq<[T]> for VecDeque<T, N> {
+    fn eq(&self, other: &[T]) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T, const N: usize> Extend<T> for VecDeque<T, N> {
+    /// Appends the items of `iter`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the deque fills up.
+    #[track_caller]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push_back(value);
+        }
+    }
+}
+
+impl<'a, T: Copy + 'a, const N: usize> Extend<&'a T> for VecDeque<T, N> {
+    #[track_caller]
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for VecDeque<T, N> {
+    /// Collects the items of `iter` into a deque.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more than `N` items.
+    #[track_caller]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut deque = Self::new();
+        deque.extend(iter);
+        deque
+    }
+}
+
+impl<T, const N: usize, const M: usize> From<[T; M]> for VecDeque<T, N> {
+    /// Collects the elements of an array into a deque.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `M` is greater than `N`.
+    #[track_caller]
+    fn from(array: [T; M]) -> Self {
+        if M > N {
+            full();
+        }
+        array.into_iter().collect()
+    }
+}
+
+impl<T, const N: usize> IntoIterator for VecDeque<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { inner: self.inner }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a VecDeque<T, N> {
+    type Item = &'a T;
+    type IntoIter = crate::iter::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut VecDeque<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = crate::iter::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.iter_mut()
+    }
+}
+
+/// An iterator that moves the elements out of a [`VecDeque`].
+pub struct IntoIter<T, const N: usize> {
+    inner: Deque<T, N>,
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.inner.len(), Some(self.inner.len()))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        self.inner.pop_back()
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> FusedIterator for IntoIter<T, N> {}
@@ -0,0 +1,270 @@
+//! Computing CRCs of data as it is written to a byte deque.
+//!
+//! [`CrcWriter`] appends to a deque like the deque's own write methods, and
+//! keeps a running [`Crc`] of every byte that was appended. Framing code can
+//! then append the checksum after the payload without reading it back out.
+//!
+//! # Examples
+//!
+//! ```
+//! use fullhouse::{
+//!     crc::{Algorithm, CrcWriter},
+//!     Deque,
+//! };
+//!
+//! let mut wire: Deque<u8, 16> = Deque::new();
+//! let mut writer = CrcWriter::new(&mut wire, Algorithm::CRC16_CCITT_FALSE);
+//! writer.write(b"1234");
+//! writer.write(b"56789");
+//! let crc = writer.crc() as u16;
+//! assert_eq!(crc, 0x29b1);
+//!
+//! wire.push_back_slice(&crc.to_be_bytes());
+//! assert_eq!(wire.len(), 11);
+//! ```
+
+use crate::{DequeBase, Storage};
+
+/// The parameters of a CRC algorithm, in the style of the [CRC
+/// catalogue][catalogue].
+///
+/// Only algorithms where `refin == refout` are supported.
+///
+/// [catalogue]: https://reveng.sourceforge.io/crc-catalogue/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Algorithm {
+    /// The width of the CRC in bits, from 8 to 32.
+    pub width: u32,
+
+    /// The generator polynomial, in normal (not reflected) form.
+    pub poly: u32,
+
+    /// The initial register value, in normal form.
+    pub init: u32,
+
+    /// Whether bytes are processed least significant bit first.
+    pub reflect: bool,
+
+    /// The value XORed into the register to get the CRC.
+    pub xorout: u32,
+}
+
+impl Algorithm {
+    /// CRC-8/SMBUS.
+    pub const CRC8_SMBUS: Self = Self {
+        width: 8,
+        poly: 0x07,
+        init: 0x00,
+        reflect: false,
+        xorout: 0x00,
+    };
+
+    /// CRC-16/CCITT-FALSE (also known as CRC-16/IBM-3740).
+    pub const CRC16_CCITT_FALSE: Self = Self {
+        width: 16,
+        poly: 0x1021,
+        init: 0xffff,
+        reflect: false,
+        xorout: 0x0000,
+    };
+
+    /// CRC-16/MODBUS.
+    pub const CRC16_MODBUS: Self = Self {
+        width: 16,
+        poly: 0x8005,
+        init: 0xffff,
+        reflect: true,
+        xorout: 0x0000,
+    };
+
+    /// CRC-32 as used by Ethernet, zlib, and PNG (also known as
+    /// CRC-32/ISO-HDLC).
+    pub const CRC32: Self = Self {
+        width: 32,
+        poly: 0x04c1_1db7,
+        init: 0xffff_ffff,
+        reflect: true,
+        xorout: 0xffff_ffff,
+    };
+
+    /// The mask of the bits in the register.
+    const fn mask(&self) -> u32 {
+        u32::MAX >> (32 - self.width)
+    }
+
+    /// Reverses the low `width` bits of `value`.
+    const fn reflect_bits(&self, value: u32) -> u32 {
+        value.reverse_bits() >> (32 - self.width)
+    }
+}
+
+/// A running CRC.
+///
+/// This processes one bit at a time, without a lookup table, to keep the
+/// code size small.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::crc::{Algorithm, Crc};
+///
+/// let mut crc = Crc::new(Algorithm::CRC32);
+/// crc.update(b"123456789");
+/// assert_eq!(crc.value(), 0xcbf4_3926);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Crc {
+    algorithm: Algorithm,
+
+    /// The register, reflected if the algorithm is reflected.
+    register: u32,
+
+    /// The polynomial, reflected if the algorithm is reflected.
+    poly: u32,
+}
+
+impl Crc {
+    /// Starts a CRC of empty data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the width of `algorithm` is not between 8 and 32.
+    pub const fn new(algorithm: Algorithm) -> Self {
+        assert!(
+            algorithm.width >= 8 && algorithm.width <= 32,
+            "Unsupported CRC width"
+        );
+        let (register, poly) = if algorithm.reflect {
+            (
+                algorithm.reflect_bits(algorithm.init),
+                algorithm.reflect_bits(algorithm.poly),
+            )
+        } else {
+            (algorithm.init & algorithm.mask(), algorithm.poly)
+        };
+        Self {
+            algorithm,
+            register,
+            poly,
+        }
+    }
+
+    /// The algorithm of this CRC.
+    pub const fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// Adds `data` to the CRC.
+    pub fn update(&mut self, data: &[u8]) {
+        let mask = self.algorithm.mask();
+        if self.algorithm.reflect {
+            for &byte in data {
+                self.register ^= u32::from(byte);
+                for _ in 0..8 {
+                    let carry = self.register & 1 != 0;
+                    self.register >>= 1;
+                    if carry {
+                        self.register ^= self.poly;
+                    }
+                }
+            }
+        } else {
+            let top = 1 << (self.algorithm.width - 1);
+            for &byte in data {
+                self.register ^= u32::from(byte) << (self.algorithm.width - 8);
+                for _ in 0..8 {
+                    let carry = self.register & top != 0;
+                    self.register = (self.register << 1) & mask;
+                    if carry {
+                        self.register ^= self.poly;
+                    }
+                }
+            }
+        }
+    }
+
+    /// The CRC of the data added so far.
+    pub const fn value(&self) -> u32 {
+        (self.register ^ self.algorithm.xorout) & self.algorithm.mask()
+    }
+
+    /// Starts over with empty data.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.algorithm);
+    }
+}
+
+/// Appends bytes to a deque, and keeps a running CRC of the bytes that were
+/// appended.
+///
+/// With the `embedded-io` or `std` features, this also implements their
+/// `Write` traits, with the same behavior as the deque's implementations.
+pub struct CrcWriter<'a, S: Storage<u8>> {
+    ring: &'a mut DequeBase<u8, S>,
+    crc: Crc,
+}
+
+impl<'a, S: Storage<u8>> CrcWriter<'a, S> {
+    /// Creates a writer that appends to `ring`, starting a new CRC with
+    /// `algorithm`.
+    pub fn new(ring: &'a mut DequeBase<u8, S>, algorithm: Algorithm) -> Self {
+        Self {
+            ring,
+            crc: Crc::new(algorithm),
+        }
+    }
+
+    /// Appends as many bytes from `data` as fit, returning the number of
+    /// bytes appended.
+    ///
+    /// Only the appended bytes are added to the CRC.
+    pub fn write(&mut self, data: &[u8]) -> usize {
+        let count = self.ring.push_back_slice(data);
+        self.crc.update(&data[..count]);
+        count
+    }
+
+    /// The CRC of the bytes appended so far.
+    pub fn crc(&self) -> u32 {
+        self.crc.value()
+    }
+
+    /// Starts a new CRC, e.g. for the next frame.
+    pub fn reset(&mut self) {
+        self.crc.reset()
+    }
+
+    /// The deque being written to.
+    pub fn ring(&mut self) -> &mut DequeBase<u8, S> {
+        self.ring
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<S: Storage<u8>> embedded_io::ErrorType for CrcWriter<'_, S> {
+    type Error = embedded_io::ErrorKind;
+}
+
+#[cfg(feature = "embedded-io")]
+impl<S: Storage<u8>> embedded_io::Write for CrcWriter<'_, S> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let count = embedded_io::Write::write(self.ring, buf)?;
+        self.crc.update(&buf[..count]);
+        Ok(count)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: Storage<u8>> std::io::Write for CrcWriter<'_, S> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        Ok(CrcWriter::write(self, buf))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+//! Reading ahead from the front of a deque, then deciding whether to
+//! consume.
+
+use crate::{DequeBase, Storage};
+
+/// A cursor that reads forward from the front of a deque without removing
+/// anything, until it is [committed](Self::commit).
+///
+/// Committing removes every element the cursor moved past. Dropping the
+/// cursor (or calling [`rewind`](Self::rewind)) leaves the deque as it was,
+/// which is what a parser wants when it finds that a frame isn't complete
+/// yet.
+///
+/// Returned by [`DequeBase::read_cursor`].
+pub struct ReadCursor<'a, T, S: Storage<T>> {
+    deque: &'a mut DequeBase<T, S>,
+
+    /// The number of elements moved past.
+    pos: usize,
+}
+
+impl<T, S: Storage<T>> ReadCursor<'_, T, S> {
+    /// The number of elements the cursor has moved past.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// The number of elements after the cursor.
+    pub fn remaining(&self) -> usize {
+        self.deque.len() - self.pos
+    }
+
+    /// Returns the next element, without moving past it.
+    pub fn peek(&self) -> Option<&T> {
+        self.deque.get(self.pos)
+    }
+
+    /// Returns the next element, and moves past it.
+    pub fn advance(&mut self) -> Option<&T> {
+        let value = self.deque.get(self.pos)?;
+        self.pos += 1;
+        Some(value)
+    }
+
+    /// Moves past up to `count` elements, returning how many were skipped.
+    pub fn skip(&mut self, count: usize) -> usize {
+        let count = count.min(self.remaining());
+        self.pos += count;
+        count
+    }
+
+    /// Moves the cursor back to the front of the deque.
+    pub fn rewind(&mut self) {
+        self.pos = 0;
+    }
+
+    /// Removes the elements the cursor moved past from the deque, returning
+    /// how many were removed.
+    pub fn commit(self) -> usize {
+        self.deque.advance_front(self.pos);
+        self.pos
+    }
+}
+
+impl<T: Copy, S: Storage<T>> ReadCursor<'_, T, S> {
+    /// Copies as many of the next elements into `buf` as fit, and moves past
+    /// them, returning how many were copied.
+    pub fn read(&mut self, buf: &mut [T]) -> usize {
+        let count = self.deque.read_at(self.pos, buf);
+        self.pos += count;
+        count
+    }
+
+    /// Fills `buf` with the next elements and moves past them, or returns
+    /// `false` without moving if there aren't enough.
+    pub fn read_exact(&mut self, buf: &mut [T]) -> bool {
+        if buf.len() > self.remaining() {
+            return false;
+        }
+        self.read(buf);
+        true
+    }
+}
+
+impl<T, S: Storage<T>> DequeBase<T, S> {
+    /// Returns a cursor for reading ahead from the front of the deque,
+    /// removing what was read only if it is
+    /// [committed](ReadCursor::commit).
+    ///
+    /// # Examples
+    ///
+    /// Parsing length-prefixed frames as they trickle in:
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// fn parse(rx: &mut Deque<u8, 16>, out: &mut [u8]) -> Option<usize> {
+    ///     let mut cursor = rx.read_cursor();
+    ///     let len = *cursor.advance()? as usize;
+    ///     if !cursor.read_exact(&mut out[..len]) {
+    ///         // Incomplete; leave it for next time.
+    ///         return None;
+    ///     }
+    ///     cursor.commit();
+    ///     Some(len)
+    /// }
+    ///
+    /// let mut rx: Deque<u8, 16> = Deque::new();
+    /// let mut frame = [0; 16];
+    /// rx.push_back_slice(&[3, b'a', b'b']);
+    /// assert_eq!(parse(&mut rx, &mut frame), None);
+    /// assert_eq!(rx.len(), 3);
+    ///
+    /// rx.push_back_slice(&[b'c', 1]);
+    /// assert_eq!(parse(&mut rx, &mut frame), Some(3));
+    /// assert_eq!(&frame[..3], b"abc");
+    /// assert_eq!(rx.len(), 1);
+    /// ```
+    pub fn read_cursor(&mut self) -> ReadCursor<'_, T, S> {
+        ReadCursor {
+            deque: self,
+            pos: 0,
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Deserialization of deques from sequences.
+
+use core::{fmt, marker::PhantomData};
+
+use serde::de::{Deserialize, DeserializeSeed, Deserializer, Error, SeqAccess, Visitor};
+
+use crate::{Deque, DequeBase, Storage};
+
+/// Deserializes a sequence into a new deque, failing if it has more than
+/// `CAPACITY` elements.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::Deque;
+/// use serde::{de::value::{Error, SeqDeserializer}, Deserialize};
+///
+/// let input = SeqDeserializer::<_, Error>::new([1, 2, 3].into_iter());
+/// let deque: Deque<u8, 4> = Deque::deserialize(input).unwrap();
+/// assert_eq!(deque.as_slices().0, [1, 2, 3]);
+///
+/// let input = SeqDeserializer::<_, Error>::new([1, 2, 3].into_iter());
+/// assert!(Deque::<u8, 2>::deserialize(input).is_err());
+/// ```
+impl<'de, T: Deserialize<'de>, const CAPACITY: usize> Deserialize<'de> for Deque<T, CAPACITY> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut deque = Self::new();
+        deserialize_into(&mut deque, deserializer)?;
+        Ok(deque)
+    }
+}
+
+/// Deserializes a sequence, appending its elements to the back of `deque`.
+///
+/// This decodes directly into existing storage, e.g. a [`DequeView`] or a
+/// `static` deque, without a temporary container. If the deque runs out of
+/// space, this returns an error; the elements decoded up to that point are
+/// left in the deque.
+///
+/// [`DequeView`]: crate::DequeView
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::{de::deserialize_into, Deque};
+/// use serde::de::value::{Error, SeqDeserializer};
+///
+/// let mut deque: Deque<u16, 4> = Deque::new();
+/// deque.push_back(0).unwrap();
+///
+/// let input = SeqDeserializer::<_, Error>::new([1u16, 2].into_iter());
+/// deserialize_into(&mut deque, input).unwrap();
+/// assert_eq!(deque.as_slices().0, [0, 1, 2]);
+///
+/// let input = SeqDeserializer::<_, Error>::new([3u16, 4].into_iter());
+/// assert!(deserialize_into(&mut deque, input).is_err());
+/// assert_eq!(deque.len(), 3);
+/// ```
+pub fn deserialize_into<'de, T, S, D>(
+    deque: &mut DequeBase<T, S>,
+    deserializer: D,
+) -> Result<(), D::Error>
+where
+    T: Deserialize<'de>,
+    S: Storage<T>,
+    D: Deserializer<'de>,
+{
+    Append::new(deque).deserialize(deserializer)
+}
+
+/// A [`DeserializeSeed`] that appends the elements of a sequence to a deque.
+///
+/// This is what [`deserialize_into`] uses; it can also be used directly to
+/// decode a deque nested inside another value, e.g. with
+/// [`SeqAccess::next_element_seed`].
+pub struct Append<'a, T, S: Storage<T>> {
+    deque: &'a mut DequeBase<T, S>,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T, S: Storage<T>> Append<'a, T, S> {
+    /// Creates a seed that appends to `deque`.
+    pub fn new(deque: &'a mut DequeBase<T, S>) -> Self {
+        Self {
+            deque,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>, S: Storage<T>> DeserializeSeed<'de> for Append<'_, T, S> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, T: Deserialize<'de>, S: Storage<T>> Visitor<'de> for Append<'_, T, S> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "a sequence of at most {} elements",
+            self.deque.capacity() - self.deque.len()
+        )
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let free = self.deque.capacity() - self.deque.len();
+        if let Some(hint) = seq.size_hint() {
+            if hint > free {
+                return Err(A::Error::invalid_length(hint, &self));
+            }
+        }
+
+        let mut count = 0;
+        while let Some(element) = seq.next_element()? {
+            if self.deque.push_back(element).is_err() {
+                return Err(A::Error::invalid_length(count + 1, &self));
+            }
+            count += 1;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,222 @@
+//! A history of numeric samples, stored as the differences between them.
+
+use core::iter::FusedIterator;
+
+use crate::{
+    iter::Iter,
+    varint::{decode, encode, zigzag_decode, zigzag_encode, MAX_LEN},
+    Deque,
+};
+
+/// The most bytes the difference between two `i32`s takes as a varint.
+const MAX_DELTA_LEN: usize = 5;
+
+/// A ring of `i32` samples that stores each one as its difference from the
+/// previous sample, in `N` bytes.
+///
+/// Differences are [varints](crate::varint), so a slowly changing series
+/// (a temperature, a pressure, a counter) takes a byte or two per sample
+/// instead of four. When there's no room for a new sample, the oldest ones
+/// are dropped to make room, so the ring always holds as much recent
+/// history as fits.
+///
+/// Samples are decoded one after another when iterating, so reading is
+/// sequential from the oldest sample.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::delta::DeltaRing;
+///
+/// // 32 bytes hold the last 33 samples, where a `[i32; 8]` holds 8.
+/// let mut history: DeltaRing<32> = DeltaRing::new();
+/// for i in 0..100 {
+///     history.push(2150 + i % 7);
+/// }
+/// assert_eq!(history.len(), 33);
+/// assert_eq!(history.back(), Some(2150 + 99 % 7));
+///
+/// let average = history.iter().sum::<i32>() / history.len() as i32;
+/// assert_eq!(average, 2153);
+/// ```
+pub struct DeltaRing<const N: usize> {
+    /// The differences between consecutive samples, after the first.
+    deltas: Deque<u8, N>,
+
+    /// The oldest sample, if `len > 0`.
+    first: i32,
+
+    /// The newest sample, if `len > 0`.
+    last: i32,
+
+    len: usize,
+}
+
+impl<const N: usize> DeltaRing<N> {
+    /// Creates an empty ring.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is less than 5, the most bytes a difference can take.
+    pub const fn new() -> Self {
+        assert!(N >= MAX_DELTA_LEN, "DeltaRing needs at least 5 bytes");
+        Self {
+            deltas: Deque::new(),
+            first: 0,
+            last: 0,
+            len: 0,
+        }
+    }
+
+    /// The number of samples in the ring.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the ring contains no samples.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of bytes used to store the differences.
+    pub fn bytes_used(&self) -> usize {
+        self.deltas.len()
+    }
+
+    /// Removes all samples.
+    pub fn clear(&mut self) {
+        self.deltas.clear();
+        self.len = 0;
+    }
+
+    /// Returns the oldest sample.
+    pub fn front(&self) -> Option<i32> {
+        (self.len > 0).then(|| self.first)
+    }
+
+    /// Returns the newest sample.
+    pub fn back(&self) -> Option<i32> {
+        (self.len > 0).then(|| self.last)
+    }
+
+    /// Appends a sample, dropping the oldest ones if there isn't room for
+    /// it. Returns the number of samples dropped.
+    pub fn push(&mut self, value: i32) -> usize {
+        if self.len == 0 {
+            self.first = value;
+            self.last = value;
+            self.len = 1;
+            return 0;
+        }
+        let delta = i64::from(value) - i64::from(self.last);
+        let mut buf = [0; MAX_LEN];
+        let len = encode(zigzag_encode(delta), &mut buf);
+        let encoded = &buf[..len];
+
+        let mut dropped = 0;
+        while N - self.deltas.len() < encoded.len() {
+            // There are at least two samples, since a lone sample has no
+            // differences and the ring has room for any one.
+            self.pop_front();
+            dropped += 1;
+        }
+        self.deltas.push_back_slice(encoded);
+        self.last = value;
+        self.len += 1;
+        dropped
+    }
+
+    /// Removes the oldest sample and returns it.
+    pub fn pop_front(&mut self) -> Option<i32> {
+        let value = self.front()?;
+        if self.len > 1 {
+            let delta = self
+                .deltas
+                .read_varint_zigzag()
+                .ok()
+                .flatten()
+                .expect("corrupt delta");
+            self.first = (i64::from(self.first) + delta) as i32;
+        }
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Returns an iterator over the samples, oldest first.
+    pub fn iter(&self) -> DeltaIter<'_> {
+        DeltaIter {
+            deltas: self.deltas.iter(),
+            next: self.first,
+            remaining: self.len,
+        }
+    }
+}
+
+impl<const N: usize> Default for DeltaRing<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Clone for DeltaRing<N> {
+    fn clone(&self) -> Self {
+        Self {
+            deltas: self.deltas.clone(),
+            first: self.first,
+            last: self.last,
+            len: self.len,
+        }
+    }
+}
+
+impl<'a, const N: usize> IntoIterator for &'a DeltaRing<N> {
+    type Item = i32;
+    type IntoIter = DeltaIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the samples of a [`DeltaRing`], oldest first.
+///
+/// Returned by [`DeltaRing::iter`].
+pub struct DeltaIter<'a> {
+    deltas: Iter<'a, u8>,
+
+    /// The sample to return next, if `remaining > 0`.
+    next: i32,
+    remaining: usize,
+}
+
+impl Iterator for DeltaIter<'_> {
+    type Item = i32;
+
+    fn next(&mut self) -> Option<i32> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let value = self.next;
+        self.remaining -= 1;
+        if self.remaining > 0 {
+            let mut buf = [0; MAX_DELTA_LEN];
+            let mut len = 0;
+            for (slot, &byte) in buf.iter_mut().zip(self.deltas.clone()) {
+                *slot = byte;
+                len += 1;
+            }
+            let (delta, len) = decode(&buf[..len]).ok().flatten().expect("corrupt delta");
+            self.deltas.nth(len - 1);
+            self.next = (i64::from(value) + zigzag_decode(delta)) as i32;
+        }
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for DeltaIter<'_> {}
+
+impl FusedIterator for DeltaIter<'_> {}
@@ -0,0 +1,235 @@
+//! Scaffolding for driving DMA channels from ring buffers.
+
+use core::cell::UnsafeCell;
+
+use crate::Deque;
+
+/// A transmit buffer that is sent in blocks of at most `BLOCK` elements, one
+/// DMA transfer at a time.
+///
+/// The main loop [`write`](Self::write)s data and calls
+/// [`start_next`](Self::start_next) to kick off a transfer if the channel is
+/// idle. The DMA completion interrupt calls
+/// [`transfer_complete`](Self::transfer_complete), which removes the block
+/// that was sent and starts the next one. Both take a closure that starts a
+/// transfer of the given block.
+///
+/// Each block is contiguous in memory, so blocks are shorter than `BLOCK`
+/// where the data wraps around the end of the buffer, or runs out. A block
+/// stays at the same address, unmodified, until its transfer is complete,
+/// as long as the feeder itself isn't moved (e.g. because it's in a
+/// `static`). To share the feeder between the main loop and the interrupt,
+/// put it in a mutex such as `critical_section::Mutex<RefCell<_>>`.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::dma::ChunkFeeder;
+///
+/// let mut tx: ChunkFeeder<u8, 16, 4> = ChunkFeeder::new();
+/// let mut sent = Vec::new();
+///
+/// tx.write(b"hello world");
+/// assert!(tx.start_next(|block| sent.push(block.to_vec())));
+///
+/// // In the DMA interrupt:
+/// while tx.transfer_complete(|block| sent.push(block.to_vec())) {}
+///
+/// assert_eq!(sent, [&b"hell"[..], b"o wo", b"rld"]);
+/// assert!(tx.is_empty());
+/// ```
+pub struct ChunkFeeder<T, const N: usize, const BLOCK: usize> {
+    data: Deque<T, N>,
+
+    /// The length of the block at the front that is being transferred, or
+    /// zero if the channel is idle.
+    in_flight: usize,
+}
+
+impl<T: Copy, const N: usize, const BLOCK: usize> ChunkFeeder<T, N, BLOCK> {
+    /// Creates an empty feeder.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `BLOCK` is zero.
+    pub const fn new() -> Self {
+        assert!(BLOCK > 0, "block size must not be zero");
+        Self {
+            data: Deque::new(),
+            in_flight: 0,
+        }
+    }
+
+    /// The number of elements waiting to be sent, including the block being
+    /// transferred.
+    pub const fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if there is nothing left to send.
+    pub const fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns `true` if a transfer is in progress.
+    pub const fn is_busy(&self) -> bool {
+        self.in_flight > 0
+    }
+
+    /// Appends as many elements from `data` as fit, returning how many were
+    /// appended.
+    ///
+    /// This never touches the block being transferred.
+    pub fn write(&mut self, data: &[T]) -> usize {
+        self.data.push_back_slice(data)
+    }
+
+    /// Starts transferring the next block by calling `start` with it, unless
+    /// a transfer is already in progress or there is nothing to send.
+    ///
+    /// Returns `true` if a transfer was started.
+    pub fn start_next(&mut self, start: impl FnOnce(&[T])) -> bool {
+        if self.is_busy() {
+            return false;
+        }
+        let front = self.data.as_slices().0;
+        let block = &front[..front.len().min(BLOCK)];
+        if block.is_empty() {
+            return false;
+        }
+        self.in_flight = block.len();
+        start(block);
+        true
+    }
+
+    /// Removes the block that was being transferred, and starts the next one
+    /// (see [`start_next`](Self::start_next)).
+    ///
+    /// Call this from the DMA completion interrupt. Returns `true` if
+    /// another transfer was started.
+    pub fn transfer_complete(&mut self, start: impl FnOnce(&[T])) -> bool {
+        self.data.advance_front(self.in_flight);
+        self.in_flight = 0;
+        self.start_next(start)
+    }
+}
+
+impl<T: Copy, const N: usize, const BLOCK: usize> Default for ChunkFeeder<T, N, BLOCK> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A double buffer for a circular DMA channel that fills one half while the
+/// other is being read, as with the half-transfer and transfer-complete
+/// interrupts on STM32-style DMA controllers.
+///
+/// Point the DMA channel at [`as_mut_ptr`](Self::as_mut_ptr) with a length
+/// of `2 * N` elements in circular mode, and call
+/// [`swap_from_isr`](Self::swap_from_isr) from both the half-transfer and
+/// transfer-complete interrupts. The half that was just filled can then be
+/// read with [`read_completed`](Self::read_completed) while the DMA writes to
+/// the other one.
+///
+/// The buffer must not be moved while the DMA channel is running, e.g.
+/// because it's in a `static`.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::dma::PingPong;
+///
+/// let mut adc: PingPong<u16, 4> = PingPong::new(0);
+/// assert_eq!(adc.current_write_half(), 0);
+///
+/// // Simulate the DMA filling the first half.
+/// let dma = adc.as_mut_ptr();
+/// for i in 0..4 {
+///     unsafe { dma.add(i).write(i as u16) };
+/// }
+/// // Half-transfer interrupt:
+/// assert!(adc.swap_from_isr());
+/// assert_eq!(adc.current_write_half(), 1);
+///
+/// let sum = adc.read_completed(|half| half.iter().sum::<u16>());
+/// assert_eq!(sum, Some(6));
+/// assert_eq!(adc.read_completed(|_| ()), None);
+/// ```
+pub struct PingPong<T, const N: usize> {
+    buf: UnsafeCell<[[T; N]; 2]>,
+
+    /// The half that the DMA is writing to.
+    active: usize,
+
+    /// Whether the other half has been filled and not yet read.
+    ready: bool,
+
+    /// Whether a completed half was overwritten before it was read.
+    overrun: bool,
+}
+
+impl<T: Copy, const N: usize> PingPong<T, N> {
+    /// Creates a double buffer with every element set to `init`.
+    pub const fn new(init: T) -> Self {
+        Self {
+            buf: UnsafeCell::new([[init; N]; 2]),
+            active: 0,
+            ready: false,
+            overrun: false,
+        }
+    }
+
+    /// A pointer to the start of the whole `2 * N` element buffer, for
+    /// configuring the DMA channel.
+    pub fn as_mut_ptr(&self) -> *mut T {
+        self.buf.get().cast()
+    }
+
+    /// The half (0 or 1) that the DMA is currently writing to.
+    pub const fn current_write_half(&self) -> usize {
+        self.active
+    }
+
+    /// Marks the current half as complete and switches to the other one.
+    ///
+    /// Call this from the half-transfer and transfer-complete interrupts.
+    /// Returns `false` if the previously completed half was never read, in
+    /// which case its data has already been overwritten by the DMA; this is
+    /// also recorded for [`take_overrun`](Self::take_overrun).
+    pub fn swap_from_isr(&mut self) -> bool {
+        let ok = !self.ready;
+        self.overrun |= !ok;
+        self.ready = true;
+        self.active ^= 1;
+        ok
+    }
+
+    /// Returns `true` if a completed half is waiting to be read.
+    pub const fn is_ready(&self) -> bool {
+        self.ready
+    }
+
+    /// Calls `f` with the most recently completed half and marks it as read,
+    /// or returns `None` if there is no unread half.
+    ///
+    /// The DMA must be finished with `f` before it completes the next half,
+    /// otherwise it will start overwriting the data that `f` is reading.
+    pub fn read_completed<R>(&mut self, f: impl FnOnce(&[T; N]) -> R) -> Option<R> {
+        if !self.ready {
+            return None;
+        }
+        let half = self.active ^ 1;
+        // Safety: The DMA only writes to the active half, and the completed
+        // half isn't reachable through any other reference while `self` is
+        // borrowed mutably.
+        let result = f(unsafe { &(*self.buf.get())[half] });
+        self.ready = false;
+        Some(result)
+    }
+
+    /// Returns `true` if any completed half was overwritten before it was
+    /// read since the last call, and clears the flag.
+    pub fn take_overrun(&mut self) -> bool {
+        core::mem::replace(&mut self.overrun, false)
+    }
+}
@@ -0,0 +1,247 @@
+//! Removing many elements at once.
+//!
+//! # Panic safety
+//!
+//! An element's `Drop` implementation or a user closure may panic partway
+//! through one of these operations. When that happens, no element is dropped
+//! twice, and the deque is left valid:
+//!
+//! - [`clear`](DequeBase::clear) and
+//!   [`advance_front`](DequeBase::advance_front) still drop every element
+//!   they were removing, and leave the deque without them.
+//! - [`retain`](DequeBase::retain) keeps the element the closure panicked on
+//!   and every element after it, in their original order, along with the
+//!   elements that were already kept. An element whose drop panicked has
+//!   been removed.
+//! - [`Drain`] leaves the elements it hasn't returned yet in the deque.
+//! - [`ExtractIf`] behaves like `retain`, keeping the element the predicate
+//!   panicked on and every element after it.
+
+use core::iter::FusedIterator;
+
+use crate::{DequeBase, Storage};
+
+/// A draining iterator that removes elements from the front of a deque.
+///
+/// Elements that aren't returned are removed when the iterator is dropped.
+/// If dropping one of them panics, the rest stay in the deque.
+///
+/// Returned by [`DequeBase::drain`].
+pub struct Drain<'a, T, S: Storage<T>> {
+    deque: &'a mut DequeBase<T, S>,
+}
+
+impl<T, S: Storage<T>> Iterator for Drain<'_, T, S> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.deque.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.deque.len(), Some(self.deque.len()))
+    }
+}
+
+impl<T, S: Storage<T>> DoubleEndedIterator for Drain<'_, T, S> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.deque.pop_back()
+    }
+}
+
+impl<T, S: Storage<T>> ExactSizeIterator for Drain<'_, T, S> {}
+
+impl<T, S: Storage<T>> FusedIterator for Drain<'_, T, S> {}
+
+impl<T, S: Storage<T>> Drop for Drain<'_, T, S> {
+    fn drop(&mut self) {
+        // Each element is removed before it is dropped, so a panic leaves
+        // only the elements after it.
+        while self.deque.pop_front().is_some() {}
+    }
+}
+
+/// An iterator that removes and returns the elements that match a
+/// predicate, keeping the rest in order.
+///
+/// If the iterator is dropped before it is finished, the elements it hasn't
+/// looked at yet are kept.
+///
+/// Returned by [`DequeBase::extract_if`].
+pub struct ExtractIf<'a, T, S: Storage<T>, F> {
+    guard: RetainGuard<'a, T, S>,
+    pred: F,
+}
+
+impl<T, S: Storage<T>, F: FnMut(&mut T) -> bool> Iterator for ExtractIf<'_, T, S, F> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Like `retain_mut`, but returns the removed elements.
+        while self.guard.remaining > 0 {
+            let matched = match self.guard.deque.front_mut() {
+                Some(front) => (self.pred)(front),
+                None => break,
+            };
+            self.guard.remaining -= 1;
+            if matched {
+                return self.guard.deque.pop_front();
+            }
+            rotate_one(self.guard.deque);
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.guard.remaining))
+    }
+}
+
+impl<T, S: Storage<T>, F: FnMut(&mut T) -> bool> FusedIterator for ExtractIf<'_, T, S, F> {}
+
+/// Moves the elements that `retain` hasn't looked at yet back behind the
+/// kept elements, including during unwinding, so the order is preserved.
+struct RetainGuard<'a, T, S: Storage<T>> {
+    deque: &'a mut DequeBase<T, S>,
+    remaining: usize,
+}
+
+impl<T, S: Storage<T>> Drop for RetainGuard<'_, T, S> {
+    fn drop(&mut self) {
+        for _ in 0..self.remaining {
+            rotate_one(self.deque);
+        }
+    }
+}
+
+/// Moves the front element to the back.
+fn rotate_one<T, S: Storage<T>>(deque: &mut DequeBase<T, S>) {
+    if let Some(value) = deque.pop_front() {
+        // There is room for it, since it was just removed.
+        let _ = deque.push_back(value);
+    }
+}
+
+impl<T, S: Storage<T>> DequeBase<T, S> {
+    /// Returns an iterator that removes elements from the front of the deque.
+    ///
+    /// Any elements that remain when the iterator is dropped are removed
+    /// too.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<i32, 4> = Deque::new();
+    /// d.push_back(1).unwrap();
+    /// d.push_back(2).unwrap();
+    /// d.push_back(3).unwrap();
+    ///
+    /// let mut drain = d.drain();
+    /// assert_eq!(drain.next(), Some(1));
+    /// drop(drain);
+    /// assert!(d.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, T, S> {
+        Drain { deque: self }
+    }
+
+    /// Returns an iterator that removes and returns the elements for which
+    /// `pred` returns `true`, leaving the rest in order.
+    ///
+    /// `pred` is called once for each element, from front to back, as the
+    /// iterator is advanced.
+    ///
+    /// # Examples
+    ///
+    /// Pulling expired entries out of a timer queue:
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut timers: Deque<(u32, char), 8> = Deque::new();
+    /// for timer in [(30, 'a'), (10, 'b'), (50, 'c'), (20, 'd')] {
+    ///     timers.push_back(timer).unwrap();
+    /// }
+    ///
+    /// let now = 25;
+    /// let mut expired = timers.extract_if(|&mut (deadline, _)| deadline <= now);
+    /// assert_eq!(expired.next(), Some((10, 'b')));
+    /// assert_eq!(expired.next(), Some((20, 'd')));
+    /// assert_eq!(expired.next(), None);
+    /// drop(expired);
+    ///
+    /// assert!(timers.iter().eq(&[(30, 'a'), (50, 'c')]));
+    /// ```
+    pub fn extract_if<F: FnMut(&mut T) -> bool>(&mut self, pred: F) -> ExtractIf<'_, T, S, F> {
+        ExtractIf {
+            guard: RetainGuard {
+                remaining: self.len(),
+                deque: self,
+            },
+            pred,
+        }
+    }
+
+    /// Keeps only the elements for which `f` returns `true`, in order.
+    ///
+    /// See the [module docs](crate::drain#panic-safety) for what happens if
+    /// `f` or a drop panics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<i32, 8> = Deque::new();
+    /// for i in 1..=6 {
+    ///     d.push_back(i).unwrap();
+    /// }
+    /// d.retain(|&x| x % 2 == 0);
+    /// assert!(d.iter().eq(&[2, 4, 6]));
+    /// ```
+    pub fn retain(&mut self, mut f: impl FnMut(&T) -> bool) {
+        self.retain_mut(|x| f(x));
+    }
+
+    /// Keeps only the elements for which `f` returns `true`, in order,
+    /// letting `f` modify them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<i32, 8> = Deque::new();
+    /// for i in 1..=4 {
+    ///     d.push_back(i).unwrap();
+    /// }
+    /// d.retain_mut(|x| {
+    ///     *x *= 10;
+    ///     *x > 20
+    /// });
+    /// assert!(d.iter().eq(&[30, 40]));
+    /// ```
+    pub fn retain_mut(&mut self, mut f: impl FnMut(&mut T) -> bool) {
+        // Each element is looked at while it is at the front, then moved to
+        // the back if it is kept. The guard moves the rest to the back if
+        // `f` or a drop panics.
+        let mut guard = RetainGuard {
+            remaining: self.len(),
+            deque: self,
+        };
+        while guard.remaining > 0 {
+            let keep = match guard.deque.front_mut() {
+                Some(front) => f(front),
+                None => break,
+            };
+            guard.remaining -= 1;
+            if keep {
+                rotate_one(guard.deque);
+            } else {
+                guard.deque.pop_front();
+            }
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Deficit round-robin multiplexing of byte streams.
+
+use crate::Deque;
+
+/// A deficit round-robin multiplexer merging `K` byte queues of `N` bytes
+/// into a single output stream.
+///
+/// Each flow has its own [`Deque<u8, N>`](Deque) that can be filled
+/// independently (e.g. one per socket), and a quantum: the number of bytes it
+/// may send each time it is visited in the round-robin. Reading from the
+/// multiplexer visits the flows in order, letting each one send up to its
+/// quantum (plus any unused allowance it accumulated while it was in service)
+/// before moving on to the next, so a busy flow cannot starve the others.
+///
+/// Flows that are empty when visited are skipped and forfeit their remaining
+/// deficit, as in classic DRR.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::DrrMux;
+///
+/// let mut mux: DrrMux<2, 16> = DrrMux::new([2, 1]);
+/// for &b in b"aaaaaa" {
+///     mux.push(0, b).unwrap();
+/// }
+/// for &b in b"bbb" {
+///     mux.push(1, b).unwrap();
+/// }
+///
+/// let mut out = [0; 9];
+/// assert_eq!(mux.read(&mut out), 9);
+/// assert_eq!(&out, b"aabaabaab");
+/// ```
+pub struct DrrMux<const K: usize, const N: usize> {
+    /// The per-flow byte queues.
+    flows: [Deque<u8, N>; K],
+
+    /// The number of bytes each flow may send per round.
+    quanta: [usize; K],
+
+    /// The number of bytes each flow may still send in the current round.
+    deficits: [usize; K],
+
+    /// The flow currently being visited by the round-robin.
+    current: usize,
+
+    /// Whether the current flow has already been granted its quantum for this
+    /// visit.
+    in_service: bool,
+}
+
+impl<const K: usize, const N: usize> DrrMux<K, N> {
+    /// Creates a multiplexer with empty flows and the given per-flow quanta.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the quanta is zero.
+    pub fn new(quanta: [usize; K]) -> Self {
+        assert!(
+            quanta.iter().all(|&quantum| quantum != 0),
+            "Flow quanta must be non-zero"
+        );
+        Self {
+            flows: [(); K].map(|_| Deque::new()),
+            quanta,
+            deficits: [0; K],
+            current: 0,
+            in_service: false,
+        }
+    }
+
+    /// The number of flows this multiplexer merges.
+    pub const fn flows(&self) -> usize {
+        K
+    }
+
+    /// Provides a reference to the queue of the given flow.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `flow >= K`.
+    pub fn flow(&self, flow: usize) -> &Deque<u8, N> {
+        &self.flows[flow]
+    }
+
+    /// Provides a mutable reference to the queue of the given flow.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `flow >= K`.
+    pub fn flow_mut(&mut self, flow: usize) -> &mut Deque<u8, N> {
+        &mut self.flows[flow]
+    }
+
+    /// Appends a byte to the given flow, returning it back if that flow is
+    /// full.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `flow >= K`.
+    pub fn push(&mut self, flow: usize, byte: u8) -> Result<(), u8> {
+        self.flows[flow].push_back(byte)
+    }
+
+    /// The total number of bytes pending in all flows.
+    pub fn len(&self) -> usize {
+        self.flows.iter().map(Deque::len).sum()
+    }
+
+    /// Returns `true` if no flow has any pending bytes.
+    pub fn is_empty(&self) -> bool {
+        self.flows.iter().all(Deque::is_empty)
+    }
+
+    /// Removes the next byte of the output stream, returning it along with
+    /// the flow it came from, or `None` if all flows are empty.
+    pub fn pop_with_flow(&mut self) -> Option<(usize, u8)> {
+        if self.is_empty() {
+            // Idle: nobody keeps their allowance until the next round.
+            self.deficits = [0; K];
+            self.in_service = false;
+            return None;
+        }
+
+        // Terminates: at least one flow is non-empty, and it will be granted
+        // a non-zero quantum when the round-robin reaches it.
+        loop {
+            let flow = self.current;
+            if !self.in_service {
+                if self.flows[flow].is_empty() {
+                    self.deficits[flow] = 0;
+                    self.advance();
+                    continue;
+                }
+                self.deficits[flow] += self.quanta[flow];
+                self.in_service = true;
+            }
+
+            if self.deficits[flow] > 0 {
+                if let Some(byte) = self.flows[flow].pop_front() {
+                    self.deficits[flow] -= 1;
+                    return Some((flow, byte));
+                }
+            }
+
+            // Either the allowance is used up or the flow ran dry; an empty
+            // flow forfeits whatever allowance it had left.
+            if self.flows[flow].is_empty() {
+                self.deficits[flow] = 0;
+            }
+            self.advance();
+        }
+    }
+
+    /// Removes the next byte of the output stream, or returns `None` if all
+    /// flows are empty.
+    pub fn pop(&mut self) -> Option<u8> {
+        self.pop_with_flow().map(|(_, byte)| byte)
+    }
+
+    /// Fills `buf` with bytes from the output stream, returning the number of
+    /// bytes written.
+    ///
+    /// Fewer than `buf.len()` bytes are written only if all flows run empty.
+    pub fn read(&mut self, buf: &mut [u8]) -> usize {
+        for (count, slot) in buf.iter_mut().enumerate() {
+            match self.pop() {
+                Some(byte) => *slot = byte,
+                None => return count,
+            }
+        }
+        buf.len()
+    }
+
+    /// Moves the round-robin on to the next flow.
+    fn advance(&mut self) {
+        self.current = (self.current + 1) % K;
+        self.in_service = false;
+    }
+}
@@ -0,0 +1,150 @@
+//! Reading and writing fixed-size numbers in byte deques.
+//!
+//! A number that straddles the end of the storage is split across both
+//! slices of the deque. These methods copy it out (or in) a byte slice at a
+//! time, so the caller never has to reassemble it by hand.
+
+use crate::{DequeBase, Storage};
+
+/// The order of the bytes of a number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ByteOrder {
+    /// Least significant byte first.
+    Little,
+    /// Most significant byte first, also known as network byte order.
+    Big,
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A primitive number that can be read from or written to a byte deque.
+///
+/// This is implemented for all of the integer and floating-point types
+/// except `usize` and `isize`, whose size depends on the target.
+pub trait Number: Copy + sealed::Sealed {
+    #[doc(hidden)]
+    type Bytes: AsRef<[u8]> + AsMut<[u8]> + Default;
+
+    #[doc(hidden)]
+    fn to_bytes(self, order: ByteOrder) -> Self::Bytes;
+
+    #[doc(hidden)]
+    fn from_bytes(bytes: Self::Bytes, order: ByteOrder) -> Self;
+}
+
+macro_rules! impl_number {
+    ($($ty:ty),*) => {
+        $(
+            impl sealed::Sealed for $ty {}
+
+            impl Number for $ty {
+                type Bytes = [u8; core::mem::size_of::<$ty>()];
+
+                fn to_bytes(self, order: ByteOrder) -> Self::Bytes {
+                    match order {
+                        ByteOrder::Little => self.to_le_bytes(),
+                        ByteOrder::Big => self.to_be_bytes(),
+                    }
+                }
+
+                fn from_bytes(bytes: Self::Bytes, order: ByteOrder) -> Self {
+                    match order {
+                        ByteOrder::Little => Self::from_le_bytes(bytes),
+                        ByteOrder::Big => Self::from_be_bytes(bytes),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_number!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl<S: Storage<u8>> DequeBase<u8, S> {
+    /// Removes a number from the front of the deque, or returns `None`
+    /// without removing anything if the deque doesn't hold all of its bytes
+    /// yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::{endian::ByteOrder, Deque};
+    ///
+    /// let mut rx: Deque<u8, 8> = Deque::new();
+    /// rx.push_back_slice(&[0x00, 0x00, 0x80, 0x3f, 0x12]);
+    /// assert_eq!(rx.read_num::<f32>(ByteOrder::Little), Some(1.0));
+    /// assert_eq!(rx.read_num::<u16>(ByteOrder::Big), None);
+    /// assert_eq!(rx.len(), 1);
+    /// ```
+    pub fn read_num<N: Number>(&mut self, order: ByteOrder) -> Option<N> {
+        let mut bytes = N::Bytes::default();
+        if self.len() < bytes.as_ref().len() {
+            return None;
+        }
+        self.pop_front_slice(bytes.as_mut());
+        Some(N::from_bytes(bytes, order))
+    }
+
+    /// Appends a number to the deque.
+    ///
+    /// Returns `Err(value)` without writing anything if the deque doesn't
+    /// have room for all of its bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::{endian::ByteOrder, Deque};
+    ///
+    /// let mut tx: Deque<u8, 4> = Deque::new();
+    /// tx.write_num(0x1234u16, ByteOrder::Big).unwrap();
+    /// assert_eq!(tx.as_slices().0, &[0x12, 0x34]);
+    /// assert_eq!(tx.write_num(7u32, ByteOrder::Big), Err(7));
+    /// ```
+    pub fn write_num<N: Number>(&mut self, value: N, order: ByteOrder) -> Result<(), N> {
+        let bytes = value.to_bytes(order);
+        if self.capacity() - self.len() < bytes.as_ref().len() {
+            return Err(value);
+        }
+        self.push_back_slice(bytes.as_ref());
+        Ok(())
+    }
+}
+
+macro_rules! accessors {
+    ($($ty:ty: $read:ident, $write:ident, $order:ident, $name:literal;)*) => {
+        impl<S: Storage<u8>> DequeBase<u8, S> {
+            $(
+                #[doc = concat!("Removes a ", $name, " `", stringify!($ty), "` from the front of the deque.")]
+                ///
+                /// See [`read_num`](Self::read_num).
+                pub fn $read(&mut self) -> Option<$ty> {
+                    self.read_num(ByteOrder::$order)
+                }
+
+                #[doc = concat!("Appends a ", $name, " `", stringify!($ty), "` to the deque.")]
+                ///
+                /// See [`write_num`](Self::write_num).
+                pub fn $write(&mut self, value: $ty) -> Result<(), $ty> {
+                    self.write_num(value, ByteOrder::$order)
+                }
+            )*
+        }
+    };
+}
+
+accessors! {
+    u16: read_u16_le, write_u16_le, Little, "little-endian";
+    u16: read_u16_be, write_u16_be, Big, "big-endian";
+    u32: read_u32_le, write_u32_le, Little, "little-endian";
+    u32: read_u32_be, write_u32_be, Big, "big-endian";
+    u64: read_u64_le, write_u64_le, Little, "little-endian";
+    u64: read_u64_be, write_u64_be, Big, "big-endian";
+    i16: read_i16_le, write_i16_le, Little, "little-endian";
+    i16: read_i16_be, write_i16_be, Big, "big-endian";
+    i32: read_i32_le, write_i32_le, Little, "little-endian";
+    i32: read_i32_be, write_i32_be, Big, "big-endian";
+    i64: read_i64_le, write_i64_le, Little, "little-endian";
+    i64: read_i64_be, write_i64_be, Big, "big-endian";
+}
@@ -0,0 +1,235 @@
+//! Weighted fair queuing across several fixed-capacity queues.
+
+use crate::Deque;
+
+/// Fixed-point scale applied to virtual finish times.
+///
+/// A unit of cost served from a class with weight `w` advances that class's
+/// virtual clock by `WFQ_SCALE / w`, so larger scales give finer resolution
+/// between weights that are close together.
+const WFQ_SCALE: u64 = 1 << 16;
+
+/// A weighted fair queuing scheduler multiplexing `K` queues of `N` elements.
+///
+/// Each queue (a "class") is assigned a non-zero weight. When several classes
+/// have pending elements, they are served in proportion to their weights: a
+/// class with weight 2 gets twice the share of a class with weight 1.
+///
+/// Every element carries a cost (1 by default, or e.g. a packet length via
+/// [`enqueue_with_cost`](Self::enqueue_with_cost)), and is stamped with a
+/// virtual finish time when it is enqueued. [`dequeue`](Self::dequeue) always
+/// yields the element with the earliest finish time, using the self-clocked
+/// approximation of WFQ (the virtual time is the finish time of the element
+/// most recently dequeued).
+///
+/// Virtual times use wrapping arithmetic, so the scheduler can run
+/// indefinitely without overflowing.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::FairScheduler;
+///
+/// // Class 0 gets 3/4 of the slots, class 1 gets 1/4.
+/// let mut sched: FairScheduler<char, 2, 8> = FairScheduler::new([3, 1]);
+/// for _ in 0..4 {
+///     sched.enqueue(0, 'a').unwrap();
+///     sched.enqueue(1, 'b').unwrap();
+/// }
+///
+/// let mut order = [' '; 4];
+/// for slot in &mut order {
+///     *slot = sched.dequeue().unwrap().1;
+/// }
+/// assert_eq!(order, ['a', 'a', 'a', 'b']);
+/// ```
+pub struct FairScheduler<T, const K: usize, const N: usize> {
+    /// Pending elements of each class, tagged with their virtual finish time.
+    queues: [Deque<(u64, T), N>; K],
+
+    /// The weight of each class.
+    weights: [u32; K],
+
+    /// The virtual finish time of the most recently enqueued element of each
+    /// class.
+    last_finish: [u64; K],
+
+    /// The current virtual time, i.e. the finish time of the element that was
+    /// dequeued most recently.
+    virtual_time: u64,
+}
+
+impl<T, const K: usize, const N: usize> FairScheduler<T, K, N> {
+    /// Creates an empty scheduler with the given per-class weights.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the weights is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::FairScheduler;
+    ///
+    /// let sched: FairScheduler<u32, 3, 16> = FairScheduler::new([4, 2, 1]);
+    /// assert!(sched.is_empty());
+    /// ```
+    pub fn new(weights: [u32; K]) -> Self {
+        assert!(
+            weights.iter().all(|&weight| weight != 0),
+            "Class weights must be non-zero"
+        );
+        Self {
+            queues: [(); K].map(|_| Deque::new()),
+            weights,
+            last_finish: [0; K],
+            virtual_time: 0,
+        }
+    }
+
+    /// The number of classes this scheduler multiplexes.
+    pub const fn classes(&self) -> usize {
+        K
+    }
+
+    /// The maximum number of elements each class can hold.
+    pub const fn class_capacity(&self) -> usize {
+        N
+    }
+
+    /// The weight of the given class.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `class >= K`.
+    pub fn weight(&self, class: usize) -> u32 {
+        self.weights[class]
+    }
+
+    /// The number of elements pending in the given class.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `class >= K`.
+    pub fn class_len(&self, class: usize) -> usize {
+        self.queues[class].len()
+    }
+
+    /// The total number of elements pending in all classes.
+    pub fn len(&self) -> usize {
+        self.queues.iter().map(Deque::len).sum()
+    }
+
+    /// Returns `true` if no class has any pending elements.
+    pub fn is_empty(&self) -> bool {
+        self.queues.iter().all(Deque::is_empty)
+    }
+
+    /// Appends an element with unit cost to the given class.
+    ///
+    /// Returns the element back if that class is full.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `class >= K`.
+    pub fn enqueue(&mut self, class: usize, value: T) -> Result<(), T> {
+        self.enqueue_with_cost(class, 1, value)
+    }
+
+    /// Appends an element with the given cost (e.g. its size in bytes) to the
+    /// given class.
+    ///
+    /// Returns the element back if that class is full.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `class >= K`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::FairScheduler;
+    ///
+    /// // Equal weights, but class 0 sends packets 4x as large:
+    /// let mut sched: FairScheduler<&str, 2, 8> = FairScheduler::new([1, 1]);
+    /// sched.enqueue_with_cost(0, 400, "big").unwrap();
+    /// sched.enqueue_with_cost(0, 400, "big").unwrap();
+    /// for _ in 0..4 {
+    ///     sched.enqueue_with_cost(1, 100, "small").unwrap();
+    /// }
+    ///
+    /// assert_eq!(sched.dequeue(), Some((1, "small")));
+    /// assert_eq!(sched.dequeue(), Some((1, "small")));
+    /// assert_eq!(sched.dequeue(), Some((1, "small")));
+    /// assert_eq!(sched.dequeue(), Some((0, "big")));
+    /// ```
+    pub fn enqueue_with_cost(&mut self, class: usize, cost: u32, value: T) -> Result<(), T> {
+        if self.queues[class].is_full() {
+            return Err(value);
+        }
+
+        // A class that has been idle restarts from the current virtual time,
+        // rather than claiming credit for the time it was not competing.
+        let start = if tag_before(self.last_finish[class], self.virtual_time) {
+            self.virtual_time
+        } else {
+            self.last_finish[class]
+        };
+        let finish =
+            start.wrapping_add(u64::from(cost) * WFQ_SCALE / u64::from(self.weights[class]));
+
+        self.last_finish[class] = finish;
+        self.queues[class]
+            .push_back((finish, value))
+            .map_err(|(_, value)| value)
+    }
+
+    /// Removes the next element to be served, returning it along with the
+    /// class it was enqueued to, or `None` if all classes are empty.
+    pub fn dequeue(&mut self) -> Option<(usize, T)> {
+        let class = self.next_class()?;
+        let (finish, value) = self.queues[class].pop_front()?;
+        self.virtual_time = finish;
+        Some((class, value))
+    }
+
+    /// Provides a reference to the next element to be served, along with the
+    /// class it was enqueued to, or `None` if all classes are empty.
+    pub fn peek(&self) -> Option<(usize, &T)> {
+        let class = self.next_class()?;
+        self.queues[class].front().map(|(_, value)| (class, value))
+    }
+
+    /// Removes all pending elements and resets the virtual clock.
+    pub fn clear(&mut self) {
+        for queue in &mut self.queues {
+            queue.clear();
+        }
+        self.last_finish = [0; K];
+        self.virtual_time = 0;
+    }
+
+    /// The class whose head element has the earliest finish time.
+    ///
+    /// Ties are broken in favor of the lowest class index.
+    fn next_class(&self) -> Option<usize> {
+        let mut best: Option<(usize, u64)> = None;
+        for (class, queue) in self.queues.iter().enumerate() {
+            if let Some(&(finish, _)) = queue.front() {
+                match best {
+                    Some((_, best_finish)) if !tag_before(finish, best_finish) => {}
+                    _ => best = Some((class, finish)),
+                }
+            }
+        }
+        best.map(|(class, _)| class)
+    }
+}
+
+/// Compares two virtual times, accounting for wraparound.
+///
+/// Returns `true` if `a` is strictly earlier than `b`, assuming the two are
+/// less than half of the `u64` range apart.
+fn tag_before(a: u64, b: u64) -> bool {
+    (a.wrapping_sub(b) as i64) < 0
+}
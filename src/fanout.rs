@@ -0,0 +1,159 @@
+//! A buffer that keeps each item until every consumer has acknowledged it.
+
+use crate::Deque;
+
+/// Identifies a consumer of a [`FanOut`].
+///
+/// Returned by [`FanOut::register`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConsumerId(usize);
+
+/// A buffer of up to `N` items delivered to up to `K` consumers, where an
+/// item's slot is only reclaimed once every registered consumer has
+/// [acknowledged](Self::ack) it.
+///
+/// Each consumer reads the items it hasn't acknowledged yet with
+/// [`pending`](Self::pending), and acknowledges them in order once they
+/// have been delivered, e.g. when a link confirms it received them. Until
+/// then they stay pending, so they can be sent again after a timeout,
+/// which gives at-least-once delivery. The buffer fills up when the slowest
+/// consumer falls `N` items behind.
+///
+/// A newly registered consumer starts at the oldest item still in the
+/// buffer. Items pushed while no consumer is registered are kept for the
+/// first one.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::fanout::FanOut;
+///
+/// let mut fanout: FanOut<u32, 2, 8> = FanOut::new();
+/// let radio = fanout.register().unwrap();
+/// let uart = fanout.register().unwrap();
+///
+/// fanout.push(1).unwrap();
+/// fanout.push(2).unwrap();
+///
+/// // The UART link delivered both; the radio only the first.
+/// assert_eq!(fanout.ack(uart, 2), 2);
+/// assert_eq!(fanout.ack(radio, 1), 1);
+/// assert_eq!(fanout.len(), 1);
+///
+/// // The radio still has to deliver item 2.
+/// assert!(fanout.pending(radio).eq([&2]));
+/// assert_eq!(fanout.pending(uart).len(), 0);
+/// ```
+pub struct FanOut<T, const K: usize, const N: usize> {
+    items: Deque<T, N>,
+
+    /// The number of items (from the front) each consumer has acknowledged,
+    /// or `None` if the consumer isn't registered.
+    acked: [Option<usize>; K],
+}
+
+impl<T, const K: usize, const N: usize> FanOut<T, K, N> {
+    /// Creates an empty buffer with no consumers.
+    pub const fn new() -> Self {
+        Self {
+            items: Deque::new(),
+            acked: [None; K],
+        }
+    }
+
+    /// The maximum number of items the buffer can hold.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// The number of items in the buffer.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if the buffer contains no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns `true` if the buffer is at capacity.
+    pub fn is_full(&self) -> bool {
+        self.items.is_full()
+    }
+
+    /// Adds a consumer, or returns `None` if there are already `K`.
+    pub fn register(&mut self) -> Option<ConsumerId> {
+        let index = self.acked.iter().position(Option::is_none)?;
+        self.acked[index] = Some(0);
+        Some(ConsumerId(index))
+    }
+
+    /// Removes a consumer, reclaiming the items only it was holding up.
+    ///
+    /// Its ID may be handed out again by [`register`](Self::register).
+    pub fn unregister(&mut self, id: ConsumerId) {
+        self.acked[id.0] = None;
+        self.reclaim();
+    }
+
+    /// Appends an item for every consumer.
+    ///
+    /// Returns `Err(value)` if the buffer is full.
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        self.items.push_back(value)
+    }
+
+    fn acked(&self, id: ConsumerId) -> usize {
+        self.acked[id.0].expect("not registered")
+    }
+
+    /// Returns an iterator over the items a consumer hasn't acknowledged,
+    /// oldest first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` was unregistered.
+    pub fn pending(
+        &self,
+        id: ConsumerId,
+    ) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator + '_ {
+        self.items.iter().skip(self.acked(id))
+    }
+
+    /// Acknowledges up to `count` of a consumer's oldest pending items,
+    /// returning how many were acknowledged.
+    ///
+    /// Items every consumer has acknowledged are removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` was unregistered.
+    pub fn ack(&mut self, id: ConsumerId, count: usize) -> usize {
+        let acked = self.acked(id);
+        let count = count.min(self.items.len() - acked);
+        self.acked[id.0] = Some(acked + count);
+        self.reclaim();
+        count
+    }
+
+    /// Removes the items every registered consumer has acknowledged.
+    fn reclaim(&mut self) {
+        let done = match self.acked.iter().flatten().min() {
+            Some(&done) => done,
+            None => return,
+        };
+        if done == 0 {
+            return;
+        }
+        self.items.advance_front(done);
+        for acked in self.acked.iter_mut().flatten() {
+            *acked -= done;
+        }
+    }
+}
+
+impl<T, const K: usize, const N: usize> Default for FanOut<T, K, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
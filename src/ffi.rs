@@ -0,0 +1,179 @@
+//! A C API for byte deques.
+//!
+//! This lets C code in a mixed-language firmware share byte deques with the
+//! Rust side. The declarations are in `include/fullhouse.h`.
+//!
+//! A [`FullhouseRing`] is a [`DequeView<u8>`](crate::DequeView) over a
+//! buffer provided by the caller. C code allocates the handle itself (its
+//! size is fixed), initializes it with [`fullhouse_ring_init`], and then
+//! only accesses it through these functions. Rust code can access the same
+//! ring with [`FullhouseRing::as_deque`].
+//!
+//! # Examples
+//!
+//! ```
+//! use core::mem::MaybeUninit;
+//! use fullhouse::ffi::*;
+//!
+//! let mut buf = [0u8; 8];
+//! let mut ring = MaybeUninit::<FullhouseRing>::uninit();
+//! unsafe {
+//!     let ring = ring.as_mut_ptr();
+//!     fullhouse_ring_init(ring, buf.as_mut_ptr(), buf.len());
+//!     assert_eq!(fullhouse_ring_write(ring, b"hello".as_ptr(), 5), 5);
+//!
+//!     let mut out = [0u8; 8];
+//!     assert_eq!(fullhouse_ring_read(ring, out.as_mut_ptr(), out.len()), 5);
+//!     assert_eq!(&out[..5], b"hello");
+//!
+//!     // Empty buffers may be null.
+//!     assert_eq!(fullhouse_ring_write(ring, core::ptr::null(), 0), 0);
+//! }
+//! ```
+
+use core::{
+    mem::{align_of, size_of, MaybeUninit},
+    slice,
+};
+
+use crate::DequeView;
+
+/// An opaque handle to a byte deque, with the size and alignment of five
+/// `uintptr_t`s.
+#[repr(C)]
+pub struct FullhouseRing {
+    opaque: [usize; 5],
+}
+
+const _: () = assert!(size_of::<FullhouseRing>() == size_of::<DequeView<'static, u8>>());
+const _: () = assert!(align_of::<FullhouseRing>() == align_of::<DequeView<'static, u8>>());
+
+impl FullhouseRing {
+    /// Returns the deque behind an initialized handle.
+    ///
+    /// # Safety
+    ///
+    /// The handle must have been initialized with [`fullhouse_ring_init`],
+    /// and its buffer must still be valid.
+    pub unsafe fn as_deque(&mut self) -> &mut DequeView<'static, u8> {
+        &mut *(self as *mut Self).cast::<DequeView<'static, u8>>()
+    }
+}
+
+/// Like [`slice::from_raw_parts`], but allows `data` to be null (or
+/// otherwise invalid) when `len` is zero, as C callers expect.
+unsafe fn slice_or_empty<'a, T>(data: *const T, len: usize) -> &'a [T] {
+    if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(data, len)
+    }
+}
+
+/// Like [`slice::from_raw_parts_mut`], but allows `data` to be null (or
+/// otherwise invalid) when `len` is zero, as C callers expect.
+unsafe fn slice_or_empty_mut<'a, T>(data: *mut T, len: usize) -> &'a mut [T] {
+    if len == 0 {
+        &mut []
+    } else {
+        slice::from_raw_parts_mut(data, len)
+    }
+}
+
+/// Initializes `ring` as an empty deque using the `len` bytes at `buf` as
+/// storage.
+///
+/// # Safety
+///
+/// - `ring` must be valid for writes.
+/// - `buf` must be valid for reads and writes of `len` bytes for as long as
+///   the ring is used, and not be accessed other than through the ring. It
+///   may be null if `len` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn fullhouse_ring_init(ring: *mut FullhouseRing, buf: *mut u8, len: usize) {
+    let storage = slice_or_empty_mut(buf.cast::<MaybeUninit<u8>>(), len);
+    ring.cast::<DequeView<'static, u8>>()
+        .write(DequeView::new(storage));
+}
+
+/// Appends as many of the `len` bytes at `data` as fit, returning the number
+/// of bytes appended.
+///
+/// # Safety
+///
+/// `ring` must be initialized, and `data` must be valid for reads of `len`
+/// bytes. `data` may be null if `len` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn fullhouse_ring_write(
+    ring: *mut FullhouseRing,
+    data: *const u8,
+    len: usize,
+) -> usize {
+    (*ring)
+        .as_deque()
+        .push_back_slice(slice_or_empty(data, len))
+}
+
+/// Removes up to `len` bytes from the front of the ring into `out`,
+/// returning the number of bytes removed.
+///
+/// # Safety
+///
+/// `ring` must be initialized, and `out` must be valid for writes of `len`
+/// bytes. `out` may be null if `len` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn fullhouse_ring_read(
+    ring: *mut FullhouseRing,
+    out: *mut u8,
+    len: usize,
+) -> usize {
+    (*ring)
+        .as_deque()
+        .pop_front_slice(slice_or_empty_mut(out, len))
+}
+
+/// Copies up to `len` bytes from the front of the ring into `out` without
+/// removing them, returning the number of bytes copied.
+///
+/// # Safety
+///
+/// `ring` must be initialized, and `out` must be valid for writes of `len`
+/// bytes. `out` may be null if `len` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn fullhouse_ring_peek(
+    ring: *mut FullhouseRing,
+    out: *mut u8,
+    len: usize,
+) -> usize {
+    (*ring).as_deque().read_at(0, slice_or_empty_mut(out, len))
+}
+
+/// The number of bytes in the ring.
+///
+/// # Safety
+///
+/// `ring` must be initialized.
+#[no_mangle]
+pub unsafe extern "C" fn fullhouse_ring_len(ring: *mut FullhouseRing) -> usize {
+    (*ring).as_deque().len()
+}
+
+/// The maximum number of bytes the ring can hold.
+///
+/// # Safety
+///
+/// `ring` must be initialized.
+#[no_mangle]
+pub unsafe extern "C" fn fullhouse_ring_capacity(ring: *mut FullhouseRing) -> usize {
+    (*ring).as_deque().capacity()
+}
+
+/// Removes all bytes from the ring.
+///
+/// # Safety
+///
+/// `ring` must be initialized.
+#[no_mangle]
+pub unsafe extern "C" fn fullhouse_ring_clear(ring: *mut FullhouseRing) {
+    (*ring).as_deque().clear()
+}
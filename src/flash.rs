@@ -0,0 +1,502 @@
+//! A FIFO of records stored in NOR flash.
+
+use core::ops::Range;
+
+use embedded_storage::nor_flash::NorFlash;
+
+/// Marks a sector that is in use by a [`FlashQueue`] ("FHQ1").
+const SECTOR_MAGIC: u32 = 0x3151_4846;
+
+/// The length field of a record slot that has not been written yet.
+const ERASED_LEN: u16 = 0xffff;
+
+/// The size of the buffer used to pad unaligned reads and writes.
+///
+/// This limits the supported `READ_SIZE` and `WRITE_SIZE`.
+const SCRATCH_SIZE: usize = 32;
+
+/// Errors returned by [`FlashQueue`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlashQueueError<E> {
+    /// The flash driver reported an error.
+    Flash(E),
+
+    /// There is no free sector left to write the record to.
+    Full,
+
+    /// The record does not fit in a single sector.
+    TooLarge,
+
+    /// The buffer passed to [`pop`](FlashQueue::pop) is too small for the
+    /// next record, which has the given length. The record is not removed.
+    BufferTooSmall(usize),
+}
+
+/// A persistent FIFO of variable-length records, stored in a region of NOR
+/// flash through the [`embedded-storage`](embedded_storage) traits.
+///
+/// The region is used as a ring of erase sectors. Each sector starts with a
+/// header holding a sequence number, followed by records that each carry
+/// their length and a CRC of their contents. When the queue is opened with
+/// [`new`](Self::new), the sector headers are scanned to find the oldest and
+/// newest sectors, so the queued records survive a power loss.
+///
+/// Wear is spread evenly over the region: sectors are written in order
+/// around the ring, and each sector is erased once per pass, when all of its
+/// records have been popped (or when it is overwritten by
+/// [`push_overwriting`](Self::push_overwriting)).
+///
+/// Pops are only persisted a sector at a time, by erasing the sector. After
+/// a power loss, records that were popped from the oldest sector before it
+/// was erased are delivered again, so consumers should tolerate duplicates.
+/// Records whose write was interrupted by a power loss fail their CRC and are
+/// skipped.
+///
+/// # Examples
+///
+/// ```
+/// # use embedded_storage::nor_flash::{ErrorType, NorFlash, NorFlashErrorKind, ReadNorFlash};
+/// # struct RamFlash([u8; 4096]);
+/// # impl ErrorType for RamFlash {
+/// #     type Error = NorFlashErrorKind;
+/// # }
+/// # impl ReadNorFlash for RamFlash {
+/// #     const READ_SIZE: usize = 1;
+/// #     fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+/// #         let offset = offset as usize;
+/// #         bytes.copy_from_slice(&self.0[offset..offset + bytes.len()]);
+/// #         Ok(())
+/// #     }
+/// #     fn capacity(&self) -> usize {
+/// #         self.0.len()
+/// #     }
+/// # }
+/// # impl NorFlash for RamFlash {
+/// #     const WRITE_SIZE: usize = 4;
+/// #     const ERASE_SIZE: usize = 1024;
+/// #     fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+/// #         self.0[from as usize..to as usize].fill(0xff);
+/// #         Ok(())
+/// #     }
+/// #     fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+/// #         let offset = offset as usize;
+/// #         for (cell, byte) in self.0[offset..offset + bytes.len()].iter_mut().zip(bytes) {
+/// #             *cell &= byte;
+/// #         }
+/// #         Ok(())
+/// #     }
+/// # }
+/// use fullhouse::flash::FlashQueue;
+///
+/// let flash = RamFlash([0xff; 4096]);
+/// let mut queue = FlashQueue::new(flash, 0..4096).unwrap();
+/// queue.push(b"temp=21.5").unwrap();
+/// queue.push(b"temp=21.7").unwrap();
+///
+/// // Power loss: reopen the queue from the same flash.
+/// let flash = queue.into_inner();
+/// let mut queue = FlashQueue::new(flash, 0..4096).unwrap();
+///
+/// let mut buf = [0; 32];
+/// let len = queue.pop(&mut buf).unwrap().unwrap();
+/// assert_eq!(&buf[..len], b"temp=21.5");
+/// let len = queue.pop(&mut buf).unwrap().unwrap();
+/// assert_eq!(&buf[..len], b"temp=21.7");
+/// assert_eq!(queue.pop(&mut buf), Ok(None));
+/// ```
+pub struct FlashQueue<F> {
+    flash: F,
+
+    /// The offset of the first sector of the region.
+    base: u32,
+
+    /// The number of sectors in the region.
+    sector_count: u32,
+
+    /// The sector holding the oldest records.
+    head: u32,
+
+    /// The offset of the next record to pop, relative to the start of the
+    /// `head` sector.
+    read_offset: u32,
+
+    /// The sector that records are currently written to.
+    tail: u32,
+
+    /// The offset of the next record to write, relative to the start of the
+    /// `tail` sector.
+    ///
+    /// Zero if the `tail` sector has not been started yet (only when the
+    /// whole region is unused).
+    write_offset: u32,
+
+    /// The sequence number to give the next sector that is started.
+    next_seq: u32,
+}
+
+impl<F: NorFlash> FlashQueue<F> {
+    /// Opens the queue stored in the given range of the flash, recovering any
+    /// records left from before.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is not aligned to `F::ERASE_SIZE`, if it contains
+    /// fewer than two sectors, or if the flash's read or write size is not
+    /// supported.
+    pub fn new(flash: F, range: Range<u32>) -> Result<Self, FlashQueueError<F::Error>> {
+        let sector_size = F::ERASE_SIZE as u32;
+        assert!(
+            range.start % sector_size == 0 && range.end % sector_size == 0,
+            "Region must be aligned to erase sectors"
+        );
+        assert!(
+            range.end / sector_size >= range.start / sector_size + 2,
+            "Region must contain at least two sectors"
+        );
+        assert!(
+            F::WRITE_SIZE <= SCRATCH_SIZE && F::READ_SIZE <= SCRATCH_SIZE,
+            "Unsupported flash read or write size"
+        );
+        assert!(
+            F::WRITE_SIZE % F::READ_SIZE == 0,
+            "Unsupported flash read or write size"
+        );
+
+        let mut queue = Self {
+            flash,
+            base: range.start,
+            sector_count: (range.end - range.start) / sector_size,
+            head: 0,
+            read_offset: Self::sector_header_size(),
+            tail: 0,
+            write_offset: 0,
+            next_seq: 0,
+        };
+        queue.recover()?;
+        Ok(queue)
+    }
+
+    /// Closes the queue, returning the flash driver.
+    pub fn into_inner(self) -> F {
+        self.flash
+    }
+
+    /// Returns `true` if there are no records left to pop.
+    pub fn is_empty(&self) -> bool {
+        self.head == self.tail && (self.write_offset == 0 || self.read_offset >= self.write_offset)
+    }
+
+    /// The largest record that can be stored.
+    pub fn max_record_len(&self) -> usize {
+        let available =
+            F::ERASE_SIZE as u32 - Self::sector_header_size() - Self::record_header_size();
+        let available = available as usize / F::WRITE_SIZE * F::WRITE_SIZE;
+        available.min(usize::from(ERASED_LEN - 1))
+    }
+
+    /// Appends a record to the queue.
+    ///
+    /// Returns [`FlashQueueError::Full`] if all sectors are in use.
+    pub fn push(&mut self, data: &[u8]) -> Result<(), FlashQueueError<F::Error>> {
+        self.push_inner(data, false)
+    }
+
+    /// Appends a record to the queue, discarding the sector holding the
+    /// oldest records if all sectors are in use.
+    pub fn push_overwriting(&mut self, data: &[u8]) -> Result<(), FlashQueueError<F::Error>> {
+        self.push_inner(data, true)
+    }
+
+    /// Removes the oldest record, copying it to `buf` and returning its
+    /// length, or returns `None` if the queue is empty.
+    pub fn pop(&mut self, buf: &mut [u8]) -> Result<Option<usize>, FlashQueueError<F::Error>> {
+        let sector_size = F::ERASE_SIZE as u32;
+        loop {
+            if self.is_empty() {
+                return Ok(None);
+            }
+
+            let len = if self.read_offset + Self::record_header_size() <= sector_size {
+                let (len, crc) = self.read_record_header(self.head, self.read_offset)?;
+                if len != ERASED_LEN && self.read_offset + Self::record_size(len) <= sector_size {
+                    Some((len, crc))
+                } else {
+                    None
+                }
+            } else {
+                None
+            };
+
+            let (len, crc) = match len {
+                Some(header) => header,
+                None if self.head == self.tail => {
+                    // The rest of the sector is unreadable, and there is
+                    // nothing after it.
+                    self.read_offset = self.write_offset;
+                    continue;
+                }
+                None => {
+                    // End of the head sector; move on to the next one.
+                    self.advance_head()?;
+                    continue;
+                }
+            };
+
+            let data = buf
+                .get_mut(..usize::from(len))
+                .ok_or(FlashQueueError::BufferTooSmall(usize::from(len)))?;
+            let data_addr =
+                self.sector_addr(self.head) + self.read_offset + Self::record_header_size();
+            self.read_bytes(data_addr, data)?;
+            self.read_offset += Self::record_size(len);
+
+            if crc16(data) == crc {
+                return Ok(Some(data.len()));
+            }
+            // Otherwise, the write was interrupted; skip the record.
+        }
+    }
+
+    fn push_inner(
+        &mut self,
+        data: &[u8],
+        overwrite: bool,
+    ) -> Result<(), FlashQueueError<F::Error>> {
+        if data.len() > self.max_record_len() {
+            return Err(FlashQueueError::TooLarge);
+        }
+        let len = data.len() as u16;
+        let sector_size = F::ERASE_SIZE as u32;
+
+        if self.write_offset == 0 {
+            self.start_sector(self.tail)?;
+        }
+        if self.write_offset + Self::record_size(len) > sector_size {
+            let next = self.next_sector(self.tail);
+            if next == self.head {
+                if !overwrite {
+                    return Err(FlashQueueError::Full);
+                }
+                self.advance_head()?;
+            }
+            self.tail = next;
+            self.start_sector(next)?;
+        }
+
+        let addr = self.sector_addr(self.tail) + self.write_offset;
+        let mut header = [0; 4];
+        header[..2].copy_from_slice(&len.to_le_bytes());
+        header[2..].copy_from_slice(&crc16(data).to_le_bytes());
+        // Write the header first, so that an interrupted write still claims
+        // its space and is detected by the CRC.
+        self.write_bytes(addr, &header)?;
+        self.write_bytes(addr + Self::record_header_size(), data)?;
+        self.write_offset += Self::record_size(len);
+        Ok(())
+    }
+
+    /// Finds the head and tail sectors and the write position after opening
+    /// the queue.
+    fn recover(&mut self) -> Result<(), FlashQueueError<F::Error>> {
+        // The sectors in use form a run of consecutive sequence numbers; the
+        // head is the one whose predecessor does not continue the run.
+        let mut head: Option<(u32, u32)> = None;
+        for sector in 0..self.sector_count {
+            if let Some(seq) = self.read_sector_seq(sector)? {
+                let prev = self.read_sector_seq(self.prev_sector(sector))?;
+                if prev != Some(seq.wrapping_sub(1)) {
+                    match head {
+                        Some((_, head_seq)) if head_seq <= seq => {}
+                        _ => head = Some((sector, seq)),
+                    }
+                }
+            }
+        }
+        let (head, head_seq) = match head {
+            Some(head) => head,
+            None => return Ok(()),
+        };
+
+        let (mut tail, mut tail_seq) = (head, head_seq);
+        loop {
+            let next = self.next_sector(tail);
+            if next == head || self.read_sector_seq(next)? != Some(tail_seq.wrapping_add(1)) {
+                break;
+            }
+            tail = next;
+            tail_seq = tail_seq.wrapping_add(1);
+        }
+
+        // Skip over the records already written to the tail sector.
+        let sector_size = F::ERASE_SIZE as u32;
+        let mut offset = Self::sector_header_size();
+        while offset + Self::record_header_size() <= sector_size {
+            let (len, _) = self.read_record_header(tail, offset)?;
+            if len == ERASED_LEN {
+                break;
+            }
+            if offset + Self::record_size(len) > sector_size {
+                // Corrupt length; the rest of the sector cannot be used.
+                offset = sector_size;
+                break;
+            }
+            offset += Self::record_size(len);
+        }
+
+        self.head = head;
+        self.tail = tail;
+        self.read_offset = Self::sector_header_size();
+        self.write_offset = offset;
+        self.next_seq = tail_seq.wrapping_add(1);
+        Ok(())
+    }
+
+    /// Erases the head sector after its records have been consumed, and moves
+    /// on to the next one.
+    fn advance_head(&mut self) -> Result<(), FlashQueueError<F::Error>> {
+        self.erase_sector(self.head)?;
+        self.head = self.next_sector(self.head);
+        self.read_offset = Self::sector_header_size();
+        Ok(())
+    }
+
+    /// Prepares a sector to receive records and makes it the newest one.
+    fn start_sector(&mut self, sector: u32) -> Result<(), FlashQueueError<F::Error>> {
+        // Consumed sectors are erased right away, so this is usually a no-op;
+        // only erase if something was left over.
+        let mut header = [0; 8];
+        self.read_bytes(self.sector_addr(sector), &mut header)?;
+        if header.iter().any(|&byte| byte != 0xff) {
+            self.erase_sector(sector)?;
+        }
+
+        header[..4].copy_from_slice(&SECTOR_MAGIC.to_le_bytes());
+        header[4..].copy_from_slice(&self.next_seq.to_le_bytes());
+        self.write_bytes(self.sector_addr(sector), &header)?;
+
+        self.next_seq = self.next_seq.wrapping_add(1);
+        self.write_offset = Self::sector_header_size();
+        Ok(())
+    }
+
+    fn erase_sector(&mut self, sector: u32) -> Result<(), FlashQueueError<F::Error>> {
+        let addr = self.sector_addr(sector);
+        self.flash
+            .erase(addr, addr + F::ERASE_SIZE as u32)
+            .map_err(FlashQueueError::Flash)
+    }
+
+    /// Reads the sequence number of a sector, or `None` if it is not in use.
+    fn read_sector_seq(&mut self, sector: u32) -> Result<Option<u32>, FlashQueueError<F::Error>> {
+        let mut header = [0; 8];
+        self.read_bytes(self.sector_addr(sector), &mut header)?;
+        let magic = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+        let seq = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+        Ok(if magic == SECTOR_MAGIC {
+            Some(seq)
+        } else {
+            None
+        })
+    }
+
+    /// Reads the length and CRC of the record at `offset` in `sector`.
+    fn read_record_header(
+        &mut self,
+        sector: u32,
+        offset: u32,
+    ) -> Result<(u16, u16), FlashQueueError<F::Error>> {
+        let mut header = [0; 4];
+        self.read_bytes(self.sector_addr(sector) + offset, &mut header)?;
+        Ok((
+            u16::from_le_bytes([header[0], header[1]]),
+            u16::from_le_bytes([header[2], header[3]]),
+        ))
+    }
+
+    /// Reads `out.len()` bytes starting at the `READ_SIZE`-aligned `addr`.
+    fn read_bytes(&mut self, addr: u32, out: &mut [u8]) -> Result<(), FlashQueueError<F::Error>> {
+        let aligned = out.len() / F::READ_SIZE * F::READ_SIZE;
+        let (body, rest) = out.split_at_mut(aligned);
+        self.flash
+            .read(addr, body)
+            .map_err(FlashQueueError::Flash)?;
+        if !rest.is_empty() {
+            let mut scratch = [0; SCRATCH_SIZE];
+            let scratch = &mut scratch[..F::READ_SIZE];
+            self.flash
+                .read(addr + aligned as u32, scratch)
+                .map_err(FlashQueueError::Flash)?;
+            rest.copy_from_slice(&scratch[..rest.len()]);
+        }
+        Ok(())
+    }
+
+    /// Writes `data` starting at the `WRITE_SIZE`-aligned `addr`, padding the
+    /// end with erased bytes.
+    fn write_bytes(&mut self, addr: u32, data: &[u8]) -> Result<(), FlashQueueError<F::Error>> {
+        let aligned = data.len() / F::WRITE_SIZE * F::WRITE_SIZE;
+        let (body, rest) = data.split_at(aligned);
+        if !body.is_empty() {
+            self.flash
+                .write(addr, body)
+                .map_err(FlashQueueError::Flash)?;
+        }
+        if !rest.is_empty() {
+            let mut scratch = [0xff; SCRATCH_SIZE];
+            scratch[..rest.len()].copy_from_slice(rest);
+            self.flash
+                .write(addr + aligned as u32, &scratch[..F::WRITE_SIZE])
+                .map_err(FlashQueueError::Flash)?;
+        }
+        Ok(())
+    }
+
+    fn sector_addr(&self, sector: u32) -> u32 {
+        self.base + sector * F::ERASE_SIZE as u32
+    }
+
+    fn next_sector(&self, sector: u32) -> u32 {
+        (sector + 1) % self.sector_count
+    }
+
+    fn prev_sector(&self, sector: u32) -> u32 {
+        (sector + self.sector_count - 1) % self.sector_count
+    }
+
+    /// The space taken by a sector header (magic and sequence number).
+    fn sector_header_size() -> u32 {
+        align_up(8, F::WRITE_SIZE)
+    }
+
+    /// The space taken by a record header (length and CRC).
+    fn record_header_size() -> u32 {
+        align_up(4, F::WRITE_SIZE)
+    }
+
+    /// The space taken by a record with `len` bytes of data.
+    fn record_size(len: u16) -> u32 {
+        Self::record_header_size() + align_up(usize::from(len), F::WRITE_SIZE)
+    }
+}
+
+/// Rounds `value` up to a multiple of `align`.
+fn align_up(value: usize, align: usize) -> u32 {
+    ((value + align - 1) / align * align) as u32
+}
+
+/// CRC-16/CCITT-FALSE, used to detect records that were not completely
+/// written.
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0xffff_u16;
+    for &byte in data {
+        crc ^= u16::from(byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
@@ -0,0 +1,103 @@
+//! Formatting text into byte deques.
+//!
+//! [`DequeBase<u8, S>`](DequeBase) implements [`fmt::Write`], so `write!` can
+//! be used to append text to a byte deque. By default, writing a string that
+//! doesn't fit fails with [`fmt::Error`] and leaves the deque unchanged. To
+//! discard the oldest bytes instead, write through [`Evicting`].
+
+use core::fmt;
+
+use crate::{DequeBase, Storage};
+
+/// Appends the UTF-8 encoding of each string, or fails with [`fmt::Error`] if
+/// there is not enough free space for it.
+///
+/// Each string is written entirely or not at all, so a multi-byte character
+/// is never split. A single `write!` can still be partially written, if only
+/// some of its pieces fit.
+///
+/// # Examples
+///
+/// ```
+/// use core::fmt::Write;
+/// use fullhouse::Deque;
+///
+/// let mut line: Deque<u8, 16> = Deque::new();
+/// write!(line, "t={}ms", 1234).unwrap();
+/// assert_eq!(line.as_slices().0, b"t=1234ms");
+///
+/// assert!(line.write_str("too long to fit").is_err());
+/// assert_eq!(line.len(), 8);
+/// ```
+impl<S: Storage<u8>> fmt::Write for DequeBase<u8, S> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if s.len() > self.capacity() - self.len() {
+            return Err(fmt::Error);
+        }
+        self.push_back_slice(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// A [`fmt::Write`] adapter that makes room for new text by removing the
+/// oldest bytes from the front of the deque.
+///
+/// Writing never fails. If a string is longer than the capacity of the deque,
+/// only its end is kept. Whole characters are removed, so if the deque held
+/// valid UTF-8, it still does afterwards.
+///
+/// # Examples
+///
+/// ```
+/// use core::fmt::Write;
+/// use fullhouse::{fmt::Evicting, Deque};
+///
+/// let mut log: Deque<u8, 8> = Deque::new();
+/// write!(Evicting(&mut log), "éa").unwrap();
+///
+/// // Making room for one byte removes both bytes of the 'é'.
+/// write!(Evicting(&mut log), "bcdefg").unwrap();
+///
+/// let mut text = [0; 8];
+/// let len = log.len();
+/// for (i, byte) in text.iter_mut().enumerate().take(len) {
+///     *byte = log[i];
+/// }
+/// assert_eq!(core::str::from_utf8(&text[..len]), Ok("abcdefg"));
+/// ```
+pub struct Evicting<'a, S: Storage<u8>>(pub &'a mut DequeBase<u8, S>);
+
+impl<S: Storage<u8>> fmt::Write for Evicting<'_, S> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let deque = &mut *self.0;
+        let mut bytes = s.as_bytes();
+
+        // Keep only the end of the string if it doesn't fit at all.
+        if bytes.len() > deque.capacity() {
+            bytes = &bytes[bytes.len() - deque.capacity()..];
+            while bytes.first().map_or(false, |&b| is_continuation(b)) {
+                bytes = &bytes[1..];
+            }
+            deque.clear();
+        }
+
+        // Evict whole characters from the front until there is room.
+        while bytes.len() > deque.capacity() - deque.len() {
+            deque.pop_front();
+            while deque.front().map_or(false, |&b| is_continuation(b)) {
+                deque.pop_front();
+            }
+        }
+
+        for &byte in bytes {
+            // There is enough space, made above.
+            let _ = deque.push_back(byte);
+        }
+        Ok(())
+    }
+}
+
+/// Returns `true` if `byte` is not the first byte of a UTF-8 sequence.
+fn is_continuation(byte: u8) -> bool {
+    byte & 0xC0 == 0x80
+}
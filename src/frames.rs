@@ -0,0 +1,353 @@
+//! Splitting byte deques into delimited frames.
+//!
+//! Line-based protocols (AT commands, NMEA, shells) and COBS end every frame
+//! with a delimiter byte. [`DequeBase::split_on`] iterates over the complete
+//! frames in a receive buffer without removing them, and
+//! [`DequeBase::drain_frames`] removes them, leaving any trailing partial
+//! frame in the deque until the rest of it arrives.
+//!
+//! [`LineBuffer`] wraps a receive buffer that is filled a byte at a time from
+//! a UART interrupt, and hands out the complete lines to the application.
+//!
+//! Encodings that escape the delimiter, like [COBS](crate::cobs) and
+//! [SLIP](crate::slip), decode each frame in place and return it as a
+//! [`DecodedFrame`].
+
+use core::{iter::FusedIterator, mem, ops::Deref};
+
+use crate::{search::memchr, Deque, DequeBase, Storage};
+
+/// An iterator over the complete frames in a byte deque.
+///
+/// Each frame is returned as a pair of slices, like
+/// [`as_slices`](DequeBase::as_slices), because it may wrap around the end
+/// of the storage. The delimiter is not included. Bytes after the last
+/// delimiter are not returned.
+///
+/// Returned by [`DequeBase::split_on`].
+#[derive(Debug, Clone)]
+pub struct Frames<'a> {
+    front: &'a [u8],
+    back: &'a [u8],
+    delim: u8,
+}
+
+impl<'a> Iterator for Frames<'a> {
+    type Item = (&'a [u8], &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(i) = memchr(self.delim, self.front) {
+            let frame = &self.front[..i];
+            self.front = &self.front[i + 1..];
+            return Some((frame, &[]));
+        }
+        let i = memchr(self.delim, self.back)?;
+        let frame = (self.front, &self.back[..i]);
+        self.front = &self.back[i + 1..];
+        self.back = &[];
+        Some(frame)
+    }
+}
+
+impl FusedIterator for Frames<'_> {}
+
+impl<S: Storage<u8>> DequeBase<u8, S> {
+    /// Returns an iterator over the complete frames in the deque, each ended
+    /// by `delim`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut rx: Deque<u8, 64> = Deque::new();
+    /// rx.push_back_slice(b"OK\n+CSQ: 9\nAT");
+    ///
+    /// let mut frames = rx.split_on(b'\n');
+    /// assert_eq!(frames.next(), Some((&b"OK"[..], &b""[..])));
+    /// assert_eq!(frames.next(), Some((&b"+CSQ: 9"[..], &b""[..])));
+    /// assert_eq!(frames.next(), None);
+    /// ```
+    pub fn split_on(&self, delim: u8) -> Frames<'_> {
+        let (front, back) = self.as_slices();
+        Frames { front, back, delim }
+    }
+
+    /// Removes the complete frames from the front of the deque, each ended
+    /// by `delim`, calling `f` with each one.
+    ///
+    /// The frame is passed as a pair of slices, without the delimiter. Bytes
+    /// after the last delimiter stay in the deque. Returns the number of
+    /// frames removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut rx: Deque<u8, 64> = Deque::new();
+    /// rx.push_back_slice(b"ls\npwd\nec");
+    ///
+    /// let mut total = 0;
+    /// let count = rx.drain_frames(b'\n', |a, b| total += a.len() + b.len());
+    /// assert_eq!(count, 2);
+    /// assert_eq!(total, 5);
+    /// assert_eq!(rx.as_slices().0, b"ec");
+    /// ```
+    pub fn drain_frames(&mut self, delim: u8, mut f: impl FnMut(&[u8], &[u8])) -> usize {
+        let mut count = 0;
+        let mut consumed = 0;
+        for (front, back) in self.split_on(delim) {
+            f(front, back);
+            consumed += front.len() + back.len() + 1;
+            count += 1;
+        }
+        self.advance_front(consumed);
+        count
+    }
+}
+
+/// A UART receive buffer that is filled one byte at a time and read one line
+/// at a time.
+///
+/// The interrupt handler calls [`push_from_isr`](Self::push_from_isr) with
+/// each received byte, and the application iterates over the complete lines
+/// with [`lines`](Self::lines) or [`frames`](Self::frames). A partial line at
+/// the end stays in the buffer until the rest of it arrives. To share the
+/// buffer with the interrupt handler, put it in a mutex such as
+/// `critical_section::Mutex<RefCell<_>>`.
+///
+/// Bytes that arrive while the buffer is full are dropped, which is recorded
+/// for [`take_overflow`](Self::take_overflow). If a single line fills the
+/// whole buffer, it can never be completed; call [`clear`](Self::clear) to
+/// discard it.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::frames::LineBuffer;
+///
+/// let mut rx: LineBuffer<64> = LineBuffer::new();
+/// for &byte in b"$GPGGA,1\r\n$GPRMC,2\r\n$GP" {
+///     rx.push_from_isr(byte);
+/// }
+///
+/// let mut lines = rx.lines();
+/// assert_eq!(lines.next(), Some((&b"$GPGGA,1"[..], &b""[..])));
+/// assert_eq!(lines.next(), Some((&b"$GPRMC,2"[..], &b""[..])));
+/// assert_eq!(lines.next(), None);
+///
+/// // The partial line is kept.
+/// assert_eq!(rx.len(), 3);
+/// ```
+#[derive(Clone)]
+pub struct LineBuffer<const N: usize> {
+    data: Deque<u8, N>,
+
+    /// The number of bytes at the front that have already been returned as
+    /// frames. They are removed on the next mutable access, because the
+    /// returned frames borrow them.
+    consumed: usize,
+
+    overflow: bool,
+}
+
+impl<const N: usize> LineBuffer<N> {
+    /// Creates an empty buffer.
+    pub const fn new() -> Self {
+        Self {
+            data: Deque::new(),
+            consumed: 0,
+            overflow: false,
+        }
+    }
+
+    /// The number of bytes in the buffer that haven't been returned in a
+    /// frame yet.
+    pub const fn len(&self) -> usize {
+        self.data.len() - self.consumed
+    }
+
+    /// Returns `true` if there are no bytes waiting to be read.
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes the bytes that have already been read.
+    fn compact(&mut self) {
+        self.data.advance_front(mem::replace(&mut self.consumed, 0));
+    }
+
+    /// Appends a received byte, returning `false` if the buffer is full and
+    /// the byte was dropped.
+    pub fn push_from_isr(&mut self, byte: u8) -> bool {
+        self.compact();
+        let ok = self.data.push_back(byte).is_ok();
+        self.overflow |= !ok;
+        ok
+    }
+
+    /// Returns `true` if any bytes were dropped because the buffer was full
+    /// since the last call, and clears the flag.
+    pub fn take_overflow(&mut self) -> bool {
+        mem::replace(&mut self.overflow, false)
+    }
+
+    /// Discards everything in the buffer, including any partial line.
+    pub fn clear(&mut self) {
+        self.consumed = 0;
+        self.data.clear();
+    }
+
+    /// Returns an iterator that removes the complete frames from the buffer,
+    /// each ended by `delim`.
+    ///
+    /// Frames are returned as a pair of slices, without the delimiter, like
+    /// [`DequeBase::split_on`]. A frame is removed once the iterator has
+    /// returned it; the rest stay in the buffer if the iterator is dropped
+    /// early.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::frames::LineBuffer;
+    ///
+    /// let mut rx: LineBuffer<16> = LineBuffer::new();
+    /// for &byte in b"ab\0cd\0e" {
+    ///     rx.push_from_isr(byte);
+    /// }
+    ///
+    /// assert_eq!(rx.frames(0).next(), Some((&b"ab"[..], &b""[..])));
+    /// assert_eq!(rx.frames(0).count(), 1);
+    /// assert_eq!(rx.frames(0).next(), None);
+    /// assert_eq!(rx.len(), 1);
+    /// ```
+    pub fn frames(&mut self, delim: u8) -> ReadFrames<'_> {
+        self.compact();
+        ReadFrames {
+            frames: self.data.split_on(delim),
+            consumed: &mut self.consumed,
+            strip_cr: false,
+        }
+    }
+
+    /// Returns an iterator that removes the complete lines from the buffer.
+    ///
+    /// This is [`frames(b'\n')`](Self::frames), except that a `\r` before
+    /// the `\n` is also removed.
+    pub fn lines(&mut self) -> ReadFrames<'_> {
+        ReadFrames {
+            strip_cr: true,
+            ..self.frames(b'\n')
+        }
+    }
+}
+
+impl<const N: usize> Default for LineBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An iterator that removes the complete frames from a [`LineBuffer`].
+///
+/// Returned by [`LineBuffer::frames`] and [`LineBuffer::lines`].
+#[derive(Debug)]
+pub struct ReadFrames<'a> {
+    frames: Frames<'a>,
+    consumed: &'a mut usize,
+    strip_cr: bool,
+}
+
+impl<'a> Iterator for ReadFrames<'a> {
+    type Item = (&'a [u8], &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (mut front, mut back) = self.frames.next()?;
+        *self.consumed += front.len() + back.len() + 1;
+        if self.strip_cr {
+            if let Some((b'\r', rest)) = back.split_last() {
+                back = rest;
+            } else if let (true, Some((b'\r', rest))) = (back.is_empty(), front.split_last()) {
+                front = rest;
+            }
+        }
+        Some((front, back))
+    }
+}
+
+impl FusedIterator for ReadFrames<'_> {}
+
+/// A decoded frame, borrowed from the deque it was received in.
+///
+/// Dereferences to the decoded bytes. Dropping it removes the frame from the
+/// deque.
+///
+/// Returned by [`cobs::next_frame`](crate::cobs::next_frame) and
+/// [`slip::next_frame`](crate::slip::next_frame).
+pub struct DecodedFrame<'a, S: Storage<u8>> {
+    deque: &'a mut DequeBase<u8, S>,
+
+    /// The length of the decoded data.
+    len: usize,
+
+    /// The number of bytes to remove from the deque, including the
+    /// delimiter.
+    consumed: usize,
+}
+
+impl<S: Storage<u8>> Deref for DecodedFrame<'_, S> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.deque.as_slices().0[..self.len]
+    }
+}
+
+impl<S: Storage<u8>> Drop for DecodedFrame<'_, S> {
+    fn drop(&mut self) {
+        self.deque.advance_front(self.consumed);
+    }
+}
+
+/// Finds the next complete frame ending in `delim` at the front of `deque`,
+/// and decodes it in place with `decode`, which returns the decoded length,
+/// or `None` if the frame is malformed.
+///
+/// This is the shared part of the `next_frame` functions of the encodings,
+/// which document the behavior; `too_long` and `malformed` are their errors.
+pub(crate) fn next_decoded_frame<S: Storage<u8>, E>(
+    deque: &mut DequeBase<u8, S>,
+    delim: u8,
+    decode: fn(&mut [u8]) -> Option<usize>,
+    too_long: E,
+    malformed: E,
+) -> Option<Result<DecodedFrame<'_, S>, E>> {
+    // Skip empty frames.
+    let empty = deque.iter().take_while(|&&byte| byte == delim).count();
+    deque.advance_front(empty);
+
+    let encoded_len = match deque.find_byte(delim) {
+        Some(position) => position,
+        None if deque.is_full() => {
+            deque.clear();
+            return Some(Err(too_long));
+        }
+        None => return None,
+    };
+
+    if encoded_len > deque.as_slices().0.len() {
+        deque.make_contiguous();
+    }
+    match decode(&mut deque.as_mut_slices().0[..encoded_len]) {
+        Some(len) => Some(Ok(DecodedFrame {
+            deque,
+            len,
+            consumed: encoded_len + 1,
+        })),
+        None => {
+            deque.advance_front(encoded_len + 1);
+            Some(Err(malformed))
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! A byte buffer for editing text at a cursor.
+
+/// A fixed-capacity byte buffer with a cursor, where inserting and deleting
+/// at the cursor is O(1).
+///
+/// The free space (the gap) is kept at the cursor, so edits there don't move
+/// any bytes. Moving the cursor moves the bytes between its old and new
+/// positions across the gap, so a line editor that mostly types, deletes and
+/// steps the cursor one position at a time only ever does O(1) work per
+/// keystroke.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::GapBuffer;
+///
+/// let mut line: GapBuffer<32> = GapBuffer::new();
+/// line.insert_slice(b"led 9 of");
+/// line.move_left();
+/// line.move_left();
+/// assert_eq!(line.backspace(), Some(b' '));
+/// assert_eq!(line.backspace(), Some(b'9'));
+/// line.insert_slice(b"3 ");
+/// line.move_to_end();
+/// line.insert(b'f').unwrap();
+///
+/// assert_eq!(line.make_contiguous(), b"led 3 off");
+/// ```
+#[derive(Clone)]
+pub struct GapBuffer<const N: usize> {
+    /// The text before the cursor is `data[..gap_start]`, and the text after
+    /// it is `data[gap_end..]`.
+    data: [u8; N],
+
+    /// The position of the cursor.
+    gap_start: usize,
+
+    /// The index after the end of the gap, always in `gap_start..=N`.
+    gap_end: usize,
+}
+
+impl<const N: usize> GapBuffer<N> {
+    /// Creates an empty buffer.
+    pub const fn new() -> Self {
+        Self {
+            data: [0; N],
+            gap_start: 0,
+            gap_end: N,
+        }
+    }
+
+    /// The maximum number of bytes the buffer can hold.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// The number of bytes in the buffer.
+    pub const fn len(&self) -> usize {
+        N - (self.gap_end - self.gap_start)
+    }
+
+    /// Returns `true` if the buffer is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the buffer is full.
+    pub const fn is_full(&self) -> bool {
+        self.gap_start == self.gap_end
+    }
+
+    /// The position of the cursor, in `0..=len`.
+    pub const fn cursor(&self) -> usize {
+        self.gap_start
+    }
+
+    /// Returns the text before and after the cursor.
+    pub fn as_slices(&self) -> (&[u8], &[u8]) {
+        (&self.data[..self.gap_start], &self.data[self.gap_end..])
+    }
+
+    /// Moves the cursor to the end and returns the whole text as one slice.
+    pub fn make_contiguous(&mut self) -> &[u8] {
+        self.move_to_end();
+        &self.data[..self.gap_start]
+    }
+
+    /// Removes all bytes and moves the cursor to the start.
+    pub fn clear(&mut self) {
+        self.gap_start = 0;
+        self.gap_end = N;
+    }
+
+    /// Moves the cursor to `pos`.
+    ///
+    /// This moves the bytes between the old and new positions, so it takes
+    /// time proportional to the distance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos` is greater than the length.
+    pub fn move_to(&mut self, pos: usize) {
+        assert!(pos <= self.len(), "cursor position out of bounds");
+        if pos < self.gap_start {
+            let count = self.gap_start - pos;
+            self.data
+                .copy_within(pos..self.gap_start, self.gap_end - count);
+            self.gap_start = pos;
+            self.gap_end -= count;
+        } else {
+            let count = pos - self.gap_start;
+            self.data
+                .copy_within(self.gap_end..self.gap_end + count, self.gap_start);
+            self.gap_start = pos;
+            self.gap_end += count;
+        }
+    }
+
+    /// Moves the cursor back by one byte, returning `false` if it was
+    /// already at the start.
+    pub fn move_left(&mut self) -> bool {
+        if self.gap_start == 0 {
+            return false;
+        }
+        self.move_to(self.gap_start - 1);
+        true
+    }
+
+    /// Moves the cursor forward by one byte, returning `false` if it was
+    /// already at the end.
+    pub fn move_right(&mut self) -> bool {
+        if self.gap_end == N {
+            return false;
+        }
+        self.move_to(self.gap_start + 1);
+        true
+    }
+
+    /// Moves the cursor to the start.
+    pub fn move_to_start(&mut self) {
+        self.move_to(0);
+    }
+
+    /// Moves the cursor to the end.
+    pub fn move_to_end(&mut self) {
+        self.move_to(self.len());
+    }
+
+    /// Inserts a byte at the cursor, and moves the cursor past it.
+    ///
+    /// Returns `Err(byte)` if the buffer is full.
+    pub fn insert(&mut self, byte: u8) -> Result<(), u8> {
+        if self.is_full() {
+            return Err(byte);
+        }
+        self.data[self.gap_start] = byte;
+        self.gap_start += 1;
+        Ok(())
+    }
+
+    /// Inserts as many bytes from `data` as fit at the cursor, and moves the
+    /// cursor past them, returning how many were inserted.
+    pub fn insert_slice(&mut self, data: &[u8]) -> usize {
+        let count = data.len().min(self.gap_end - self.gap_start);
+        self.data[self.gap_start..self.gap_start + count].copy_from_slice(&data[..count]);
+        self.gap_start += count;
+        count
+    }
+
+    /// Removes the byte before the cursor and returns it, or `None` if the
+    /// cursor is at the start.
+    pub fn backspace(&mut self) -> Option<u8> {
+        if self.gap_start == 0 {
+            return None;
+        }
+        self.gap_start -= 1;
+        Some(self.data[self.gap_start])
+    }
+
+    /// Removes the byte after the cursor and returns it, or `None` if the
+    /// cursor is at the end.
+    pub fn delete(&mut self) -> Option<u8> {
+        if self.gap_end == N {
+            return None;
+        }
+        self.gap_end += 1;
+        Some(self.data[self.gap_end - 1])
+    }
+}
+
+impl<const N: usize> Default for GapBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,33 @@
+//! Hex encoding into byte deques.
+//!
+//! This is for dumping binary data to a text console, e.g. by encoding
+//! straight into a UART's transmit deque.
+
+use crate::{DequeBase, Storage};
+
+const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Appends the lowercase hex encoding of `data` to `dst`, returning the
+/// number of bytes of `data` that were encoded.
+///
+/// Each byte is encoded as two digits. Only whole bytes are encoded, so if
+/// `dst` fills up, pass the rest of `data` again once it has room.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::{hex, Deque};
+///
+/// let mut console: Deque<u8, 5> = Deque::new();
+/// assert_eq!(hex::encode(&[0xde, 0xad, 0xbe], &mut console), 2);
+/// assert_eq!(console.as_slices().0, b"dead");
+/// ```
+pub fn encode<S: Storage<u8>>(data: &[u8], dst: &mut DequeBase<u8, S>) -> usize {
+    let count = data.len().min((dst.capacity() - dst.len()) / 2);
+    for &byte in &data[..count] {
+        // There is room for both digits, checked above.
+        let _ = dst.push_back(DIGITS[usize::from(byte >> 4)]);
+        let _ = dst.push_back(DIGITS[usize::from(byte & 0xf)]);
+    }
+    count
+}
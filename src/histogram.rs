@@ -0,0 +1,307 @@
+//! Counting values into fixed bins, e.g. to track latencies.
+
+use crate::varint::{decode, encode, MAX_LEN};
+
+/// The most bytes a `u32` takes as a varint.
+const MAX_U32_LEN: usize = 5;
+
+/// A histogram of `u32` values, with `BINS` bins whose upper bounds are
+/// chosen when it is created.
+///
+/// Bin `i` counts the values greater than bin `i - 1`'s bound, up to and
+/// including its own. Values above the last bound are counted separately,
+/// as [overflow](Snapshot::overflow). Counts saturate at `u32::MAX`.
+///
+/// # Examples
+///
+/// Tracking interrupt-to-task latency in microseconds:
+///
+/// ```
+/// use fullhouse::histogram::Histogram;
+///
+/// let mut latency = Histogram::new([10, 20, 50, 100, 200, 500]);
+/// for us in [4, 8, 12, 15, 18, 33, 41, 95, 150, 900] {
+///     latency.record(us);
+/// }
+///
+/// assert_eq!(latency.percentile(50), Some(20));
+/// assert_eq!(latency.percentile(90), Some(200));
+/// assert_eq!(latency.percentile(100), Some(900));
+///
+/// let snapshot = latency.snapshot();
+/// assert_eq!(snapshot.counts(), &[2, 3, 2, 1, 1, 0]);
+/// assert_eq!(snapshot.overflow(), 1);
+/// assert_eq!(snapshot.max(), Some(900));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Histogram<const BINS: usize> {
+    bounds: [u32; BINS],
+    snapshot: Snapshot<BINS>,
+}
+
+impl<const BINS: usize> Histogram<BINS> {
+    /// Creates an empty histogram with the given upper bounds for its bins.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bounds are not strictly increasing.
+    pub const fn new(bounds: [u32; BINS]) -> Self {
+        let mut i = 1;
+        while i < BINS {
+            assert!(
+                bounds[i - 1] < bounds[i],
+                "histogram bounds must be strictly increasing"
+            );
+            i += 1;
+        }
+        Self {
+            bounds,
+            snapshot: Snapshot::EMPTY,
+        }
+    }
+
+    /// Creates an empty histogram whose bins are each `width` wide, starting
+    /// at zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero, or if the last bound overflows a `u32`.
+    pub const fn linear(width: u32) -> Self {
+        assert!(width > 0, "histogram bins must not be empty");
+        let mut bounds = [0; BINS];
+        let mut i = 0;
+        while i < BINS {
+            bounds[i] = match width.checked_mul(i as u32 + 1) {
+                Some(end) => end - 1,
+                None => panic!("histogram bounds overflow"),
+            };
+            i += 1;
+        }
+        Self::new(bounds)
+    }
+
+    /// Creates an empty histogram whose bins double in width: bin `i` holds
+    /// values below `2^i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `BINS` is greater than 32.
+    pub const fn powers_of_two() -> Self {
+        assert!(BINS <= 32, "too many bins for powers of two");
+        let mut bounds = [0; BINS];
+        let mut i = 0;
+        while i < BINS {
+            bounds[i] = (1u64 << i) as u32 - 1;
+            i += 1;
+        }
+        Self::new(bounds)
+    }
+
+    /// The upper bounds of the bins.
+    pub fn bounds(&self) -> &[u32; BINS] {
+        &self.bounds
+    }
+
+    /// Counts a value.
+    pub fn record(&mut self, value: u32) {
+        let s = &mut self.snapshot;
+        let bin = self.bounds.partition_point(|&bound| bound < value);
+        let count = s.counts.get_mut(bin).unwrap_or(&mut s.overflow);
+        *count = count.saturating_add(1);
+        if s.is_empty() || value < s.min {
+            s.min = value;
+        }
+        s.max = s.max.max(value);
+        s.total = s.total.saturating_add(1);
+    }
+
+    /// The number of values recorded.
+    pub fn len(&self) -> u32 {
+        self.snapshot.total
+    }
+
+    /// Returns `true` if no values have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.snapshot.is_empty()
+    }
+
+    /// Returns the upper bound of the bin holding the `p`th percentile
+    /// value, or `None` if no values have been recorded.
+    ///
+    /// This is the smallest bound that at least `p` percent of the values
+    /// are at or below, clamped to the largest value recorded (which is
+    /// also what's returned if the percentile falls among the overflow).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is greater than 100.
+    pub fn percentile(&self, p: u8) -> Option<u32> {
+        assert!(p <= 100, "percentile out of range");
+        let s = &self.snapshot;
+        if s.is_empty() {
+            return None;
+        }
+        // The rank of the percentile value, rounding up, and at least 1.
+        let rank = ((u64::from(s.total) * u64::from(p) + 99) / 100).max(1);
+        let mut seen = 0;
+        for (&bound, &count) in self.bounds.iter().zip(&s.counts) {
+            seen += u64::from(count);
+            if seen >= rank {
+                return Some(bound.min(s.max));
+            }
+        }
+        Some(s.max)
+    }
+
+    /// Returns a copy of the counts.
+    pub fn snapshot(&self) -> Snapshot<BINS> {
+        self.snapshot.clone()
+    }
+
+    /// Returns a copy of the counts, and resets them, e.g. for reporting
+    /// once per interval.
+    pub fn take_snapshot(&mut self) -> Snapshot<BINS> {
+        core::mem::replace(&mut self.snapshot, Snapshot::EMPTY)
+    }
+
+    /// Resets the counts.
+    pub fn clear(&mut self) {
+        self.snapshot = Snapshot::EMPTY;
+    }
+}
+
+/// The counts of a [`Histogram`] at some point.
+///
+/// Snapshots can be [encoded](Self::encode) as a few bytes for sending
+/// off the device: each field is a varint, so empty and lightly used bins
+/// take a single byte.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::histogram::{Histogram, Snapshot};
+///
+/// let mut histogram: Histogram<8> = Histogram::powers_of_two();
+/// histogram.record(3);
+/// histogram.record(100);
+///
+/// let mut buf = [0; Snapshot::<8>::MAX_ENCODED_LEN];
+/// let len = histogram.snapshot().encode(&mut buf).unwrap();
+/// assert_eq!(len, 11);
+///
+/// let (decoded, _) = Snapshot::<8>::decode(&buf[..len]).unwrap();
+/// assert_eq!(decoded, histogram.snapshot());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot<const BINS: usize> {
+    counts: [u32; BINS],
+    overflow: u32,
+
+    /// The smallest and largest values, if `total > 0`.
+    min: u32,
+    max: u32,
+
+    total: u32,
+}
+
+impl<const BINS: usize> Snapshot<BINS> {
+    const EMPTY: Self = Self {
+        counts: [0; BINS],
+        overflow: 0,
+        min: 0,
+        max: 0,
+        total: 0,
+    };
+
+    /// The most bytes [`encode`](Self::encode) writes.
+    pub const MAX_ENCODED_LEN: usize = (BINS + 3) * MAX_U32_LEN;
+
+    /// The number of values counted in each bin.
+    pub fn counts(&self) -> &[u32; BINS] {
+        &self.counts
+    }
+
+    /// The number of values above the last bin's bound.
+    pub fn overflow(&self) -> u32 {
+        self.overflow
+    }
+
+    /// The smallest value recorded, or `None` if there are none.
+    pub fn min(&self) -> Option<u32> {
+        (!self.is_empty()).then(|| self.min)
+    }
+
+    /// The largest value recorded, or `None` if there are none.
+    pub fn max(&self) -> Option<u32> {
+        (!self.is_empty()).then(|| self.max)
+    }
+
+    /// The number of values recorded.
+    pub fn total(&self) -> u32 {
+        self.total
+    }
+
+    /// Returns `true` if no values were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+
+    fn fields(&self) -> impl Iterator<Item = u32> + '_ {
+        let tail = [self.overflow, self.min, self.max];
+        self.counts.iter().copied().chain(tail)
+    }
+
+    /// Writes the snapshot to the start of `buf`, returning the number of
+    /// bytes written, or `None` if it doesn't fit.
+    ///
+    /// The encoding is each bin's count, then the overflow count, the
+    /// smallest value and the largest value, each as a varint.
+    pub fn encode(&self, buf: &mut [u8]) -> Option<usize> {
+        let mut len = 0;
+        for field in self.fields() {
+            let mut varint = [0; MAX_LEN];
+            let n = encode(field.into(), &mut varint);
+            buf.get_mut(len..len + n)?.copy_from_slice(&varint[..n]);
+            len += n;
+        }
+        Some(len)
+    }
+
+    /// Reads a snapshot written by [`encode`](Self::encode) from the start
+    /// of `bytes`, returning it and the number of bytes read.
+    ///
+    /// Returns `None` if `bytes` ends early, or doesn't hold a valid
+    /// snapshot.
+    pub fn decode(bytes: &[u8]) -> Option<(Self, usize)> {
+        let mut snapshot = Self::EMPTY;
+        let mut pos = 0;
+        let mut read = || {
+            let (value, len) = decode(bytes.get(pos..)?).ok()??;
+            // Reject overlong encodings, even of values that fit in a `u32`.
+            if len > MAX_U32_LEN {
+                return None;
+            }
+            pos += len;
+            u32::try_from(value).ok()
+        };
+        for count in &mut snapshot.counts {
+            *count = read()?;
+        }
+        snapshot.overflow = read()?;
+        snapshot.min = read()?;
+        snapshot.max = read()?;
+        snapshot.total = snapshot
+            .counts
+            .iter()
+            .fold(snapshot.overflow, |total, &count| {
+                total.saturating_add(count)
+            });
+        Some((snapshot, pos))
+    }
+}
+
+impl<const BINS: usize> Default for Snapshot<BINS> {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
@@ -0,0 +1,69 @@
+//! Moving multi-channel audio frames in and out of a sample deque.
+
+use core::slice;
+
+use crate::{DequeBase, Storage};
+
+impl<T: Copy, S: Storage<T>> DequeBase<T, S> {
+    /// Appends as many whole frames of `CH` interleaved samples as fit,
+    /// returning how many frames were appended.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut samples: Deque<i16, 5> = Deque::new();
+    /// // Stereo frames of [left, right]:
+    /// assert_eq!(samples.push_frames(&[[1, -1], [2, -2], [3, -3]]), 2);
+    /// assert!(samples.iter().eq(&[1, -1, 2, -2]));
+    /// ```
+    pub fn push_frames<const CH: usize>(&mut self, frames: &[[T; CH]]) -> usize {
+        if CH == 0 {
+            return 0;
+        }
+        let count = frames.len().min((self.capacity() - self.len()) / CH);
+        // Safety: An array of arrays is laid out as one contiguous run of
+        // `T`s.
+        let samples = unsafe { slice::from_raw_parts(frames.as_ptr().cast::<T>(), count * CH) };
+        self.push_back_slice(samples);
+        count
+    }
+
+    /// Removes whole frames of `CH` interleaved samples from the front,
+    /// writing the samples of each channel into its own slice.
+    ///
+    /// Removes as many frames as are available and fit in the shortest
+    /// slice, and returns how many.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut samples: Deque<i16, 8> = Deque::new();
+    /// samples.push_frames(&[[1, -1], [2, -2], [3, -3]]);
+    ///
+    /// let mut left = [0; 2];
+    /// let mut right = [0; 4];
+    /// assert_eq!(samples.pop_deinterleaved(&mut [&mut left, &mut right]), 2);
+    /// assert_eq!(left, [1, 2]);
+    /// assert_eq!(right[..2], [-1, -2]);
+    /// assert!(samples.iter().eq(&[3, -3]));
+    /// ```
+    pub fn pop_deinterleaved<const CH: usize>(&mut self, channels: &mut [&mut [T]; CH]) -> usize {
+        if CH == 0 {
+            return 0;
+        }
+        let room = channels.iter().map(|channel| channel.len()).min();
+        let count = (self.len() / CH).min(room.unwrap_or(0));
+        let mut samples = self.iter().copied();
+        for frame in 0..count {
+            for (channel, sample) in channels.iter_mut().zip(&mut samples) {
+                channel[frame] = sample;
+            }
+        }
+        self.advance_front(count * CH);
+        count
+    }
+}
@@ -0,0 +1,160 @@
+//! Implementations of I/O traits for byte deques.
+
+use embedded_io::{BufRead, ErrorKind, ErrorType, Read, ReadReady, Write, WriteReady};
+
+#[cfg(feature = "embedded-io-async")]
+use crate::AsyncDeque;
+use crate::{DequeBase, Storage};
+
+impl<S: Storage<u8>> ErrorType for DequeBase<u8, S> {
+    type Error = ErrorKind;
+}
+
+/// Reading removes bytes from the front of the deque.
+///
+/// Nothing else can add bytes while the deque is being read from, so an
+/// empty deque is at the end of its data: reading from it returns `Ok(0)`,
+/// the same as reading from an empty `&[u8]`. Code that reads a deque as a
+/// stream while it's being refilled in between (e.g. by polling) should
+/// check [`ReadReady`] first, since many readers stop at `Ok(0)`. To wait
+/// for data, use an [`AsyncDeque`](crate::AsyncDeque) with the
+/// `embedded-io-async` feature instead.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_io::{Read, Write};
+/// use fullhouse::Deque;
+///
+/// let mut deque: Deque<u8, 8> = Deque::new();
+/// deque.write_all(b"hello").unwrap();
+///
+/// let mut buf = [0; 8];
+/// let len = deque.read(&mut buf).unwrap();
+/// assert_eq!(&buf[..len], b"hello");
+/// ```
+impl<S: Storage<u8>> Read for DequeBase<u8, S> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        Ok(self.pop_front_slice(buf))
+    }
+}
+
+/// The buffer returned by `fill_buf` is the first contiguous part of the
+/// deque, so it may be shorter than [`len`](DequeBase::len) when the contents
+/// wrap around.
+impl<S: Storage<u8>> BufRead for DequeBase<u8, S> {
+    fn fill_buf(&mut self) -> Result<&[u8], Self::Error> {
+        Ok(self.as_slices().0)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.advance_front(amt)
+    }
+}
+
+impl<S: Storage<u8>> ReadReady for DequeBase<u8, S> {
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.is_empty())
+    }
+}
+
+/// Writing appends bytes to the back of the deque.
+///
+/// Writes are short if the deque does not have room for all of `buf`. Writing
+/// to a full deque fails with [`ErrorKind::WriteZero`].
+impl<S: Storage<u8>> Write for DequeBase<u8, S> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        match self.push_back_slice(buf) {
+            0 if !buf.is_empty() => Err(ErrorKind::WriteZero),
+            count => Ok(count),
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<S: Storage<u8>> WriteReady for DequeBase<u8, S> {
+    fn write_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.is_full())
+    }
+}
+
+#[cfg(feature = "embedded-io-async")]
+impl<const N: usize, const W: usize> ErrorType for &AsyncDeque<u8, N, W> {
+    type Error = core::convert::Infallible;
+}
+
+/// Reading removes bytes from the front of the deque, waiting until there
+/// are some.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_io_async::{Read, Write};
+/// use fullhouse::AsyncDeque;
+///
+/// static UART_RX: AsyncDeque<u8, 64> = AsyncDeque::new();
+///
+/// async fn read_line(buf: &mut [u8]) -> usize {
+///     let mut rx = &UART_RX;
+///     let mut len = 0;
+///     while len < buf.len() {
+///         rx.read_exact(&mut buf[len..len + 1]).await.unwrap();
+///         len += 1;
+///         if buf[len - 1] == b'\n' {
+///             break;
+///         }
+///     }
+///     len
+/// }
+///
+/// async fn example() {
+///     // In the UART interrupt handler:
+///     (&UART_RX).write_all(b"AT\n").await.unwrap();
+///
+///     let mut line = [0; 16];
+///     let len = read_line(&mut line).await;
+///     assert_eq!(&line[..len], b"AT\n");
+/// }
+/// ```
+#[cfg(feature = "embedded-io-async")]
+impl<const N: usize, const W: usize> embedded_io_async::Read for &AsyncDeque<u8, N, W> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            self.wait_not_empty().await;
+            // Another task may have emptied the deque again in the meantime.
+            let len = self.with(|deque| deque.pop_front_slice(buf));
+            if len > 0 {
+                return Ok(len);
+            }
+        }
+    }
+}
+
+/// Writing appends bytes to the back of the deque, waiting until there is
+/// room for some.
+#[cfg(feature = "embedded-io-async")]
+impl<const N: usize, const W: usize> embedded_io_async::Write for &AsyncDeque<u8, N, W> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            self.wait_not_full().await;
+            // Another task may have filled the deque again in the meantime.
+            let len = self.with(|deque| deque.push_back_slice(buf));
+            if len > 0 {
+                return Ok(len);
+            }
+        }
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
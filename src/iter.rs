@@ -0,0 +1,271 @@
+//! Iterators over the elements of a deque.
+//!
+//! The elements of a deque are stored in up to two contiguous slices (see
+//! [`DequeBase::as_slices`]), so these iterators are built from two slice
+//! iterators. Folding (which `sum`, `for_each`, `count`, and many adapters
+//! use) runs over each slice in turn, so it optimizes as well as a loop over
+//! a slice. Skipping elements with `nth` (which `skip` uses) takes
+//! constant time.
+
+use core::{iter::FusedIterator, mem, slice};
+
+use crate::{DequeBase, Storage};
+
+/// An iterator over references to the elements of a deque, from front to
+/// back.
+///
+/// Returned by [`DequeBase::iter`].
+#[derive(Debug, Clone)]
+pub struct Iter<'a, T> {
+    front: slice::Iter<'a, T>,
+    back: slice::Iter<'a, T>,
+}
+
+impl<'a, T> Iter<'a, T> {
+    pub(crate) fn from_slices(front: &'a [T], back: &'a [T]) -> Self {
+        Self {
+            front: front.iter(),
+            back: back.iter(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(item) = self.front.next() {
+            return Some(item);
+        }
+        self.front = mem::replace(&mut self.back, [].iter());
+        self.front.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+
+    fn count(self) -> usize {
+        self.len()
+    }
+
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        // Slice iterators skip ahead in constant time.
+        let front_len = self.front.len();
+        if n < front_len {
+            return self.front.nth(n);
+        }
+        self.front = mem::replace(&mut self.back, [].iter());
+        self.front.nth(n - front_len)
+    }
+
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        let acc = self.front.fold(init, &mut f);
+        self.back.fold(acc, f)
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if let Some(item) = self.back.next_back() {
+            return Some(item);
+        }
+        self.back = mem::replace(&mut self.front, [].iter());
+        self.back.next_back()
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        let back_len = self.back.len();
+        if n < back_len {
+            return self.back.nth_back(n);
+        }
+        self.back = mem::replace(&mut self.front, [].iter());
+        self.back.nth_back(n - back_len)
+    }
+
+    fn rfold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        let acc = self.back.rfold(init, &mut f);
+        self.front.rfold(acc, f)
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {
+    fn len(&self) -> usize {
+        self.front.len() + self.back.len()
+    }
+}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// An iterator over mutable references to the elements of a deque, from
+/// front to back.
+///
+/// Returned by [`DequeBase::iter_mut`].
+#[derive(Debug)]
+pub struct IterMut<'a, T> {
+    front: slice::IterMut<'a, T>,
+    back: slice::IterMut<'a, T>,
+}
+
+impl<'a, T> IterMut<'a, T> {
+    pub(crate) fn from_slices(front: &'a mut [T], back: &'a mut [T]) -> Self {
+        Self {
+            front: front.iter_mut(),
+            back: back.iter_mut(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(item) = self.front.next() {
+            return Some(item);
+        }
+        self.front = mem::replace(&mut self.back, [].iter_mut());
+        self.front.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+
+    fn count(self) -> usize {
+        self.len()
+    }
+
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        // Slice iterators skip ahead in constant time.
+        let front_len = self.front.len();
+        if n < front_len {
+            return self.front.nth(n);
+        }
+        self.front = mem::replace(&mut self.back, [].iter_mut());
+        self.front.nth(n - front_len)
+    }
+
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        let acc = self.front.fold(init, &mut f);
+        self.back.fold(acc, f)
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if let Some(item) = self.back.next_back() {
+            return Some(item);
+        }
+        self.back = mem::replace(&mut self.front, [].iter_mut());
+        self.back.next_back()
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        let back_len = self.back.len();
+        if n < back_len {
+            return self.back.nth_back(n);
+        }
+        self.back = mem::replace(&mut self.front, [].iter_mut());
+        self.back.nth_back(n - back_len)
+    }
+
+    fn rfold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        let acc = self.back.rfold(init, &mut f);
+        self.front.rfold(acc, f)
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {
+    fn len(&self) -> usize {
+        self.front.len() + self.back.len()
+    }
+}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+impl<T, S: Storage<T>> DequeBase<T, S> {
+    /// Returns an iterator over the elements, from front to back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<u32, 4> = Deque::new();
+    /// d.push_back(2).unwrap();
+    /// d.push_back(3).unwrap();
+    /// d.push_front(1).unwrap();
+    ///
+    /// assert!(d.iter().eq(&[1, 2, 3]));
+    /// assert_eq!(d.iter().sum::<u32>(), 6);
+    ///
+    /// // Skipping takes constant time, even across the wrap-around.
+    /// assert!(d.iter().skip(2).eq(&[3]));
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (front, back) = self.as_slices();
+        Iter::from_slices(front, back)
+    }
+
+    /// Returns an iterator over mutable references to the elements, from
+    /// front to back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<u32, 4> = Deque::new();
+    /// d.push_back(1).unwrap();
+    /// d.push_back(2).unwrap();
+    ///
+    /// for x in d.iter_mut() {
+    ///     *x *= 10;
+    /// }
+    /// assert!(d.iter().eq(&[10, 20]));
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let (front, back) = self.as_mut_slices();
+        IterMut::from_slices(front, back)
+    }
+}
+
+impl<'a, T, S: Storage<T>> IntoIterator for &'a DequeBase<T, S> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, S: Storage<T>> IntoIterator for &'a mut DequeBase<T, S> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
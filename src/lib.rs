@@ -1,20 +1,259 @@
 #![no_std]
+#![cfg_attr(feature = "allocator-api", feature(allocator_api))]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+pub mod align;
+#[cfg(feature = "critical-section")]
+pub mod async_deque;
+pub mod base64;
+#[cfg(feature = "panic-blackbox")]
+pub mod blackbox;
+#[cfg(feature = "alloc")]
+pub mod boxed;
+mod bulk;
+pub mod bus;
+#[cfg(feature = "critical-section")]
+pub mod channel;
+#[cfg(feature = "alloc")]
+pub mod chunked;
+pub mod cobs;
+pub mod compact;
+pub mod compat;
+pub mod crc;
+pub mod cursor;
+#[cfg(feature = "serde")]
+pub mod de;
+pub mod delta;
+pub mod dma;
+pub mod drain;
+pub mod drr;
+pub mod endian;
+pub mod fair;
+pub mod fanout;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "embedded-storage")]
+pub mod flash;
+pub mod fmt;
+pub mod frames;
+pub mod gap;
+pub mod hex;
+pub mod histogram;
+mod interleave;
+#[cfg(feature = "embedded-io")]
+mod io;
+pub mod iter;
+#[cfg(feature = "log")]
+pub mod logger;
+mod no_panic;
+#[cfg(feature = "nb")]
+mod nonblocking;
+pub mod parse;
+pub mod persistent;
+mod placement;
+#[cfg(feature = "bytemuck")]
+mod pod;
+#[cfg(feature = "postcard")]
+pub mod postcard;
+pub mod pow2;
+pub mod priority;
+#[cfg(feature = "critical-section")]
+pub mod priority_channel;
+#[cfg(all(kani, feature = "kani"))]
+mod proofs;
+pub mod region;
+#[cfg(target_has_atomic = "ptr")]
+pub mod rtt;
+mod search;
+#[cfg(feature = "critical-section")]
+pub mod select;
+#[cfg(target_has_atomic = "ptr")]
+pub mod semaphore;
+#[cfg(feature = "serde")]
+mod ser;
+#[cfg(feature = "embedded-hal-nb")]
+pub mod serial;
+pub mod sg;
+#[cfg(feature = "critical-section")]
+pub mod shared;
+#[cfg(feature = "futures-sink")]
+pub mod sink;
+pub mod slip;
+#[cfg(target_has_atomic = "ptr")]
+pub mod slot;
+#[cfg(feature = "alloc")]
+pub mod small;
+pub mod split;
+pub mod stats;
+#[cfg(feature = "std")]
+mod std_io;
+pub mod storage;
+#[cfg(feature = "futures-core")]
+mod stream;
+#[cfg(target_has_atomic = "ptr")]
+mod sync;
+#[cfg(feature = "critical-section")]
+pub mod tagged;
+#[cfg(feature = "critical-section")]
+pub mod task;
+pub mod timed;
+#[cfg(target_has_atomic = "ptr")]
+pub mod trace;
+pub mod ttl;
+pub mod usb;
+pub mod varint;
+#[cfg(feature = "alloc")]
+mod vec;
+#[cfg(target_has_atomic = "ptr")]
+pub mod waker;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "critical-section")]
+pub use async_deque::AsyncDeque;
+#[cfg(feature = "alloc")]
+pub use boxed::BoxDeque;
+#[cfg(feature = "alloc")]
+pub use chunked::ChunkedBuffer;
+pub use compact::CompactDeque;
+pub use drr::DrrMux;
+pub use fair::FairScheduler;
+pub use gap::GapBuffer;
+pub use persistent::PersistentDeque;
+pub use pow2::Pow2Deque;
+pub use priority::KeyedPriorityQueue;
+#[cfg(target_has_atomic = "ptr")]
+pub use semaphore::Semaphore;
+#[cfg(feature = "critical-section")]
+pub use shared::{CsDeque, CsQueue};
+#[cfg(target_has_atomic = "ptr")]
+pub use slot::SlotAllocator;
+#[cfg(feature = "alloc")]
+pub use small::SmallDeque;
+pub use storage::Storage;
+#[cfg(target_has_atomic = "ptr")]
+pub use trace::Tracer;
+#[cfg(target_has_atomic = "ptr")]
+pub use waker::AtomicWaker;
 
 use core::{
-    mem::MaybeUninit,
-    ops::{Index, IndexMut},
+    marker::PhantomData,
+    mem::{self, MaybeUninit},
+    ops::{Index, IndexMut, Range},
+    ptr, slice,
 };
 
-pub struct Deque<T, const CAPACITY: usize> {
-    /// The index of the first element stored in `data`, if non-empty.
+use no_panic::NoPanic;
+
+/// The byte written over every slot whose value is popped, cleared or
+/// dropped, with the `poison-on-pop` feature.
+///
+/// Use-after-pop bugs (through a stale pointer from
+/// [`occupied_segments`](DequeBase::occupied_segments), say) then read an
+/// obviously wrong value instead of one that looks plausible. The
+/// `zero-on-pop` feature writes zeros instead, so that secrets such as keys
+/// don't linger in memory.
+///
+/// Neither feature covers the `const` builders
+/// [`without_front`](DequeBase::without_front) and
+/// [`without_back`](DequeBase::without_back).
+pub const POISON: u8 = 0xa5;
+
+/// A fixed-capacity deque whose elements are stored inline.
+///
+/// This is a [`DequeBase`] backed by a `[MaybeUninit<T>; CAPACITY]` array,
+/// so it can be created in a `const` context and placed in a `static`.
+pub type Deque<T, const CAPACITY: usize> = DequeBase<T, [MaybeUninit<T>; CAPACITY]>;
+
+/// A fixed-capacity deque over a borrowed buffer.
+///
+/// The capacity is the length of the buffer, which is only known at runtime.
+/// This lets code that uses a deque (such as a driver) be written against a
+/// single type, regardless of the buffer size that the application chose.
+///
+/// # Examples
+///
+/// ```
+/// use core::mem::MaybeUninit;
+/// use fullhouse::DequeView;
+///
+/// fn fill(deque: &mut DequeView<'_, u8>) {
+///     while deque.push_back(0xff).is_ok() {}
+/// }
+///
+/// let mut small = [MaybeUninit::uninit(); 4];
+/// let mut large = [MaybeUninit::uninit(); 64];
+///
+/// let mut a = DequeView::new(&mut small);
+/// let mut b = DequeView::new(&mut large);
+/// fill(&mut a);
+/// fill(&mut b);
+/// assert_eq!(a.len(), 4);
+/// assert_eq!(b.len(), 64);
+/// ```
+///
+/// # Separating the indexes from the buffer
+///
+/// A `DequeView` only holds the deque's indexes and a reference to the
+/// buffer, so the two can be placed in different memory regions. For
+/// example, the indexes can be kept in fast tightly-coupled memory, while a
+/// large buffer goes in slower external RAM. The header's `static` starts out
+/// uninitialized, and is linked to its buffer during initialization:
+///
+/// ```
+/// use core::{mem::MaybeUninit, ptr::addr_of_mut};
+/// use fullhouse::DequeView;
+///
+/// #[link_section = ".dtcm"]
+/// static mut SAMPLES: MaybeUninit<DequeView<'static, i16>> = MaybeUninit::uninit();
+///
+/// #[link_section = ".sdram"]
+/// static mut SAMPLE_BUF: [MaybeUninit<i16>; 65536] = [MaybeUninit::uninit(); 65536];
+///
+/// // During initialization:
+/// // Safety: Nothing else accesses these statics concurrently.
+/// let samples = unsafe {
+///     let buf = &mut *addr_of_mut!(SAMPLE_BUF);
+///     (*addr_of_mut!(SAMPLES)).write(DequeView::new(buf))
+/// };
+/// samples.push_back(-1).unwrap();
+/// assert_eq!(samples.capacity(), 65536);
+/// ```
+pub type DequeView<'a, T> = DequeBase<T, &'a mut [MaybeUninit<T>]>;
+
+/// A fixed-capacity deque over the storage `S`.
+///
+/// The deque never grows; its capacity is the size of the buffer provided by
+/// the [`Storage`]. Most code will want to use one of the aliases instead of
+/// naming this type directly:
+///
+/// - [`Deque`] stores its elements inline.
+/// - [`DequeView`] borrows its buffer from elsewhere.
+///
+/// # Panics
+///
+/// [`push_front`](Self::push_front), [`push_back`](Self::push_back),
+/// [`pop_front`](Self::pop_front), [`pop_back`](Self::pop_back),
+/// [`get`](Self::get), [`get_mut`](Self::get_mut), the methods built on them
+/// (like [`front`](Self::front)), [`len`](Self::len) and
+/// [`capacity`](Self::capacity) never panic, as long as the [`Storage`]
+/// doesn't. Safety-certified builds can use just these and link without any
+/// panic formatting code. The `no-panic` feature makes a release build fail
+/// to link if that stops being true.
+pub struct DequeBase<T, S: Storage<T>> {
+    /// The index of the first element stored in `storage`, if non-empty.
     start: usize,
 
-    /// The first index past the last element stored in `data`.
+    /// The first index past the last element stored in `storage`.
     end: usize,
 
-    /// The number of elements stored in `data`.
+    /// The number of elements stored in `storage`.
     ///
-    /// Always congruent with `end - start` modulo `CAPACITY`, in other
+    /// Always congruent with `end - start` modulo the capacity.
     len: usize,
 
     /// A circular buffer.
@@ -28,22 +267,25 @@ pub struct Deque<T, const CAPACITY: usize> {
     /// and at indexes before `end`.
     ///
     /// - If `start < end`, they are stored in-order in the range of indexes
-    /// `start..end`.
+    ///   `start..end`.
     ///
     /// - If `start > end`, they are stored in two sub-arrays with index ranges
-    /// `start..CAPACITY` and `0..end`.
+    ///   `start..capacity` and `0..end`.
     ///
     /// - If `start == end`, it is an ambiguous case, the buffer may either be
-    /// full or empty, and the `len` field should be used to disambiguate this
-    /// case.
+    ///   full or empty, and the `len` field should be used to disambiguate this
+    ///   case.
     ///
     /// In all cases, the `len` field tracks the total number of elements in
     /// all valid regions:
     ///
     /// - If `start < end`, then `len` is equal to `end - start`.
-    /// - If `start > end`, then `len` is equal to `CAPACITY + end - start`.
-    /// - If `start == end`, then `len` is either equal to `0` or `CAPACITY`.
-    data: [MaybeUninit<T>; CAPACITY],
+    /// - If `start > end`, then `len` is equal to `capacity + end - start`.
+    /// - If `start == end`, then `len` is either equal to `0` or `capacity`.
+    storage: S,
+
+    /// The deque owns the `T` values in `storage`.
+    _marker: PhantomData<T>,
 }
 
 impl<T, const CAPACITY: usize> Deque<T, CAPACITY> {
@@ -70,10 +312,247 @@ impl<T, const CAPACITY: usize> Deque<T, CAPACITY> {
             //
             // This is the same as the unstable `MaybeUninit::uninit_array()` at
             // the time of writing.
-            data: unsafe { MaybeUninit::<[MaybeUninit<T>; CAPACITY]>::uninit().assume_init() },
+            storage: unsafe { MaybeUninit::<[MaybeUninit<T>; CAPACITY]>::uninit().assume_init() },
+            _marker: PhantomData,
+        }
+    }
+
+    /// Initializes an empty deque in the memory pointed to by `ptr`.
+    ///
+    /// Unlike writing the result of [`new`](Self::new) to `ptr`, this only
+    /// writes the index fields and never constructs the (possibly large)
+    /// storage array on the stack.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be valid for writes and properly aligned. Any value already
+    /// at `ptr` is overwritten without being dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::mem::MaybeUninit;
+    /// use fullhouse::Deque;
+    ///
+    /// let mut slot = MaybeUninit::<Deque<u8, 4096>>::uninit();
+    /// let deque = unsafe {
+    ///     Deque::init_in_place(slot.as_mut_ptr());
+    ///     slot.assume_init_mut()
+    /// };
+    /// deque.push_back(1).unwrap();
+    /// # unsafe { slot.assume_init_drop() };
+    /// ```
+    pub unsafe fn init_in_place(ptr: *mut Self) {
+        // Safety: The caller guarantees that `ptr` is valid for writes.
+        // - `storage` does not need to be written, any bit pattern (including
+        //   uninitialized memory) is valid for `[MaybeUninit<T>; N]`.
+        // - `_marker` is zero-sized.
+        core::ptr::addr_of_mut!((*ptr).start).write(0);
+        core::ptr::addr_of_mut!((*ptr).end).write(0);
+        core::ptr::addr_of_mut!((*ptr).len).write(0);
+    }
+}
+
+impl<T: Copy, const CAPACITY: usize> Deque<T, CAPACITY> {
+    /// Creates a deque holding `values`, from front to back.
+    ///
+    /// This is a `const fn`, so it can build a pre-filled deque in a `const`
+    /// or `static` initializer.
+    ///
+    /// # Panics
+    ///
+    /// Panics (at compile time, in a const context) if there are more values
+    /// than the capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// static FREE_IDS: Deque<u8, 8> = Deque::from_array([1, 2, 3]);
+    /// assert_eq!(FREE_IDS.front(), Some(&1));
+    ///
+    /// const LOOKUP: Deque<u16, 4> = Deque::from_array([10, 20]).with_back(30);
+    /// assert_eq!(LOOKUP.len(), 3);
+    /// assert_eq!(LOOKUP.back(), Some(&30));
+    /// ```
+    pub const fn from_array<const M: usize>(values: [T; M]) -> Self {
+        let mut deque = Self::new();
+        let mut i = 0;
+        while i < M {
+            deque = deque.with_back(values[i]);
+            i += 1;
+        }
+        deque
+    }
+
+    /// Appends an element to the back of the deque, and returns the deque.
+    ///
+    /// This is [`push_back`](DequeBase::push_back) for `const` contexts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the deque is full.
+    pub const fn with_back(mut self, value: T) -> Self {
+        assert!(self.len < CAPACITY, "Deque is full");
+        self.storage[self.end] = MaybeUninit::new(value);
+        self.end = if self.end + 1 == CAPACITY {
+            0
+        } else {
+            self.end + 1
+        };
+        self.len += 1;
+        self
+    }
+
+    /// Prepends an element to the front of the deque, and returns the deque.
+    ///
+    /// This is [`push_front`](DequeBase::push_front) for `const` contexts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the deque is full.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// const D: Deque<u8, 4> = Deque::new().with_back(2).with_front(1);
+    /// assert_eq!(D.front(), Some(&1));
+    /// ```
+    pub const fn with_front(mut self, value: T) -> Self {
+        assert!(self.len < CAPACITY, "Deque is full");
+        self.start = if self.start == 0 {
+            CAPACITY - 1
+        } else {
+            self.start - 1
+        };
+        self.storage[self.start] = MaybeUninit::new(value);
+        self.len += 1;
+        self
+    }
+
+    /// Removes the first element, and returns the deque and the element (or
+    /// `None` if the deque is empty).
+    ///
+    /// This is [`pop_front`](DequeBase::pop_front) for `const` contexts.
+    ///
+    /// The `zero-on-pop` and `poison-on-pop` features don't apply here: the
+    /// popped value's bytes stay in the returned deque's storage, since
+    /// `const` code can't overwrite them. Use [`pop_front`](DequeBase::pop_front) for
+    /// values that must be scrubbed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// const POPPED: (Deque<u8, 4>, Option<u8>) = Deque::from_array([1, 2]).without_front();
+    /// assert_eq!(POPPED.1, Some(1));
+    /// assert_eq!(POPPED.0.len(), 1);
+    /// ```
+    pub const fn without_front(mut self) -> (Self, Option<T>) {
+        if self.len == 0 {
+            return (self, None);
+        }
+        // Safety: `start` is the index of the first valid element, and it is
+        // copied rather than moved out, since `T: Copy`.
+        let value = unsafe { self.storage[self.start].assume_init() };
+        self.start = if self.start + 1 == CAPACITY {
+            0
+        } else {
+            self.start + 1
+        };
+        self.len -= 1;
+        (self, Some(value))
+    }
+
+    /// Removes the last element, and returns the deque and the element (or
+    /// `None` if the deque is empty).
+    ///
+    /// This is [`pop_back`](DequeBase::pop_back) for `const` contexts.
+    ///
+    /// The `zero-on-pop` and `poison-on-pop` features don't apply here: the
+    /// popped value's bytes stay in the returned deque's storage, since
+    /// `const` code can't overwrite them. Use [`pop_back`](DequeBase::pop_back) for
+    /// values that must be scrubbed.
+    pub const fn without_back(mut self) -> (Self, Option<T>) {
+        if self.len == 0 {
+            return (self, None);
+        }
+        self.end = if self.end == 0 {
+            CAPACITY - 1
+        } else {
+            self.end - 1
+        };
+        // Safety: After moving `end` back, it is the index of the last valid
+        // element, and it is copied rather than moved out, since `T: Copy`.
+        let value = unsafe { self.storage[self.end].assume_init() };
+        self.len -= 1;
+        (self, Some(value))
+    }
+}
+
+impl<'a, T> DequeView<'a, T> {
+    /// Creates an empty deque over the given buffer.
+    ///
+    /// Any values already in the buffer are ignored (and not dropped).
+    pub fn new(buf: &'a mut [MaybeUninit<T>]) -> Self {
+        Self::from_storage(buf)
+    }
+}
+
+impl<T, S: Storage<T>> DequeBase<T, S> {
+    /// Creates an empty deque over the given storage.
+    ///
+    /// Any values already in the storage are ignored (and not dropped).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::mem::MaybeUninit;
+    /// use fullhouse::DequeBase;
+    ///
+    /// let mut buf = [MaybeUninit::uninit(); 8];
+    /// let mut deque: DequeBase<u8, &mut [MaybeUninit<u8>]> = DequeBase::from_storage(&mut buf);
+    /// deque.push_back(1).unwrap();
+    /// assert_eq!(deque.capacity(), 8);
+    /// ```
+    pub fn from_storage(storage: S) -> Self {
+        Self {
+            start: 0,
+            end: 0,
+            len: 0,
+            storage,
+            _marker: PhantomData,
         }
     }
 
+    /// Removes all elements from the deque, and replaces its storage with a
+    /// new one, returning the old storage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::mem::MaybeUninit;
+    /// use fullhouse::DequeView;
+    ///
+    /// let mut small = [MaybeUninit::uninit(); 2];
+    /// let mut large = [MaybeUninit::uninit(); 8];
+    ///
+    /// let mut deque = DequeView::new(&mut small);
+    /// deque.push_back(1).unwrap();
+    /// let old = deque.replace_storage(&mut large);
+    /// assert_eq!(old.len(), 2);
+    /// assert!(deque.is_empty());
+    /// assert_eq!(deque.capacity(), 8);
+    /// ```
+    pub fn replace_storage(&mut self, storage: S) -> S {
+        self.clear();
+        core::mem::replace(&mut self.storage, storage)
+    }
+
     /// The maximum number of elements this deque can hold.
     ///
     /// # Examples
@@ -84,8 +563,8 @@ impl<T, const CAPACITY: usize> Deque<T, CAPACITY> {
     /// let deque: Deque<u32, 10> = Deque::new();
     /// assert_eq!(deque.capacity(), 10);
     /// ```
-    pub const fn capacity(&self) -> usize {
-        CAPACITY
+    pub fn capacity(&self) -> usize {
+        self.storage.capacity()
     }
 
     /// The number of elements currently in the deque.
@@ -137,12 +616,15 @@ impl<T, const CAPACITY: usize> Deque<T, CAPACITY> {
     /// deque.push_front(4);
     /// assert!(deque.is_full());
     /// ```
-    pub const fn is_full(&self) -> bool {
-        self.len == CAPACITY
+    pub fn is_full(&self) -> bool {
+        self.len == self.capacity()
     }
 
     /// Clears the deque, removing all values.
     ///
+    /// If dropping a value panics, the remaining values are still dropped,
+    /// and the deque is left empty.
+    ///
     /// # Examples
     ///
     /// ```
@@ -154,30 +636,29 @@ impl<T, const CAPACITY: usize> Deque<T, CAPACITY> {
     /// assert!(deque.is_empty());
     /// ```
     pub fn clear(&mut self) {
-        // Get a list of valid indexes.
-        let indexes = self.indexes();
+        let (first, second) = self.ranges();
 
         // Reset region state:
         self.start = 0;
         self.end = 0;
         self.len = 0;
+        self.check_invariants();
 
-        // Drop all known-valid values in the array:
-        for idx in indexes {
-            // Safety: The value in the MaybeUninit must be valid.
-            // - indexes() yields an iterator of indexes that are all valid by
-            //   definition (as defined in the docstring for `data`).
-            // - indexes() yields each index only once, so the value has not
-            //   been invalidated by an earlier loop iteration.
+        // Skip going through the values entirely if dropping them does
+        // nothing (e.g. for integers).
+        if mem::needs_drop::<T>() {
+            // Safety: The values in the MaybeUninits must be valid.
+            // - ranges() returns the ranges of valid values, as defined in the
+            //   docstring for `storage`.
+            // - The ranges don't overlap, so no value is dropped twice.
             //
-            // Postcondition: The value in the MaybeUninit is invalidated.
-            // - The iterator yields each index only once - it will not be
-            //   accessed again in this loop.
-            // - The region is reset to empty immediately after the iterator is
-            //   constructed and before this loop executes, so later code
+            // Postcondition: The values in the MaybeUninits are invalidated.
+            // - The region is reset to empty before dropping, so later code
             //   (including panics) will not assume that this data is valid.
-            unsafe { self.data[idx].assume_init_drop() };
+            unsafe { drop_ranges(self.as_mut_ptr(), first.clone(), second.clone()) };
         }
+        self.scrub(first);
+        self.scrub(second);
     }
 
     /// Prepends an element to the deque.
@@ -193,18 +674,23 @@ impl<T, const CAPACITY: usize> Deque<T, CAPACITY> {
     /// assert_eq!(d.pop_front(), Some(2));
     /// ```
     pub fn push_front(&mut self, value: T) -> Result<(), T> {
-        if self.is_full() {
+        let guard = NoPanic::new();
+        let result = if self.is_full() {
             Err(value)
         } else {
             // Insert value before the beginning of the region:
-            let new_start = (self.start + CAPACITY - 1) % CAPACITY;
-            self.data[new_start].write(value);
+            let new_start = self.wrap_sub(self.start, 1);
+            // Safety: Wrapping keeps the index in the storage.
+            unsafe { self.slot_mut(new_start).write(value) };
 
             // Expand region to include new element:
             self.start = new_start;
             self.len += 1;
+            self.check_invariants();
             Ok(())
-        }
+        };
+        guard.disarm();
+        result
     }
 
     /// Appends an element to the back of the deque.
@@ -220,17 +706,22 @@ impl<T, const CAPACITY: usize> Deque<T, CAPACITY> {
     /// assert_eq!(buf.pop_back(), Some(3));
     /// ```
     pub fn push_back(&mut self, value: T) -> Result<(), T> {
-        if self.is_full() {
+        let guard = NoPanic::new();
+        let result = if self.is_full() {
             Err(value)
         } else {
             // Insert the value after the end of the region:
-            self.data[self.end].write(value);
+            // Safety: `end` is always an index in the storage.
+            unsafe { self.slot_mut(self.end).write(value) };
 
             // Expand region to include new element:
-            self.end = (self.end + 1) % CAPACITY;
+            self.end = self.wrap_add(self.end, 1);
             self.len += 1;
+            self.check_invariants();
             Ok(())
-        }
+        };
+        guard.disarm();
+        result
     }
 
     /// Removes the first element and returns it, or `None` if the deque is
@@ -250,25 +741,30 @@ impl<T, const CAPACITY: usize> Deque<T, CAPACITY> {
     /// assert_eq!(d.pop_front(), None);
     /// ```
     pub fn pop_front(&mut self) -> Option<T> {
-        if self.is_empty() {
+        let guard = NoPanic::new();
+        let result = if self.is_empty() {
             None
         } else {
             // Shrink region by 1 element from start.
             let old_start = self.start;
-            self.start = (old_start + 1) % CAPACITY;
+            self.start = self.wrap_add(old_start, 1);
             self.len -= 1;
+            self.check_invariants();
 
             // Safety: The value in the MaybeUninit must be valid.
             // - At the start of the function, it was in the valid region of the
-            //   `data` array, and is not otherwise accessed in this function.
+            //   storage, and is not otherwise accessed in this function.
             //
             // Postcondition: The value in the MaybeUninit is invalidated (it
             // has been moved).
             // - The region has already been shrunk, so later code (including
             //   panics) will not assume that this index is valid.
-            let value = unsafe { self.data[old_start].assume_init_read() };
+            let value = unsafe { self.slot(old_start).read() };
+            self.scrub(old_start..old_start + 1);
             Some(value)
-        }
+        };
+        guard.disarm();
+        result
     }
 
     /// Removes the last element from the deque and returns it, or `None` if
@@ -286,24 +782,119 @@ impl<T, const CAPACITY: usize> Deque<T, CAPACITY> {
     /// assert_eq!(buf.pop_back(), Some(3));
     /// ```
     pub fn pop_back(&mut self) -> Option<T> {
-        if self.is_empty() {
+        let guard = NoPanic::new();
+        let result = if self.is_empty() {
             None
         } else {
             // Shrink region by 1 element from end.
-            self.end = (self.end + CAPACITY - 1) % CAPACITY;
+            self.end = self.wrap_sub(self.end, 1);
             self.len -= 1;
+            self.check_invariants();
 
             // Safety: The value in the MaybeUninit must be valid.
             // - At the start of the function, it was in the valid region of the
-            //   `data` array, and is not otherwise accessed in this function.
+            //   storage, and is not otherwise accessed in this function.
             //
             // Postcondition: The value in the MaybeUninit is invalidated (it
             // has been moved)
             // - The region has already been shrunk, so later code (including
             //   panics) will not assume that this index is valid.
-            let value = unsafe { self.data[self.end].assume_init_read() };
+            let value = unsafe { self.slot(self.end).read() };
+            self.scrub(self.end..self.end + 1);
             Some(value)
+        };
+        guard.disarm();
+        result
+    }
+
+    /// Inserts an element at `index`, shifting the elements after it toward
+    /// the back.
+    ///
+    /// Whichever side of `index` has fewer elements is the one that moves,
+    /// so inserting near either end is cheap. Returns `Err(value)` if the
+    /// deque is full.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<char, 4> = Deque::new();
+    /// d.push_back('a').unwrap();
+    /// d.push_back('c').unwrap();
+    /// d.insert(1, 'b').unwrap();
+    /// assert_eq!(d.as_slices().0, &['a', 'b', 'c']);
+    /// ```
+    pub fn insert(&mut self, index: usize, value: T) -> Result<(), T> {
+        assert!(index <= self.len, "insertion index out of bounds");
+        if self.is_full() {
+            return Err(value);
+        }
+        if index < self.len - index {
+            // Move the elements before `index` one slot toward the front.
+            let old_start = self.start;
+            self.shift_toward_front(old_start, index);
+            self.start = self.wrap_sub(old_start, 1);
+        } else {
+            // Move the elements from `index` on one slot toward the back.
+            let from = self.wrap_add(self.start, index);
+            self.shift_toward_back(from, self.len - index);
+            self.end = self.wrap_add(self.end, 1);
         }
+        let slot = self.wrap_add(self.start, index);
+        // Safety: Wrapping keeps the index in the storage, and the slot was
+        // vacated above.
+        unsafe { self.slot_mut(slot).write(value) };
+        self.len += 1;
+        self.check_invariants();
+        Ok(())
+    }
+
+    /// Removes the element at `index` and returns it, or `None` if `index`
+    /// is out of bounds.
+    ///
+    /// Whichever side of `index` has fewer elements is the one that moves to
+    /// close the gap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<char, 4> = Deque::new();
+    /// d.push_back('a').unwrap();
+    /// d.push_back('b').unwrap();
+    /// d.push_back('c').unwrap();
+    /// assert_eq!(d.remove(1), Some('b'));
+    /// assert_eq!(d.remove(2), None);
+    /// assert_eq!(d.as_slices().0, &['a', 'c']);
+    /// ```
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        let slot = self.data_index(index)?;
+        // Safety: The slot is in the valid region. The value is moved out, and
+        // the slot is overwritten or removed from the region below.
+        let value = unsafe { self.slot(slot).read() };
+        let freed = if index < self.len - 1 - index {
+            // Move the elements before `index` one slot toward the back.
+            let old_start = self.start;
+            self.shift_toward_back(old_start, index);
+            self.start = self.wrap_add(old_start, 1);
+            old_start
+        } else {
+            // Move the elements after `index` one slot toward the front.
+            let next = self.wrap_add(slot, 1);
+            self.shift_toward_front(next, self.len - 1 - index);
+            self.end = self.wrap_sub(self.end, 1);
+            self.end
+        };
+        self.len -= 1;
+        self.check_invariants();
+        self.scrub(freed..freed + 1);
+        Some(value)
     }
 
     /// Provides a reference to the element at the given index.
@@ -322,12 +913,15 @@ impl<T, const CAPACITY: usize> Deque<T, CAPACITY> {
     /// assert_eq!(buf.get(1), Some(&4));
     /// ```
     pub fn get(&self, index: usize) -> Option<&T> {
-        self.data_index(index).map(|idx| {
+        let guard = NoPanic::new();
+        let result = self.data_index(index).map(|idx| {
             // Safety: The value in the MaybeUninit must be valid.
             // This is guaranteed by `data_index`, which will only return
             // `Some` if the index points to a valid, initialized element.
-            unsafe { self.data[idx].assume_init_ref() }
-        })
+            unsafe { &*self.slot(idx) }
+        });
+        guard.disarm();
+        result
     }
 
     /// Provides a mutable reference to the element at the given index.
@@ -350,12 +944,15 @@ impl<T, const CAPACITY: usize> Deque<T, CAPACITY> {
     /// assert_eq!(buf[1], 7);
     /// ```
     pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
-        self.data_index(index).map(|idx| {
+        let guard = NoPanic::new();
+        let result = self.data_index(index).map(|idx| {
             // Safety: The value in the MaybeUninit must be valid.
             // This is guaranteed by `data_index`, which will only return
             // `Some` if the index points to a valid, initialized element.
-            unsafe { self.data[idx].assume_init_mut() }
-        })
+            unsafe { &mut *self.slot_mut(idx) }
+        });
+        guard.disarm();
+        result
     }
 
     /// Provides a reference to the front element, or `None` if the deque is
@@ -442,58 +1039,373 @@ impl<T, const CAPACITY: usize> Deque<T, CAPACITY> {
         self.get_mut(self.len().wrapping_sub(1))
     }
 
-    /// Indexes of valid values in the data array, in logical order from `start`
-    /// to `end`.
-    fn indexes(&self) -> impl Iterator<Item = usize> {
-        // A bit of a workaround for the type system - some of these branches
-        // could produce a simpler type than `Chain<Range, Range>`
-        // but the function _must_ have a single return type. So, all branches
-        // create two ranges, and create additional empty ranges if needed.
-        let (first, second) = if self.is_empty() {
+    /// Returns a pair of slices which contain, in order, the contents of the
+    /// deque.
+    ///
+    /// The second slice is empty unless the contents wrap around the end of
+    /// the storage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<i32, 4> = Deque::new();
+    /// d.push_back(1);
+    /// d.push_back(2);
+    /// d.push_front(0);
+    /// assert_eq!(d.as_slices(), (&[0][..], &[1, 2][..]));
+    /// ```
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (first, second) = self.ranges();
+        let base = self.as_ptr();
+
+        // Safety: The values in the MaybeUninits must be valid.
+        // - `ranges()` only returns ranges of valid, initialized elements.
+        // - `MaybeUninit<T>` has the same layout as `T`.
+        unsafe {
+            (
+                slice::from_raw_parts(base.add(first.start), first.len()),
+                slice::from_raw_parts(base.add(second.start), second.len()),
+            )
+        }
+    }
+
+    /// Returns a pair of mutable slices which contain, in order, the contents
+    /// of the deque.
+    ///
+    /// The second slice is empty unless the contents wrap around the end of
+    /// the storage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<i32, 4> = Deque::new();
+    /// d.push_back(1);
+    /// d.push_front(0);
+    /// let (front, back) = d.as_mut_slices();
+    /// front[0] = 5;
+    /// back[0] = 6;
+    /// assert_eq!(d.as_slices(), (&[5][..], &[6][..]));
+    /// ```
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (first, second) = self.ranges();
+        let base = self.as_mut_ptr();
+
+        // Safety: The values in the MaybeUninits must be valid.
+        // - `ranges()` only returns ranges of valid, initialized elements.
+        // - `MaybeUninit<T>` has the same layout as `T`.
+        // - The ranges don't overlap, so the slices can be borrowed mutably
+        //   at the same time.
+        unsafe {
+            (
+                slice::from_raw_parts_mut(base.add(first.start), first.len()),
+                slice::from_raw_parts_mut(base.add(second.start), second.len()),
+            )
+        }
+    }
+
+    /// Rearranges the storage so that the contents are in one contiguous
+    /// slice, and returns it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<i32, 4> = Deque::new();
+    /// d.push_back(1);
+    /// d.push_back(2);
+    /// d.push_front(0);
+    /// assert_eq!(d.make_contiguous(), &[0, 1, 2]);
+    /// assert_eq!(d.as_slices(), (&[0, 1, 2][..], &[][..]));
+    /// ```
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        if self.start + self.len > self.capacity() {
+            // Moving the values around in `MaybeUninit`s is just a copy, and
+            // rotating keeps them in the same logical order.
+            let start = self.start;
+            self.storage.as_mut_slice().rotate_left(start);
+            self.start = 0;
+            self.end = self.wrap_add(0, self.len);
+            self.check_invariants();
+        }
+        self.as_mut_slices().0
+    }
+
+    /// Panics if the deque's internal state is inconsistent.
+    ///
+    /// The state can only become inconsistent through a bug in unsafe code,
+    /// such as passing too large a count to
+    /// [`advance_back`](Self::advance_back), or writing past the end of a
+    /// buffer next to the deque. Checking after the suspect code runs catches
+    /// the corruption before it turns into a use-after-free somewhere else.
+    ///
+    /// With the `debug-invariants` feature, this is checked after every
+    /// method that moves the ends of the deque.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<u8, 4> = Deque::new();
+    /// d.push_back_slice(b"abc");
+    /// d.pop_front();
+    /// d.assert_invariants();
+    /// ```
+    pub fn assert_invariants(&self) {
+        let capacity = self.capacity();
+        assert_eq!(
+            capacity,
+            self.storage.as_slice().len(),
+            "storage capacity does not match its length"
+        );
+        assert!(self.len <= capacity, "length exceeds capacity");
+        if capacity == 0 {
+            assert!(self.start == 0 && self.end == 0, "index out of bounds");
+        } else {
+            assert!(self.start < capacity, "start index out of bounds");
+            assert!(self.end < capacity, "end index out of bounds");
+        }
+        assert_eq!(
+            self.wrap_add(self.start, self.len),
+            self.end,
+            "start, end and length are inconsistent"
+        );
+    }
+
+    /// Calls [`assert_invariants`](Self::assert_invariants) if the
+    /// `debug-invariants` feature is enabled.
+    #[inline]
+    fn check_invariants(&self) {
+        #[cfg(feature = "debug-invariants")]
+        self.assert_invariants();
+    }
+
+    /// Ranges of indexes of valid values in the storage. Together, in order,
+    /// they span the logical contents from `start` to `end`.
+    fn ranges(&self) -> (Range<usize>, Range<usize>) {
+        if self.is_empty() {
             (0..0, 0..0)
         } else if self.start < self.end {
             (self.start..self.end, 0..0)
         } else {
-            (self.start..CAPACITY, 0..self.end)
-        };
-        first.chain(second)
+            (self.start..self.capacity(), 0..self.end)
+        }
+    }
+
+    /// Ranges of indexes of the first `count` values in the storage, for a
+    /// `count` of at most the length.
+    fn front_ranges(&self, count: usize) -> (Range<usize>, Range<usize>) {
+        let (first, second) = self.ranges();
+        let split = count.min(first.len());
+        (
+            first.start..first.start + split,
+            second.start..second.start + (count - split),
+        )
     }
 
-    /// Compute an index into the `data` array given the offset from `start`.
+    /// Overwrites the slots in `range` after their values have been moved
+    /// out or dropped, as described for [`scrub_range`].
+    #[inline(always)]
+    fn scrub(&mut self, range: Range<usize>) {
+        // Safety: The callers only pass ranges of slots in the storage that
+        // no longer hold values.
+        unsafe { scrub_range(self.as_mut_ptr(), range) }
+    }
+
+    /// Ranges of indexes of free slots in the storage, in the order that
+    /// [`push_back`](Self::push_back) fills them.
+    fn free_ranges(&self) -> (Range<usize>, Range<usize>) {
+        if self.is_full() {
+            (0..0, 0..0)
+        } else if self.start <= self.end {
+            (self.end..self.capacity(), 0..self.start)
+        } else {
+            (self.end..self.start, 0..0)
+        }
+    }
+
+    /// Compute an index into the storage given the offset from `start`.
     ///
     /// This is guaranteed to return an index to a valid element. If the index
     /// would point outside the valid range(s) (i.e., if `offset >= len`), this
     /// function will instead return `None`.
     fn data_index(&self, offset: usize) -> Option<usize> {
         if offset < self.len() {
-            // Check whether index wraps around the end of `data`.
-            //
-            // This check basically lets us implement `(self.start + offset) %
-            // CAPACITY` without causing any wrapping arithmetic or using
-            // modulo.
-            //
-            // I don't _think_ anyone will use this with capacities near the
-            // size limit of `usize`, but you never know.
-            let pre_wrap_size = CAPACITY - self.start;
-            if offset < pre_wrap_size {
-                Some(self.start + offset)
-            } else {
-                Some(offset - pre_wrap_size)
-            }
+            Some(self.wrap_add(self.start, offset))
         } else {
             None
         }
     }
+
+    /// A pointer to the start of the storage, valid for reads of the whole
+    /// storage until the deque is next borrowed mutably.
+    ///
+    /// The elements are only ever accessed through pointers derived from
+    /// this (or [`as_mut_ptr`](Self::as_mut_ptr)), once per operation. No
+    /// references to the `MaybeUninit` slots are created along the way, so
+    /// the only references to elements are the ones handed out, which keeps
+    /// the deque clean under Miri with strict provenance and tree borrows.
+    #[inline]
+    fn as_ptr(&self) -> *const T {
+        self.storage.as_slice().as_ptr().cast()
+    }
+
+    /// A pointer to the start of the storage, valid for reads and writes of
+    /// the whole storage until the deque is next borrowed.
+    #[inline]
+    fn as_mut_ptr(&mut self) -> *mut T {
+        self.storage.as_mut_slice().as_mut_ptr().cast()
+    }
+
+    /// A pointer to the slot at `index` in the storage, without a bounds
+    /// check.
+    ///
+    /// The indexes that the deque computes are always in bounds, so this
+    /// keeps panic branches out of pushes and pops.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than the capacity.
+    #[inline]
+    unsafe fn slot(&self, index: usize) -> *const T {
+        debug_assert!(index < self.capacity());
+        self.as_ptr().add(index)
+    }
+
+    /// A pointer to the slot at `index` in the storage, without a bounds
+    /// check.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than the capacity.
+    #[inline]
+    unsafe fn slot_mut(&mut self, index: usize) -> *mut T {
+        debug_assert!(index < self.capacity());
+        self.as_mut_ptr().add(index)
+    }
+
+    /// Moves the `count` slots starting at `from` (wrapping around the end of
+    /// the storage) one slot toward the back, overwriting the slot after
+    /// them.
+    ///
+    /// This is at most two `memmove`s and a single-slot copy, where the
+    /// region crosses the end of the storage.
+    fn shift_toward_back(&mut self, from: usize, count: usize) {
+        let capacity = self.capacity();
+        let base = self.as_mut_ptr();
+        // Move the runs of contiguous slots starting from the last one, so
+        // nothing is overwritten before it's moved.
+        let mut remaining = count;
+        while remaining > 0 {
+            let last = self.wrap_add(from, remaining - 1);
+            let run = remaining.min(last + 1);
+            let run_start = last + 1 - run;
+            // Safety: All indexes are in the storage, and `ptr::copy` allows
+            // the source and destination to overlap.
+            unsafe {
+                if last == capacity - 1 {
+                    ptr::copy_nonoverlapping(base.add(last), base, 1);
+                    ptr::copy(base.add(run_start), base.add(run_start + 1), run - 1);
+                } else {
+                    ptr::copy(base.add(run_start), base.add(run_start + 1), run);
+                }
+            }
+            remaining -= run;
+        }
+    }
+
+    /// Moves the `count` slots starting at `from` (wrapping around the end of
+    /// the storage) one slot toward the front, overwriting the slot before
+    /// them.
+    fn shift_toward_front(&mut self, from: usize, count: usize) {
+        let capacity = self.capacity();
+        let base = self.as_mut_ptr();
+        // Move the runs of contiguous slots starting from the first one, so
+        // nothing is overwritten before it's moved.
+        let mut first = from;
+        let mut remaining = count;
+        while remaining > 0 {
+            let run = remaining.min(capacity - first);
+            // Safety: All indexes are in the storage, and `ptr::copy` allows
+            // the source and destination to overlap.
+            unsafe {
+                if first == 0 {
+                    ptr::copy_nonoverlapping(base, base.add(capacity - 1), 1);
+                    ptr::copy(base.add(1), base, run - 1);
+                } else {
+                    ptr::copy(base.add(first), base.add(first - 1), run);
+                }
+            }
+            first = self.wrap_add(first, run);
+            remaining -= run;
+        }
+    }
+
+    /// Computes `(index + offset) % capacity`, for an `index` in the storage
+    /// and an `offset` of at most the capacity.
+    ///
+    /// This is on the hot path of every push and pop, so it avoids modulo,
+    /// which is a library call on targets without a hardware divider.
+    #[inline]
+    fn wrap_add(&self, index: usize, offset: usize) -> usize {
+        // Check whether index wraps around the end of `storage`.
+        //
+        // Comparing against the distance to the end (instead of comparing the
+        // sum against the capacity) avoids wrapping arithmetic.
+        //
+        // I don't _think_ anyone will use this with capacities near the
+        // size limit of `usize`, but you never know.
+        let pre_wrap_size = self.capacity() - index;
+        if offset < pre_wrap_size {
+            index + offset
+        } else {
+            offset - pre_wrap_size
+        }
+    }
+
+    /// Computes `(index - offset) % capacity` (without going negative), for
+    /// an `index` in the storage and an `offset` of at most the capacity.
+    #[inline]
+    fn wrap_sub(&self, index: usize, offset: usize) -> usize {
+        if offset <= index {
+            index - offset
+        } else {
+            index + (self.capacity() - offset)
+        }
+    }
 }
 
-impl<T, const CAPACITY: usize> Drop for Deque<T, CAPACITY> {
+impl<T, const CAPACITY: usize> Default for Deque<T, CAPACITY> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, const CAPACITY: usize> Clone for Deque<T, CAPACITY> {
+    fn clone(&self) -> Self {
+        let mut deque = Self::new();
+        deque.clone_from_deque(self);
+        deque
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.clone_from_deque(source);
+    }
+}
+
+impl<T, S: Storage<T>> Drop for DequeBase<T, S> {
     fn drop(&mut self) {
         // Drops any elements still in the deque:
         self.clear();
     }
 }
 
-impl<T, const CAPACITY: usize> Index<usize> for Deque<T, CAPACITY> {
+impl<T, S: Storage<T>> Index<usize> for DequeBase<T, S> {
     type Output = T;
 
     fn index(&self, index: usize) -> &Self::Output {
@@ -501,8 +1413,69 @@ impl<T, const CAPACITY: usize> Index<usize> for Deque<T, CAPACITY> {
     }
 }
 
-impl<T, const CAPACITY: usize> IndexMut<usize> for Deque<T, CAPACITY> {
+impl<T, S: Storage<T>> IndexMut<usize> for DequeBase<T, S> {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         self.get_mut(index).expect("Out of bounds access")
     }
 }
+
+/// Overwrites the slots in `range` of the storage starting at `base` with
+/// zeros (`zero-on-pop`) or [`POISON`] (`poison-on-pop`), after their values
+/// have been moved out or dropped. Does nothing without either feature.
+///
+/// The writes are volatile, so they aren't removed as dead stores.
+///
+/// # Safety
+///
+/// `base` must be valid for writes of `range`, and the slots in it must not
+/// hold values that are used again.
+#[cfg(any(feature = "zero-on-pop", feature = "poison-on-pop"))]
+unsafe fn scrub_range<T>(base: *mut T, range: Range<usize>) {
+    let pattern = if cfg!(feature = "poison-on-pop") {
+        POISON
+    } else {
+        0
+    };
+    let size = mem::size_of::<T>();
+    let base = base.cast::<u8>();
+    for i in range.start * size..range.end * size {
+        // Safety: Guaranteed by the caller.
+        ptr::write_volatile(base.add(i), pattern);
+    }
+}
+
+#[cfg(not(any(feature = "zero-on-pop", feature = "poison-on-pop")))]
+#[inline(always)]
+unsafe fn scrub_range<T>(_base: *mut T, _range: Range<usize>) {}
+
+/// Drops the values in two ranges of the storage starting at `base`.
+///
+/// If dropping a value panics, the rest of the values in both ranges are
+/// still dropped while unwinding. (A second panic aborts, as usual.)
+///
+/// # Safety
+///
+/// `base` must be valid for writes of both ranges, the values in both
+/// ranges must be initialized, the ranges must not overlap, and the values
+/// must not be used again.
+unsafe fn drop_ranges<T>(base: *mut T, first: Range<usize>, second: Range<usize>) {
+    /// Drops a slice when it goes out of scope, including during unwinding.
+    struct Dropper<T>(*mut [T]);
+
+    impl<T> Drop for Dropper<T> {
+        fn drop(&mut self) {
+            // Safety: Guaranteed by the caller of `drop_ranges`.
+            unsafe { ptr::drop_in_place(self.0) }
+        }
+    }
+
+    let _second = Dropper(ptr::slice_from_raw_parts_mut(
+        base.add(second.start),
+        second.len(),
+    ));
+    // Dropping a slice drops the rest of its values if one of them panics.
+    ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+        base.add(first.start),
+        first.len(),
+    ));
+}
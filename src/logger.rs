@@ -0,0 +1,124 @@
+//! A `log` backend that buffers records in RAM.
+
+use core::{
+    cell::RefCell,
+    fmt::{self, Write},
+};
+
+use critical_section::Mutex;
+use log::{Log, Metadata, Record};
+
+use crate::{fmt::Evicting, Deque};
+
+/// A [`Log`] implementation that formats records into a ring of `N` bytes,
+/// to be written out later by a background task.
+///
+/// Logging only formats into RAM, so it is cheap enough for interrupt
+/// handlers, and never blocks on a slow output. When the ring is full, the
+/// oldest lines are discarded to make room. The buffered text is taken out
+/// with [`drain`](Self::drain), e.g. by a low-priority task that writes it
+/// to a UART.
+///
+/// Each record is formatted as one line: `LEVEL target: message`. Access to
+/// the ring is guarded by a [`critical_section`].
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::logger::RingLogger;
+///
+/// static LOGGER: RingLogger<256> = RingLogger::new();
+///
+/// log::set_logger(&LOGGER).unwrap();
+/// log::set_max_level(log::LevelFilter::Info);
+///
+/// log::info!(target: "app", "booted in {}ms", 12);
+/// log::debug!("not recorded");
+///
+/// let mut buf = [0; 256];
+/// let len = LOGGER.drain(&mut buf);
+/// assert_eq!(&buf[..len], b"INFO app: booted in 12ms\n");
+/// ```
+pub struct RingLogger<const N: usize> {
+    ring: Mutex<RefCell<Deque<u8, N>>>,
+}
+
+impl<const N: usize> RingLogger<N> {
+    /// Creates a logger with an empty ring.
+    pub const fn new() -> Self {
+        Self {
+            ring: Mutex::new(RefCell::new(Deque::new())),
+        }
+    }
+
+    /// The number of bytes waiting to be drained.
+    pub fn len(&self) -> usize {
+        critical_section::with(|cs| self.ring.borrow_ref(cs).len())
+    }
+
+    /// Returns `true` if there is nothing to drain.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Moves buffered text into `buf`, returning the number of bytes
+    /// written.
+    ///
+    /// If `buf` is shorter than the buffered text, the rest is kept for the
+    /// next call, so this can be called in a loop with a small buffer. Each
+    /// call holds the critical section only while copying into `buf`.
+    pub fn drain(&self, buf: &mut [u8]) -> usize {
+        critical_section::with(|cs| self.ring.borrow_ref_mut(cs).pop_front_slice(buf))
+    }
+}
+
+impl<const N: usize> Default for RingLogger<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Log for RingLogger<N> {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        critical_section::with(|cs| {
+            let mut ring = self.ring.borrow_ref_mut(cs);
+            // Evicting lines means writing never fails.
+            let _ = writeln!(
+                EvictingLines(&mut ring),
+                "{} {}: {}",
+                record.level(),
+                record.target(),
+                record.args()
+            );
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+/// Writes text, removing whole lines from the front of the ring to make
+/// room.
+struct EvictingLines<'a, const N: usize>(&'a mut Deque<u8, N>);
+
+impl<const N: usize> fmt::Write for EvictingLines<'_, N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let ring = &mut *self.0;
+        while s.len() > ring.capacity() - ring.len() && !ring.is_empty() {
+            while let Some(byte) = ring.pop_front() {
+                if byte == b'\n' {
+                    break;
+                }
+            }
+        }
+        // Only a line longer than the whole ring still doesn't fit; keep the
+        // end of it.
+        Evicting(ring).write_str(s)
+    }
+}
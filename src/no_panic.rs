@@ -0,0 +1,60 @@
+//! Link-time proof that the core operations can't panic.
+//!
+//! With the `no-panic` feature in a release build, the operations documented
+//! as never panicking hold a [`NoPanic`] guard for their whole body. Its
+//! `Drop` implementation calls a function that doesn't exist, and it is only
+//! dropped if the body unwinds. If the optimizer can prove that the body
+//! never panics, the call is removed; otherwise the build fails to link
+//! with an error naming the problem.
+//!
+//! The check needs optimizations (to remove the unreachable call) and
+//! `panic = "unwind"` (so that panics go through the guard), so it is off in
+//! debug builds. It is also off with the `debug-invariants` feature, whose
+//! checks panic on purpose. Doctests aren't optimized, so the
+//! `examples/no_panic.rs` binary exercises the operations instead. Run it on
+//! the host with:
+//!
+//! ```text
+//! cargo build --release --example no_panic --features no-panic
+//! ```
+
+/// Fails the build if it is ever dropped.
+pub(crate) struct NoPanic;
+
+impl NoPanic {
+    #[inline(always)]
+    pub(crate) fn new() -> Self {
+        NoPanic
+    }
+
+    /// Ends the guarded region without dropping the guard.
+    #[inline(always)]
+    pub(crate) fn disarm(self) {
+        core::mem::forget(self);
+    }
+}
+
+#[cfg(all(
+    feature = "no-panic",
+    not(debug_assertions),
+    not(feature = "debug-invariants")
+))]
+impl Drop for NoPanic {
+    fn drop(&mut self) {
+        extern "C" {
+            #[link_name = "\n\nERROR[fullhouse]: an operation documented as never panicking can panic\n\n"]
+            fn detected_panic() -> !;
+        }
+        // Safety: This is never called; the build fails if it could be.
+        unsafe { detected_panic() }
+    }
+}
+
+#[cfg(not(all(
+    feature = "no-panic",
+    not(debug_assertions),
+    not(feature = "debug-invariants")
+)))]
+impl Drop for NoPanic {
+    fn drop(&mut self) {}
+}
@@ -0,0 +1,46 @@
+//! Adapters for the `nb` non-blocking I/O model.
+
+use core::convert::Infallible;
+
+use crate::{DequeBase, Storage};
+
+impl<T: Copy, S: Storage<T>> DequeBase<T, S> {
+    /// Appends an element to the back of the deque, or returns
+    /// [`nb::Error::WouldBlock`] if it is full.
+    ///
+    /// The value isn't returned when the deque is full, so that it can be
+    /// retried with [`nb::block!`], which re-evaluates the whole call. This
+    /// is why `T` must be `Copy`: the caller still has its own copy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<u8, 1> = Deque::new();
+    /// nb::block!(d.push_back_nb(1)).unwrap();
+    /// assert_eq!(d.push_back_nb(2), Err(nb::Error::WouldBlock));
+    /// ```
+    pub fn push_back_nb(&mut self, value: T) -> nb::Result<(), Infallible> {
+        self.push_back(value).map_err(|_| nb::Error::WouldBlock)
+    }
+}
+
+impl<T, S: Storage<T>> DequeBase<T, S> {
+    /// Removes the first element and returns it, or returns
+    /// [`nb::Error::WouldBlock`] if the deque is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<u8, 4> = Deque::new();
+    /// assert_eq!(d.pop_front_nb(), Err(nb::Error::WouldBlock));
+    /// d.push_back(1).unwrap();
+    /// assert_eq!(nb::block!(d.pop_front_nb()), Ok(1));
+    /// ```
+    pub fn pop_front_nb(&mut self) -> nb::Result<T, Infallible> {
+        self.pop_front().ok_or(nb::Error::WouldBlock)
+    }
+}
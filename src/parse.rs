@@ -0,0 +1,165 @@
+//! Running incremental parsers over byte deques.
+//!
+//! Parsers for streaming protocols, like [nom]'s streaming parsers, take a
+//! contiguous slice and either return an item along with the number of bytes
+//! it took up, or report that the input ends too early. A receive buffer
+//! isn't contiguous when its data wraps around the end of the storage, so
+//! [`DequeBase::parse_with`] first tries the contiguous front part, and only
+//! if that is too short does it [make the deque
+//! contiguous](DequeBase::make_contiguous) and try again. The parsed bytes
+//! are removed; if more are needed, nothing is.
+//!
+//! [nom]: https://docs.rs/nom
+
+use core::iter::FusedIterator;
+
+use crate::{DequeBase, Storage};
+
+/// The reason a parser didn't return an item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParseError<E> {
+    /// The input ends before a complete item.
+    NeedMore,
+    /// The input is malformed.
+    Invalid(E),
+}
+
+/// The result of running a parser: the number of bytes consumed and the
+/// parsed item, or an error.
+pub type ParseResult<T, E = ()> = Result<(usize, T), ParseError<E>>;
+
+impl<S: Storage<u8>> DequeBase<u8, S> {
+    /// Parses an item from the front of the deque with `parse`, and removes
+    /// the bytes it consumed.
+    ///
+    /// Returns `Ok(None)` without removing anything if `parse` needs more
+    /// input. If `parse` reports an error, nothing is removed either; it's up
+    /// to the caller to skip the bad input, e.g. with
+    /// [`advance_front`](Self::advance_front).
+    ///
+    /// `parse` may be called twice: once with the front part of the deque,
+    /// and, if that isn't enough, again with all of it once it has been made
+    /// contiguous.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::{
+    ///     parse::{ParseError, ParseResult},
+    ///     Deque,
+    /// };
+    ///
+    /// /// A record is a length byte followed by that many bytes.
+    /// fn record(input: &[u8]) -> ParseResult<usize> {
+    ///     match input.split_first() {
+    ///         Some((&len, rest)) if rest.len() >= len as usize => {
+    ///             Ok((1 + len as usize, len as usize))
+    ///         }
+    ///         _ => Err(ParseError::NeedMore),
+    ///     }
+    /// }
+    ///
+    /// let mut rx: Deque<u8, 8> = Deque::new();
+    /// rx.push_back_slice(&[0; 6]);
+    /// rx.advance_front(6);
+    ///
+    /// // The record wraps around the end of the storage.
+    /// rx.push_back_slice(&[3, b'a', b'b']);
+    /// assert_eq!(rx.parse_with(record), Ok(None));
+    /// rx.push_back_slice(&[b'c', 1]);
+    /// assert_eq!(rx.parse_with(record), Ok(Some(3)));
+    /// assert_eq!(rx.len(), 1);
+    /// ```
+    pub fn parse_with<T, E>(
+        &mut self,
+        mut parse: impl FnMut(&[u8]) -> ParseResult<T, E>,
+    ) -> Result<Option<T>, E> {
+        let (front, back) = self.as_slices();
+        let mut result = parse(front);
+        if !back.is_empty() && matches!(result, Err(ParseError::NeedMore)) {
+            result = parse(self.make_contiguous());
+        }
+        match result {
+            Ok((consumed, item)) => {
+                self.advance_front(consumed);
+                Ok(Some(item))
+            }
+            Err(ParseError::NeedMore) => Ok(None),
+            Err(ParseError::Invalid(error)) => Err(error),
+        }
+    }
+
+    /// Returns an iterator that parses items from the front of the deque
+    /// with `parse` until it needs more input.
+    ///
+    /// Each item is parsed by [`parse_with`](Self::parse_with). After an
+    /// error, the iterator stops, leaving the bad input in the deque.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::{
+    ///     parse::{ParseError, ParseResult},
+    ///     Deque,
+    /// };
+    ///
+    /// /// A big-endian `u16`.
+    /// fn word(input: &[u8]) -> ParseResult<u16> {
+    ///     match input {
+    ///         [hi, lo, ..] => Ok((2, u16::from_be_bytes([*hi, *lo]))),
+    ///         _ => Err(ParseError::NeedMore),
+    ///     }
+    /// }
+    ///
+    /// let mut rx: Deque<u8, 8> = Deque::new();
+    /// rx.push_back_slice(&[0x12, 0x34, 0x00, 0x01, 0xff]);
+    ///
+    /// let words: Result<Vec<_>, _> = rx.parser(word).collect();
+    /// assert_eq!(words, Ok(vec![0x1234, 0x0001]));
+    /// assert_eq!(rx.len(), 1);
+    /// ```
+    pub fn parser<F>(&mut self, parse: F) -> Parser<'_, S, F> {
+        Parser {
+            deque: self,
+            parse,
+            failed: false,
+        }
+    }
+}
+
+/// An iterator that parses items from the front of a byte deque.
+///
+/// Returned by [`DequeBase::parser`].
+pub struct Parser<'a, S: Storage<u8>, F> {
+    deque: &'a mut DequeBase<u8, S>,
+    parse: F,
+    failed: bool,
+}
+
+impl<S, F, T, E> Iterator for Parser<'_, S, F>
+where
+    S: Storage<u8>,
+    F: FnMut(&[u8]) -> ParseResult<T, E>,
+{
+    type Item = Result<T, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        match self.deque.parse_with(&mut self.parse) {
+            Ok(item) => item.map(Ok),
+            Err(error) => {
+                self.failed = true;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+impl<S, F, T, E> FusedIterator for Parser<'_, S, F>
+where
+    S: Storage<u8>,
+    F: FnMut(&[u8]) -> ParseResult<T, E>,
+{
+}
@@ -0,0 +1,246 @@
+//! Deques that survive a reset in non-initialized RAM.
+
+use core::{mem::MaybeUninit, ptr::addr_of};
+
+use crate::Deque;
+
+/// Marks memory that holds a [`PersistentDeque`].
+const MAGIC: u32 = 0x4655_4c4c;
+
+/// The outcome of [`PersistentDeque::attach`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttachState {
+    /// The memory held a valid deque, which has been kept as-is.
+    Restored,
+
+    /// The memory did not hold a valid deque (e.g. after a power-on reset, or
+    /// if it was corrupted), so it was reinitialized as an empty deque.
+    Fresh,
+}
+
+/// A deque that can be re-attached to after a reset.
+///
+/// This is meant to live in a section of RAM that is not initialized by the
+/// startup code (commonly `.noinit` or `.uninit`), to carry data such as crash
+/// logs across a watchdog or software reset. Alongside the deque itself, it
+/// stores a magic value and a checksum of the deque's indexes, which
+/// [`attach`](Self::attach) validates before trusting the contents; if
+/// anything is off, the deque is reset to empty instead.
+///
+/// Every mutating method updates the checksum, so a reset in the middle of an
+/// operation is detected and results in an empty deque rather than a corrupt
+/// one. Elements are restricted to `Copy` types, since values left over from a
+/// previous boot are never dropped.
+///
+/// # Examples
+///
+/// ```
+/// use core::mem::MaybeUninit;
+/// use fullhouse::persistent::{AttachState, PersistentDeque};
+///
+/// #[link_section = ".noinit"]
+/// static mut CRASH_LOG: MaybeUninit<PersistentDeque<u8, 256>> = MaybeUninit::uninit();
+///
+/// # unsafe { core::ptr::addr_of_mut!(CRASH_LOG).write(MaybeUninit::zeroed()) };
+/// // Safety: This is the only reference to `CRASH_LOG`, and the section is
+/// // not initialized by the startup code.
+/// let (log, state) = unsafe { PersistentDeque::attach(&mut *core::ptr::addr_of_mut!(CRASH_LOG)) };
+/// if state == AttachState::Restored {
+///     while let Some(byte) = log.pop_front() {
+///         // Report the log from the previous boot...
+///         # let _ = byte;
+///     }
+/// }
+/// log.push_back(b'!').unwrap();
+/// ```
+#[repr(C)]
+pub struct PersistentDeque<T: Copy, const N: usize> {
+    magic: u32,
+    checksum: u32,
+    deque: Deque<T, N>,
+}
+
+impl<T: Copy, const N: usize> PersistentDeque<T, N> {
+    /// Creates an empty deque.
+    ///
+    /// This is only useful for a deque that is not shared across resets;
+    /// persistent deques should be obtained with [`attach`](Self::attach)
+    /// instead.
+    pub const fn new() -> Self {
+        Self {
+            magic: MAGIC,
+            checksum: checksum::<T, N>(0, 0, 0),
+            deque: Deque::new(),
+        }
+    }
+
+    /// Attaches to a deque in memory that may have been left by a previous
+    /// boot.
+    ///
+    /// If the memory holds a valid deque (with a matching magic value and
+    /// checksum, and consistent indexes), it is kept as-is and
+    /// [`AttachState::Restored`] is returned. Otherwise, it is initialized to
+    /// an empty deque and [`AttachState::Fresh`] is returned.
+    ///
+    /// # Safety
+    ///
+    /// The memory in `slot` must have been initialized at some point by the
+    /// hardware or a previous boot, even though Rust considers it
+    /// uninitialized. This is the case for RAM that retains its contents
+    /// across a reset, but not for e.g. freshly allocated memory.
+    ///
+    /// The element values in a restored deque are trusted to be valid `T`
+    /// values, so `T` should be a type where any bit pattern is valid, or the
+    /// memory must not have been modified by anything else.
+    pub unsafe fn attach(slot: &mut MaybeUninit<Self>) -> (&mut Self, AttachState) {
+        let ptr = slot.as_mut_ptr();
+
+        // Safety: The caller guarantees that the memory has been written
+        // before. Volatile reads keep the compiler from assuming anything
+        // about the contents of memory it considers uninitialized.
+        let (magic, stored_checksum, start, end, len) = (
+            addr_of!((*ptr).magic).read_volatile(),
+            addr_of!((*ptr).checksum).read_volatile(),
+            addr_of!((*ptr).deque.start).read_volatile(),
+            addr_of!((*ptr).deque.end).read_volatile(),
+            addr_of!((*ptr).deque.len).read_volatile(),
+        );
+
+        let consistent = if N == 0 {
+            start == 0 && end == 0 && len == 0
+        } else {
+            start < N && end < N && len <= N && (start + len) % N == end
+        };
+        let valid = magic == MAGIC && stored_checksum == checksum::<T, N>(start, end, len);
+
+        let state = if valid && consistent {
+            AttachState::Restored
+        } else {
+            // Safety: `ptr` is valid for writes, and the elements (if any) are
+            // `Copy`, so there is nothing to drop.
+            Deque::init_in_place(core::ptr::addr_of_mut!((*ptr).deque));
+            core::ptr::addr_of_mut!((*ptr).magic).write(MAGIC);
+            core::ptr::addr_of_mut!((*ptr).checksum).write(checksum::<T, N>(0, 0, 0));
+            AttachState::Fresh
+        };
+
+        // Safety: The header has either been validated or rewritten, and the
+        // storage array is always valid as `MaybeUninit`.
+        (slot.assume_init_mut(), state)
+    }
+
+    /// Provides read-only access to the underlying deque.
+    pub fn deque(&self) -> &Deque<T, N> {
+        &self.deque
+    }
+
+    /// The maximum number of elements this deque can hold.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// The number of elements currently in the deque.
+    pub const fn len(&self) -> usize {
+        self.deque.len()
+    }
+
+    /// Returns `true` if the deque is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.deque.is_empty()
+    }
+
+    /// Returns `true` if the deque is full.
+    pub fn is_full(&self) -> bool {
+        self.deque.is_full()
+    }
+
+    /// Clears the deque, removing all values.
+    pub fn clear(&mut self) {
+        self.deque.clear();
+        self.seal();
+    }
+
+    /// Prepends an element to the deque.
+    pub fn push_front(&mut self, value: T) -> Result<(), T> {
+        let result = self.deque.push_front(value);
+        self.seal();
+        result
+    }
+
+    /// Appends an element to the back of the deque.
+    pub fn push_back(&mut self, value: T) -> Result<(), T> {
+        let result = self.deque.push_back(value);
+        self.seal();
+        result
+    }
+
+    /// Removes the first element and returns it, or `None` if the deque is
+    /// empty.
+    pub fn pop_front(&mut self) -> Option<T> {
+        let result = self.deque.pop_front();
+        self.seal();
+        result
+    }
+
+    /// Removes the last element from the deque and returns it, or `None` if
+    /// it is empty.
+    pub fn pop_back(&mut self) -> Option<T> {
+        let result = self.deque.pop_back();
+        self.seal();
+        result
+    }
+
+    /// Provides a reference to the element at the given index.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.deque.get(index)
+    }
+
+    /// Provides a mutable reference to the element at the given index.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.deque.get_mut(index)
+    }
+
+    /// Provides a reference to the front element, or `None` if the deque is
+    /// empty.
+    pub fn front(&self) -> Option<&T> {
+        self.deque.front()
+    }
+
+    /// Provides a reference to the back element, or `None` if the deque is
+    /// empty.
+    pub fn back(&self) -> Option<&T> {
+        self.deque.back()
+    }
+
+    /// Updates the checksum to match the current indexes.
+    fn seal(&mut self) {
+        self.checksum = checksum::<T, N>(self.deque.start, self.deque.end, self.deque.len);
+    }
+}
+
+impl<T: Copy, const N: usize> Default for PersistentDeque<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Computes the checksum of a deque's indexes.
+///
+/// The capacity and element size are mixed in as well, so that a deque left
+/// by firmware with a different layout is not mistaken for a valid one.
+const fn checksum<T, const N: usize>(start: usize, end: usize, len: usize) -> u32 {
+    // FNV-1a over the 32-bit halves of each word.
+    const FNV_OFFSET: u32 = 0x811c_9dc5;
+    const FNV_PRIME: u32 = 0x0100_0193;
+
+    let words = [start, end, len, N, core::mem::size_of::<T>()];
+    let mut hash = FNV_OFFSET;
+    let mut i = 0;
+    while i < words.len() {
+        let word = words[i] as u64;
+        hash = (hash ^ word as u32).wrapping_mul(FNV_PRIME);
+        hash = (hash ^ (word >> 32) as u32).wrapping_mul(FNV_PRIME);
+        i += 1;
+    }
+    hash
+}
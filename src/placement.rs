@@ -0,0 +1,59 @@
+//! Placement of deques in specific memory regions.
+
+/// Creates a deque in a `static` placed in the given linker section, and
+/// returns a `&'static mut` reference to it.
+///
+/// This is useful for putting a buffer in a region that is not the default
+/// RAM, e.g. DMA-capable or non-cached memory, with a section name that is
+/// defined in the application's linker script. The deque is initialized in
+/// place when the macro is first evaluated, so this also works for sections
+/// that are not initialized by the startup code (`NOLOAD` sections).
+///
+/// The macro expands to an expression of type
+/// `Option<&'static mut Deque<T, CAPACITY>>`. Each expansion owns its own
+/// static, and it returns `Some` only the first time it is evaluated, so the
+/// mutable reference is always unique. The static is named `$name`, which
+/// helps to identify it in the symbol table and map file.
+///
+/// Requires atomic compare-and-swap support on the target.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::{static_deque, Deque};
+///
+/// fn take_buffer() -> Option<&'static mut Deque<u8, 4096>> {
+///     static_deque!(UART_TX: u8, 4096, ".dma_ram")
+/// }
+///
+/// let deque = take_buffer().unwrap();
+/// deque.push_back(1).unwrap();
+///
+/// // The buffer can only be taken once:
+/// assert!(take_buffer().is_none());
+/// ```
+#[macro_export]
+macro_rules! static_deque {
+    ($name:ident: $ty:ty, $capacity:expr, $section:literal) => {{
+        #[link_section = $section]
+        static mut $name: ::core::mem::MaybeUninit<$crate::Deque<$ty, $capacity>> =
+            ::core::mem::MaybeUninit::uninit();
+        static TAKEN: ::core::sync::atomic::AtomicBool =
+            ::core::sync::atomic::AtomicBool::new(false);
+
+        if TAKEN.swap(true, ::core::sync::atomic::Ordering::AcqRel) {
+            ::core::option::Option::None
+        } else {
+            // Safety:
+            // - `TAKEN` guarantees that this block runs at most once, so no
+            //   other reference to the static exists.
+            // - The pointer is derived from the static, so it is valid and
+            //   aligned for the deque.
+            unsafe {
+                let ptr = ::core::ptr::addr_of_mut!($name).cast::<$crate::Deque<$ty, $capacity>>();
+                $crate::Deque::init_in_place(ptr);
+                ::core::option::Option::Some(&mut *ptr)
+            }
+        }
+    }};
+}
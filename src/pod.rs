@@ -0,0 +1,99 @@
+//! Byte views of deques of plain-old-data types.
+
+use core::mem;
+
+use bytemuck::{NoUninit, Pod};
+
+use crate::{DequeBase, Storage};
+
+impl<T: NoUninit, S: Storage<T>> DequeBase<T, S> {
+    /// Returns the contents of the deque as a pair of byte slices, in order.
+    ///
+    /// This is [`as_slices`](Self::as_slices) reinterpreted as bytes, e.g.
+    /// to hand samples to a DMA transfer or a flash write.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut samples: Deque<u16, 4> = Deque::new();
+    /// samples.push_back(0x0102).unwrap();
+    /// let (front, _) = samples.as_byte_slices();
+    /// assert_eq!(front, 0x0102u16.to_ne_bytes());
+    /// ```
+    pub fn as_byte_slices(&self) -> (&[u8], &[u8]) {
+        let (front, back) = self.as_slices();
+        (bytemuck::cast_slice(front), bytemuck::cast_slice(back))
+    }
+}
+
+impl<T: Pod, S: Storage<T>> DequeBase<T, S> {
+    /// Returns the contents of the deque as a pair of mutable byte slices,
+    /// in order.
+    ///
+    /// Any bytes written through them are valid values of `T`, because `T`
+    /// is [`Pod`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut words: Deque<u32, 4> = Deque::new();
+    /// words.push_back(0).unwrap();
+    /// words.as_mut_byte_slices().0.fill(0xFF);
+    /// assert_eq!(words[0], u32::MAX);
+    /// ```
+    pub fn as_mut_byte_slices(&mut self) -> (&mut [u8], &mut [u8]) {
+        let (front, back) = self.as_mut_slices();
+        (
+            bytemuck::cast_slice_mut(front),
+            bytemuck::cast_slice_mut(back),
+        )
+    }
+
+    /// Makes the contents of the deque contiguous and returns them as one
+    /// mutable byte slice.
+    ///
+    /// See [`make_contiguous`](Self::make_contiguous).
+    pub fn make_contiguous_bytes(&mut self) -> &mut [u8] {
+        bytemuck::cast_slice_mut(self.make_contiguous())
+    }
+
+    /// Appends the elements encoded in `bytes`, e.g. samples received over a
+    /// link or read from flash, returning the number of bytes used.
+    ///
+    /// If the deque doesn't have room for all of them, as many whole elements
+    /// as fit are appended. `bytes` doesn't need to be aligned for `T`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `bytes` is not a multiple of the size of `T`,
+    /// or if `T` is zero-sized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut samples: Deque<u16, 2> = Deque::new();
+    /// let received = [1u16, 2, 3].map(u16::to_ne_bytes).concat();
+    /// assert_eq!(samples.push_back_bytes(&received), 4);
+    /// assert_eq!(samples.as_slices().0, &[1, 2]);
+    /// ```
+    pub fn push_back_bytes(&mut self, bytes: &[u8]) -> usize {
+        let size = mem::size_of::<T>();
+        assert!(size > 0, "can't push bytes of a zero-sized type");
+        assert!(
+            bytes.len() % size == 0,
+            "byte length is not a multiple of the element size"
+        );
+        let count = (bytes.len() / size).min(self.capacity() - self.len());
+        for chunk in bytes.chunks_exact(size).take(count) {
+            // Can't fail, since `count` elements fit.
+            let _ = self.push_back(bytemuck::pod_read_unaligned(chunk));
+        }
+        count * size
+    }
+}
@@ -0,0 +1,201 @@
+//! Decoding postcard messages from a byte deque.
+//!
+//! Postcard messages sent over a serial link are usually [COBS](crate::cobs)
+//! framed, so the receiver can resynchronize after lost bytes. The functions
+//! here pull complete frames out of a receive deque, e.g. one filled by a UART
+//! interrupt, and decode each frame as a message. Bytes of incomplete frames
+//! stay in the deque until more data arrives.
+//!
+//! Links that can't lose bytes (e.g. USB or a TCP stream) can send messages
+//! back to back instead, which [`take_unframed_from_deque`] decodes.
+//!
+//! # Examples
+//!
+//! ```
+//! use fullhouse::{cobs, postcard::messages, Deque};
+//!
+//! let mut rx: Deque<u8, 64> = Deque::new();
+//!
+//! // Two postcard-encoded `(u8, u16)` messages, and part of a third.
+//! cobs::encode(&[1, 0x80, 0x01], &mut rx).unwrap();
+//! cobs::encode(&[2, 0x05], &mut rx).unwrap();
+//! rx.push_back(0x03).unwrap();
+//!
+//! let mut received = messages::<(u8, u16), _>(&mut rx);
+//! assert_eq!(received.next(), Some(Ok((1, 128))));
+//! assert_eq!(received.next(), Some(Ok((2, 5))));
+//! assert_eq!(received.next(), None);
+//! assert_eq!(rx.len(), 1);
+//! ```
+
+use core::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+
+use crate::{
+    cobs::{self, CobsError},
+    DequeBase, Storage,
+};
+#[cfg(feature = "postcard-max-size")]
+use crate::{varint, Deque};
+
+/// An error from receiving a message.
+///
+/// In both cases, the offending frame has been removed from the deque, so
+/// the next call can continue with the following frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MessageError {
+    /// The frame was not validly encoded, or was too long for the deque.
+    Frame(CobsError),
+
+    /// The frame did not contain a valid message.
+    Decode(postcard::Error),
+}
+
+/// Removes the next complete frame from the front of `deque` and decodes it
+/// as a message.
+///
+/// Returns `None` if the deque does not contain a complete frame yet.
+pub fn take_from_deque<T, S>(deque: &mut DequeBase<u8, S>) -> Option<Result<T, MessageError>>
+where
+    T: DeserializeOwned,
+    S: Storage<u8>,
+{
+    Some(match cobs::next_frame(deque)? {
+        Ok(frame) => postcard::from_bytes(&frame).map_err(MessageError::Decode),
+        Err(error) => Err(MessageError::Frame(error)),
+    })
+}
+
+/// Returns an iterator over the messages in `deque`.
+///
+/// The iterator stops when there are no more complete frames; see
+/// [`take_from_deque`].
+pub fn messages<T, S>(deque: &mut DequeBase<u8, S>) -> Messages<'_, T, S>
+where
+    T: DeserializeOwned,
+    S: Storage<u8>,
+{
+    Messages {
+        deque,
+        _marker: PhantomData,
+    }
+}
+
+/// An iterator that decodes messages from a byte deque.
+///
+/// This is created by [`messages`].
+pub struct Messages<'a, T, S: Storage<u8>> {
+    deque: &'a mut DequeBase<u8, S>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned, S: Storage<u8>> Iterator for Messages<'_, T, S> {
+    type Item = Result<T, MessageError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        take_from_deque(self.deque)
+    }
+}
+
+/// Removes the next complete message from the front of `deque`, which holds
+/// postcard messages back to back without any framing.
+///
+/// Returns `None` if the deque does not contain a complete message yet.
+///
+/// Without framing, there is no way to find where the next message starts
+/// after an invalid one, so on an error all of the buffered bytes are
+/// discarded. This includes the case of a message too long for the deque.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::{postcard::take_unframed_from_deque, Deque};
+///
+/// let mut rx: Deque<u8, 16> = Deque::new();
+///
+/// // A postcard-encoded `(u8, u16)`, arriving in two parts.
+/// rx.push_back_slice(&[1, 0x80]);
+/// assert_eq!(take_unframed_from_deque::<(u8, u16), _>(&mut rx), None);
+/// rx.push_back_slice(&[0x01, 2, 5]);
+///
+/// assert_eq!(take_unframed_from_deque(&mut rx), Some(Ok((1u8, 128u16))));
+/// assert_eq!(take_unframed_from_deque(&mut rx), Some(Ok((2u8, 5u16))));
+/// assert!(rx.is_empty());
+/// ```
+pub fn take_unframed_from_deque<T, S>(
+    deque: &mut DequeBase<u8, S>,
+) -> Option<Result<T, postcard::Error>>
+where
+    T: DeserializeOwned,
+    S: Storage<u8>,
+{
+    if deque.is_empty() {
+        return None;
+    }
+    let full = deque.is_full();
+    let len = deque.len();
+    let result = postcard::take_from_bytes(deque.make_contiguous())
+        .map(|(message, rest)| (message, len - rest.len()));
+    match result {
+        Ok((message, used)) => {
+            deque.advance_front(used);
+            Some(Ok(message))
+        }
+        Err(postcard::Error::DeserializeUnexpectedEnd) if !full => None,
+        Err(error) => {
+            deque.clear();
+            Some(Err(error))
+        }
+    }
+}
+
+/// Returns an iterator over the unframed messages in `deque`.
+///
+/// The iterator stops when there are no more complete messages; see
+/// [`take_unframed_from_deque`].
+pub fn unframed_messages<T, S>(deque: &mut DequeBase<u8, S>) -> UnframedMessages<'_, T, S>
+where
+    T: DeserializeOwned,
+    S: Storage<u8>,
+{
+    UnframedMessages {
+        deque,
+        _marker: PhantomData,
+    }
+}
+
+/// An iterator that decodes unframed messages from a byte deque.
+///
+/// This is created by [`unframed_messages`].
+pub struct UnframedMessages<'a, T, S: Storage<u8>> {
+    deque: &'a mut DequeBase<u8, S>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned, S: Storage<u8>> Iterator for UnframedMessages<'_, T, S> {
+    type Item = Result<T, postcard::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        take_unframed_from_deque(self.deque)
+    }
+}
+
+/// A deque is serialized as its length and then its elements, the same as a
+/// `heapless::Vec` of the same capacity.
+///
+/// # Examples
+///
+/// ```
+/// use postcard::experimental::max_size::MaxSize;
+/// use fullhouse::Deque;
+///
+/// // One byte of length, and up to three bytes for each `u16`.
+/// assert_eq!(Deque::<u16, 8>::POSTCARD_MAX_SIZE, 1 + 8 * 3);
+/// ```
+#[cfg(feature = "postcard-max-size")]
+impl<T: postcard::experimental::max_size::MaxSize, const N: usize>
+    postcard::experimental::max_size::MaxSize for Deque<T, N>
+{
+    const POSTCARD_MAX_SIZE: usize = varint::encoded_len(N as u64) + N * T::POSTCARD_MAX_SIZE;
+}
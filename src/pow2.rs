@@ -0,0 +1,317 @@
+//! A deque specialized for power-of-two capacities.
+
+use core::{
+    mem::{self, MaybeUninit},
+    ops::{Index, IndexMut, Range},
+};
+
+use crate::{drop_ranges, scrub_range};
+
+/// A fixed-capacity deque whose capacity must be a power of two.
+///
+/// This has the basic pushing, popping and indexing methods of
+/// [`Deque`](crate::Deque), with its elements always stored inline rather
+/// than in a [`Storage`](crate::Storage), and a different index
+/// representation: `head` and `tail` are free-running counters that are only
+/// ever incremented or decremented with wrapping arithmetic, and are reduced
+/// to a storage index by masking with `CAPACITY - 1`. The length is simply
+/// `tail - head`. There is no division, modulo or wraparound branch in any
+/// operation, which matters on cores without a hardware divider such as the
+/// Cortex-M0.
+///
+/// Using a capacity that is not a power of two is a compile-time error.
+///
+/// The `zero-on-pop` and `poison-on-pop` features scrub popped slots the same
+/// way as for [`Deque`](crate::Deque), and `debug-invariants` checks the
+/// counters after every push and pop.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::Pow2Deque;
+///
+/// let mut deque: Pow2Deque<u8, 16> = Pow2Deque::new();
+/// deque.push_back(1).unwrap();
+/// deque.push_front(0).unwrap();
+/// assert_eq!(deque.pop_front(), Some(0));
+/// assert_eq!(deque.pop_front(), Some(1));
+/// ```
+///
+/// ```compile_fail
+/// use fullhouse::Pow2Deque;
+///
+/// let deque: Pow2Deque<u8, 12> = Pow2Deque::new();
+/// ```
+pub struct Pow2Deque<T, const CAPACITY: usize> {
+    /// Counter of the first element. Its storage index is `head & MASK`.
+    head: usize,
+
+    /// Counter one past the last element. Its storage index is `tail & MASK`.
+    ///
+    /// `tail.wrapping_sub(head)` is the number of elements, which is always
+    /// in `0..=CAPACITY`. Because `CAPACITY` divides `usize::MAX + 1`, the
+    /// masked indexes stay consistent when the counters wrap around.
+    tail: usize,
+
+    /// A circular buffer. Elements are stored at the masked indexes of the
+    /// counters `head..tail`.
+    data: [MaybeUninit<T>; CAPACITY],
+}
+
+impl<T, const CAPACITY: usize> Pow2Deque<T, CAPACITY> {
+    /// Mask that reduces a counter to an index into `data`.
+    const MASK: usize = {
+        assert!(
+            CAPACITY.is_power_of_two(),
+            "Pow2Deque capacity must be a power of two"
+        );
+        CAPACITY - 1
+    };
+
+    /// Creates an empty deque.
+    pub const fn new() -> Self {
+        // Force evaluation of the capacity check.
+        let _ = Self::MASK;
+
+        Self {
+            head: 0,
+            tail: 0,
+
+            // Safety: The value inside the outermost MaybeUninit must be valid.
+            // - A value of `[MaybeUninit<T>; N]` is valid because a value of
+            //  `MaybeUninit<T>` is always valid (even if the inner value
+            //  isn't).
+            data: unsafe { MaybeUninit::<[MaybeUninit<T>; CAPACITY]>::uninit().assume_init() },
+        }
+    }
+
+    /// The maximum number of elements this deque can hold.
+    pub const fn capacity(&self) -> usize {
+        CAPACITY
+    }
+
+    /// The number of elements currently in the deque.
+    pub const fn len(&self) -> usize {
+        self.tail.wrapping_sub(self.head)
+    }
+
+    /// Returns `true` if the deque is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.head == self.tail
+    }
+
+    /// Returns `true` if the deque is full.
+    pub const fn is_full(&self) -> bool {
+        self.len() == CAPACITY
+    }
+
+    /// Clears the deque, removing all values.
+    pub fn clear(&mut self) {
+        let (first, second) = self.ranges();
+
+        // Reset region state:
+        self.head = 0;
+        self.tail = 0;
+        self.check_invariants();
+
+        let base = self.data.as_mut_ptr().cast::<T>();
+        if mem::needs_drop::<T>() {
+            // Safety: The values in the MaybeUninits must be valid.
+            // - ranges() returns the ranges of valid values, which don't
+            //   overlap.
+            //
+            // Postcondition: The values in the MaybeUninits are invalidated.
+            // - The region is reset to empty before dropping, so later code
+            //   (including panics) will not assume that this data is valid.
+            unsafe { drop_ranges(base, first.clone(), second.clone()) };
+        }
+        // Safety: The ranges are in `data`, and no longer hold values.
+        unsafe {
+            scrub_range(base, first);
+            scrub_range(base, second);
+        }
+    }
+
+    /// Ranges of indexes of valid values in `data`. Together, in order, they
+    /// span the logical contents from the front to the back.
+    fn ranges(&self) -> (Range<usize>, Range<usize>) {
+        let start = self.head & Self::MASK;
+        let len = self.len();
+        let pre_wrap_size = CAPACITY - start;
+        if len <= pre_wrap_size {
+            (start..start + len, 0..0)
+        } else {
+            (start..CAPACITY, 0..len - pre_wrap_size)
+        }
+    }
+
+    /// Takes the value out of the slot at `idx` in `data`, and scrubs it.
+    ///
+    /// # Safety
+    ///
+    /// The slot must hold a valid value, which must not be used again.
+    unsafe fn take(&mut self, idx: usize) -> T {
+        let value = self.data[idx].assume_init_read();
+        scrub_range(self.data.as_mut_ptr().cast::<T>(), idx..idx + 1);
+        value
+    }
+
+    /// Panics if the counters are inconsistent, with the `debug-invariants`
+    /// feature.
+    #[inline]
+    fn check_invariants(&self) {
+        #[cfg(feature = "debug-invariants")]
+        assert!(self.len() <= CAPACITY, "length exceeds capacity");
+    }
+
+    /// Prepends an element to the deque.
+    pub fn push_front(&mut self, value: T) -> Result<(), T> {
+        if self.is_full() {
+            Err(value)
+        } else {
+            let new_head = self.head.wrapping_sub(1);
+            self.data[new_head & Self::MASK].write(value);
+            self.head = new_head;
+            self.check_invariants();
+            Ok(())
+        }
+    }
+
+    /// Appends an element to the back of the deque.
+    pub fn push_back(&mut self, value: T) -> Result<(), T> {
+        if self.is_full() {
+            Err(value)
+        } else {
+            self.data[self.tail & Self::MASK].write(value);
+            self.tail = self.tail.wrapping_add(1);
+            self.check_invariants();
+            Ok(())
+        }
+    }
+
+    /// Removes the first element and returns it, or `None` if the deque is
+    /// empty.
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            let old_head = self.head;
+            self.head = old_head.wrapping_add(1);
+            self.check_invariants();
+
+            // Safety: The value in the MaybeUninit must be valid.
+            // - At the start of the function, it was in the valid region of the
+            //   `data` array, and is not otherwise accessed in this function.
+            //
+            // Postcondition: The value in the MaybeUninit is invalidated (it
+            // has been moved).
+            // - The region has already been shrunk, so later code (including
+            //   panics) will not assume that this index is valid.
+            Some(unsafe { self.take(old_head & Self::MASK) })
+        }
+    }
+
+    /// Removes the last element from the deque and returns it, or `None` if
+    /// it is empty.
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            self.tail = self.tail.wrapping_sub(1);
+            self.check_invariants();
+
+            // Safety: The value in the MaybeUninit must be valid.
+            // - At the start of the function, it was in the valid region of the
+            //   `data` array, and is not otherwise accessed in this function.
+            //
+            // Postcondition: The value in the MaybeUninit is invalidated (it
+            // has been moved).
+            // - The region has already been shrunk, so later code (including
+            //   panics) will not assume that this index is valid.
+            Some(unsafe { self.take(self.tail & Self::MASK) })
+        }
+    }
+
+    /// Provides a reference to the element at the given index.
+    ///
+    /// Element at index 0 is at the front of the queue.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len() {
+            let idx = self.head.wrapping_add(index) & Self::MASK;
+
+            // Safety: The value in the MaybeUninit must be valid.
+            // - `index < len`, so the counter `head + index` is in the valid
+            //   region.
+            Some(unsafe { self.data[idx].assume_init_ref() })
+        } else {
+            None
+        }
+    }
+
+    /// Provides a mutable reference to the element at the given index.
+    ///
+    /// Element at index 0 is at the front of the queue.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.len() {
+            let idx = self.head.wrapping_add(index) & Self::MASK;
+
+            // Safety: The value in the MaybeUninit must be valid.
+            // - `index < len`, so the counter `head + index` is in the valid
+            //   region.
+            Some(unsafe { self.data[idx].assume_init_mut() })
+        } else {
+            None
+        }
+    }
+
+    /// Provides a reference to the front element, or `None` if the deque is
+    /// empty.
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// Provides a mutable reference to the front element, or `None` if the
+    /// deque is empty.
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(0)
+    }
+
+    /// Provides a reference to the back element, or `None` if the deque is
+    /// empty.
+    pub fn back(&self) -> Option<&T> {
+        self.get(self.len().wrapping_sub(1))
+    }
+
+    /// Provides a mutable reference to the back element, or `None` if the
+    /// deque is empty.
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(self.len().wrapping_sub(1))
+    }
+}
+
+impl<T, const CAPACITY: usize> Default for Pow2Deque<T, CAPACITY> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const CAPACITY: usize> Drop for Pow2Deque<T, CAPACITY> {
+    fn drop(&mut self) {
+        // Drops any elements still in the deque:
+        self.clear();
+    }
+}
+
+impl<T, const CAPACITY: usize> Index<usize> for Pow2Deque<T, CAPACITY> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("Out of bounds access")
+    }
+}
+
+impl<T, const CAPACITY: usize> IndexMut<usize> for Pow2Deque<T, CAPACITY> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).expect("Out of bounds access")
+    }
+}
@@ -0,0 +1,311 @@
+//! A priority queue whose entries can be changed or removed after pushing.
+
+/// Identifies an entry of a [`KeyedPriorityQueue`].
+///
+/// Handles stay valid until their entry is popped or removed. After that,
+/// methods given the handle return `None` (or `false`), even if its slot has
+/// been reused by a newer entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Handle {
+    slot: usize,
+    generation: u32,
+}
+
+struct Slot<P, T> {
+    entry: Option<(P, T)>,
+
+    /// The entry's position in the heap, if it's occupied.
+    pos: usize,
+
+    /// Incremented whenever the entry is removed, to invalidate handles.
+    generation: u32,
+}
+
+/// A fixed-capacity min-heap of up to `N` values with priorities of type
+/// `P`, which can be reprioritized or removed through the [`Handle`]
+/// returned when they are pushed.
+///
+/// The entry with the smallest priority is popped first (use
+/// [`core::cmp::Reverse`] for the largest). Pushing, popping, changing a
+/// priority and removing are all O(log n), since the heap tracks where each
+/// entry is.
+///
+/// # Examples
+///
+/// Rescheduling deadlines:
+///
+/// ```
+/// use fullhouse::KeyedPriorityQueue;
+///
+/// let mut timers: KeyedPriorityQueue<u32, &str, 8> = KeyedPriorityQueue::new();
+/// let blink = timers.push(500, "blink").unwrap();
+/// let beep = timers.push(200, "beep").unwrap();
+/// timers.push(300, "poll").unwrap();
+///
+/// timers.update(blink, 100);
+/// timers.remove(beep);
+///
+/// assert_eq!(timers.pop(), Some((100, "blink")));
+/// assert_eq!(timers.pop(), Some((300, "poll")));
+/// assert_eq!(timers.pop(), None);
+/// ```
+pub struct KeyedPriorityQueue<P, T, const N: usize> {
+    slots: [Slot<P, T>; N],
+
+    /// The slots of the entries, in heap order.
+    heap: [usize; N],
+
+    /// The indexes of the free slots, in `free[..N - len]`.
+    free: [usize; N],
+
+    len: usize,
+}
+
+impl<P: Ord, T, const N: usize> KeyedPriorityQueue<P, T, N> {
+    const EMPTY: Slot<P, T> = Slot {
+        entry: None,
+        pos: 0,
+        generation: 0,
+    };
+
+    /// Creates an empty queue.
+    pub const fn new() -> Self {
+        let mut free = [0; N];
+        let mut i = 0;
+        while i < N {
+            // Hand out the lowest slots first.
+            free[i] = N - 1 - i;
+            i += 1;
+        }
+        Self {
+            slots: [Self::EMPTY; N],
+            heap: [0; N],
+            free,
+            len: 0,
+        }
+    }
+
+    /// The number of entries in the queue.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the queue is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the queue is full.
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Adds a value with the given priority, returning its handle.
+    ///
+    /// Returns `Err((priority, value))` if the queue is full.
+    pub fn push(&mut self, priority: P, value: T) -> Result<Handle, (P, T)> {
+        if self.is_full() {
+            return Err((priority, value));
+        }
+        let slot = self.free[N - 1 - self.len];
+        let pos = self.len;
+        self.slots[slot].entry = Some((priority, value));
+        self.slots[slot].pos = pos;
+        self.heap[pos] = slot;
+        self.len += 1;
+        self.sift_up(pos);
+        Ok(Handle {
+            slot,
+            generation: self.slots[slot].generation,
+        })
+    }
+
+    /// Returns the entry with the smallest priority, without removing it.
+    pub fn peek(&self) -> Option<(&P, &T)> {
+        if self.is_empty() {
+            return None;
+        }
+        self.entry_at(0)
+    }
+
+    /// Removes the entry with the smallest priority and returns it.
+    pub fn pop(&mut self) -> Option<(P, T)> {
+        if self.is_empty() {
+            return None;
+        }
+        Some(self.remove_at(0))
+    }
+
+    /// Returns the handle of the entry with the smallest priority.
+    pub fn peek_handle(&self) -> Option<Handle> {
+        if self.is_empty() {
+            return None;
+        }
+        let slot = self.heap[0];
+        Some(Handle {
+            slot,
+            generation: self.slots[slot].generation,
+        })
+    }
+
+    /// Returns the position in the heap of the entry with `handle`, if it's
+    /// still in the queue.
+    fn position(&self, handle: Handle) -> Option<usize> {
+        let slot = self.slots.get(handle.slot)?;
+        if slot.generation == handle.generation && slot.entry.is_some() {
+            Some(slot.pos)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if the entry with `handle` is still in the queue.
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.position(handle).is_some()
+    }
+
+    /// Returns the entry with `handle`, if it's still in the queue.
+    pub fn get(&self, handle: Handle) -> Option<(&P, &T)> {
+        self.entry_at(self.position(handle)?)
+    }
+
+    /// Returns a mutable reference to the value of the entry with `handle`,
+    /// if it's still in the queue.
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        self.position(handle)?;
+        self.slots[handle.slot]
+            .entry
+            .as_mut()
+            .map(|(_, value)| value)
+    }
+
+    /// Changes the priority of the entry with `handle`, returning the old
+    /// priority, or `None` if the entry is no longer in the queue.
+    pub fn update(&mut self, handle: Handle, priority: P) -> Option<P> {
+        let pos = self.position(handle)?;
+        let (old, _) = self.slots[handle.slot].entry.as_mut()?;
+        let raise = priority < *old;
+        let old = core::mem::replace(old, priority);
+        if raise {
+            self.sift_up(pos);
+        } else {
+            self.sift_down(pos);
+        }
+        Some(old)
+    }
+
+    /// Lowers the priority of the entry with `handle` to `priority`, if that
+    /// is smaller than its current priority.
+    ///
+    /// Returns `true` if the priority was lowered. This is the "relax" step
+    /// of Dijkstra's algorithm.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::KeyedPriorityQueue;
+    ///
+    /// let mut frontier: KeyedPriorityQueue<u32, char, 4> = KeyedPriorityQueue::new();
+    /// let a = frontier.push(10, 'a').unwrap();
+    /// assert!(frontier.decrease_key(a, 7));
+    /// assert!(!frontier.decrease_key(a, 9));
+    /// assert_eq!(frontier.get(a), Some((&7, &'a')));
+    /// ```
+    pub fn decrease_key(&mut self, handle: Handle, priority: P) -> bool {
+        match self.get(handle) {
+            Some((current, _)) if priority < *current => {
+                self.update(handle, priority);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Removes the entry with `handle` and returns it, or `None` if it is no
+    /// longer in the queue.
+    pub fn remove(&mut self, handle: Handle) -> Option<(P, T)> {
+        let pos = self.position(handle)?;
+        Some(self.remove_at(pos))
+    }
+
+    /// Removes all entries.
+    pub fn clear(&mut self) {
+        while self.pop().is_some() {}
+    }
+
+    fn entry_at(&self, pos: usize) -> Option<(&P, &T)> {
+        self.slots[self.heap[pos]]
+            .entry
+            .as_ref()
+            .map(|(priority, value)| (priority, value))
+    }
+
+    fn priority_at(&self, pos: usize) -> &P {
+        // Every slot in the heap is occupied.
+        match &self.slots[self.heap[pos]].entry {
+            Some((priority, _)) => priority,
+            None => unreachable!(),
+        }
+    }
+
+    /// Removes the entry at heap position `pos`, which must be occupied.
+    fn remove_at(&mut self, pos: usize) -> (P, T) {
+        let last = self.len - 1;
+        self.swap(pos, last);
+        self.len -= 1;
+
+        let slot = self.heap[last];
+        let entry = self.slots[slot].entry.take();
+        self.slots[slot].generation = self.slots[slot].generation.wrapping_add(1);
+        self.free[N - 1 - self.len] = slot;
+
+        if pos < self.len {
+            self.sift_down(pos);
+            self.sift_up(pos);
+        }
+        // The slot was occupied, since it was in the heap.
+        entry.unwrap()
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.slots[self.heap[a]].pos = a;
+        self.slots[self.heap[b]].pos = b;
+    }
+
+    fn sift_up(&mut self, mut pos: usize) {
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            if self.priority_at(pos) >= self.priority_at(parent) {
+                break;
+            }
+            self.swap(pos, parent);
+            pos = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut pos: usize) {
+        loop {
+            let left = 2 * pos + 1;
+            let right = left + 1;
+            let mut smallest = pos;
+            if left < self.len && self.priority_at(left) < self.priority_at(smallest) {
+                smallest = left;
+            }
+            if right < self.len && self.priority_at(right) < self.priority_at(smallest) {
+                smallest = right;
+            }
+            if smallest == pos {
+                break;
+            }
+            self.swap(pos, smallest);
+            pos = smallest;
+        }
+    }
+}
+
+impl<P: Ord, T, const N: usize> Default for KeyedPriorityQueue<P, T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
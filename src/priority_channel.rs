@@ -0,0 +1,255 @@
+//! An async channel that delivers the most urgent message first.
+
+use core::{
+    cell::RefCell,
+    cmp::Reverse,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+use critical_section::Mutex;
+
+use crate::{
+    async_deque::{wake_all, watch},
+    Deque, KeyedPriorityQueue,
+};
+
+struct State<T, P, const N: usize, const W: usize> {
+    /// Ordered by priority, highest first, and then by the order they were
+    /// sent.
+    queue: KeyedPriorityQueue<(Reverse<P>, u64), T, N>,
+
+    /// The sequence number of the next message sent.
+    next_seq: u64,
+
+    /// Tasks waiting for a message.
+    not_empty: Deque<Waker, W>,
+
+    /// Tasks waiting for space.
+    not_full: Deque<Waker, W>,
+}
+
+impl<T, P: Ord, const N: usize, const W: usize> State<T, P, N, W> {
+    fn push(&mut self, priority: P, value: T) -> Result<(), T> {
+        self.queue
+            .push((Reverse(priority), self.next_seq), value)
+            .map_err(|(_, value)| value)?;
+        self.next_seq += 1;
+        wake_all(&mut self.not_empty);
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Option<(P, T)> {
+        let ((Reverse(priority), _), value) = self.queue.pop()?;
+        wake_all(&mut self.not_full);
+        Some((priority, value))
+    }
+}
+
+/// A channel holding up to `N` messages, where each message is sent with a
+/// priority of type `P`, and receiving returns the one with the highest
+/// priority.
+///
+/// Messages with equal priorities are received in the order they were sent.
+/// This lets urgent messages overtake bulk traffic on the same channel:
+///
+/// ```
+/// use fullhouse::priority_channel::PriorityChannel;
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Message {
+///     Telemetry(u16),
+///     Stop,
+/// }
+///
+/// let channel: PriorityChannel<Message, u8, 8> = PriorityChannel::new();
+/// channel.try_send(0, Message::Telemetry(1)).unwrap();
+/// channel.try_send(0, Message::Telemetry(2)).unwrap();
+/// channel.try_send(9, Message::Stop).unwrap();
+///
+/// assert_eq!(channel.try_recv(), Some(Message::Stop));
+/// assert_eq!(channel.try_recv(), Some(Message::Telemetry(1)));
+/// assert_eq!(channel.try_recv(), Some(Message::Telemetry(2)));
+/// ```
+///
+/// Up to `W` tasks can wait on each side; more than that still works, but
+/// the extra tasks poll repeatedly instead of sleeping. When a message
+/// arrives, all waiting receivers are woken, and race to take it.
+///
+/// Access to the queue is guarded by a [`critical_section`], so the
+/// non-blocking methods can also be used from interrupt handlers.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() {
+/// use fullhouse::priority_channel::PriorityChannel;
+///
+/// static COMMANDS: PriorityChannel<u32, u8, 16> = PriorityChannel::new();
+///
+/// // In a producer task:
+/// COMMANDS.send(1, 0x1234).await;
+///
+/// // In the consumer task:
+/// let command = COMMANDS.recv().await;
+/// # }
+/// ```
+pub struct PriorityChannel<T, P, const N: usize, const W: usize = 4> {
+    state: Mutex<RefCell<State<T, P, N, W>>>,
+}
+
+impl<T, P: Ord, const N: usize, const W: usize> PriorityChannel<T, P, N, W> {
+    /// Creates an empty channel.
+    pub const fn new() -> Self {
+        Self {
+            state: Mutex::new(RefCell::new(State {
+                queue: KeyedPriorityQueue::new(),
+                next_seq: 0,
+                not_empty: Deque::new(),
+                not_full: Deque::new(),
+            })),
+        }
+    }
+
+    fn with_state<R>(&self, f: impl FnOnce(&mut State<T, P, N, W>) -> R) -> R {
+        critical_section::with(|cs| f(&mut self.state.borrow_ref_mut(cs)))
+    }
+
+    /// The maximum number of messages the channel can hold.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// The number of messages waiting to be received.
+    pub fn len(&self) -> usize {
+        self.with_state(|state| state.queue.len())
+    }
+
+    /// Returns `true` if there are no messages waiting.
+    pub fn is_empty(&self) -> bool {
+        self.with_state(|state| state.queue.is_empty())
+    }
+
+    /// Returns `true` if the channel is at capacity.
+    pub fn is_full(&self) -> bool {
+        self.with_state(|state| state.queue.is_full())
+    }
+
+    /// Sends a message without waiting.
+    ///
+    /// Returns `Err(value)` if the channel is full.
+    pub fn try_send(&self, priority: P, value: T) -> Result<(), T> {
+        self.with_state(|state| state.push(priority, value))
+    }
+
+    /// Receives the message with the highest priority without waiting.
+    ///
+    /// Returns `None` if the channel is empty.
+    pub fn try_recv(&self) -> Option<T> {
+        self.try_recv_with_priority().map(|(_, value)| value)
+    }
+
+    /// Receives the message with the highest priority, along with its
+    /// priority, without waiting.
+    ///
+    /// Returns `None` if the channel is empty.
+    pub fn try_recv_with_priority(&self) -> Option<(P, T)> {
+        self.with_state(|state| state.pop())
+    }
+
+    /// Sends a message, waiting until there is space.
+    pub fn send(&self, priority: P, value: T) -> Send<'_, T, P, N, W> {
+        Send {
+            channel: self,
+            message: Some((priority, value)),
+        }
+    }
+
+    /// Receives the message with the highest priority, waiting until there
+    /// is one.
+    pub fn recv(&self) -> Recv<'_, T, P, N, W> {
+        Recv { channel: self }
+    }
+
+    /// Like [`recv`](Self::recv), but also returns the message's priority.
+    pub fn recv_with_priority(&self) -> RecvWithPriority<'_, T, P, N, W> {
+        RecvWithPriority { channel: self }
+    }
+
+    pub(crate) fn poll_recv(&self, cx: &mut Context<'_>) -> Poll<(P, T)> {
+        self.with_state(|state| match state.pop() {
+            Some(message) => Poll::Ready(message),
+            None => {
+                watch(&mut state.not_empty, cx);
+                Poll::Pending
+            }
+        })
+    }
+}
+
+impl<T, P: Ord, const N: usize, const W: usize> Default for PriorityChannel<T, P, N, W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Future returned by [`PriorityChannel::send`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Send<'a, T, P, const N: usize, const W: usize> {
+    channel: &'a PriorityChannel<T, P, N, W>,
+
+    /// `None` once it has been sent.
+    message: Option<(P, T)>,
+}
+
+// The message is never pinned.
+impl<T, P, const N: usize, const W: usize> Unpin for Send<'_, T, P, N, W> {}
+
+impl<T, P: Ord, const N: usize, const W: usize> Future for Send<'_, T, P, N, W> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let message = &mut this.message;
+        this.channel.with_state(|state| {
+            let (priority, value) = message.take().expect("polled after completion");
+            if state.queue.is_full() {
+                *message = Some((priority, value));
+                watch(&mut state.not_full, cx);
+                return Poll::Pending;
+            }
+            // Can't fail, since there is space.
+            let _ = state.push(priority, value);
+            Poll::Ready(())
+        })
+    }
+}
+
+/// Future returned by [`PriorityChannel::recv`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Recv<'a, T, P, const N: usize, const W: usize> {
+    channel: &'a PriorityChannel<T, P, N, W>,
+}
+
+impl<T, P: Ord, const N: usize, const W: usize> Future for Recv<'_, T, P, N, W> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.channel.poll_recv(cx).map(|(_, value)| value)
+    }
+}
+
+/// Future returned by [`PriorityChannel::recv_with_priority`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct RecvWithPriority<'a, T, P, const N: usize, const W: usize> {
+    channel: &'a PriorityChannel<T, P, N, W>,
+}
+
+impl<T, P: Ord, const N: usize, const W: usize> Future for RecvWithPriority<'_, T, P, N, W> {
+    type Output = (P, T);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.channel.poll_recv(cx)
+    }
+}
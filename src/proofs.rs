@@ -0,0 +1,168 @@
+//! Kani proof harnesses for the index arithmetic behind the unsafe code.
+//!
+//! Pushes, pops and `get` read and write the storage without bounds checks,
+//! trusting that `start`, `end` and `len` always describe valid slots. Each
+//! harness runs every sequence of operations (up to a length) on a deque of
+//! every capacity (up to a size), checking after each step that
+//! [`assert_invariants`](crate::DequeBase::assert_invariants) holds and that
+//! the contents match an obviously correct model. Kani also checks every
+//! pointer access along the way.
+//!
+//! Run them with:
+//!
+//! ```text
+//! cargo kani --features kani
+//! ```
+
+use core::mem::MaybeUninit;
+
+use crate::{Deque, DequeBase, DequeView, Storage};
+
+/// The largest capacity checked.
+const MAX_CAPACITY: usize = 4;
+
+/// The number of operations in each sequence.
+const STEPS: usize = 6;
+
+/// A deque that keeps its elements at the start of an array, shifting them
+/// along on every push and pop at the front.
+struct Model {
+    values: [u8; MAX_CAPACITY],
+    len: usize,
+    capacity: usize,
+}
+
+impl Model {
+    fn new(capacity: usize) -> Self {
+        Self {
+            values: [0; MAX_CAPACITY],
+            len: 0,
+            capacity,
+        }
+    }
+
+    fn push_front(&mut self, value: u8) -> bool {
+        if self.len == self.capacity {
+            return false;
+        }
+        let mut i = self.len;
+        while i > 0 {
+            self.values[i] = self.values[i - 1];
+            i -= 1;
+        }
+        self.values[0] = value;
+        self.len += 1;
+        true
+    }
+
+    fn push_back(&mut self, value: u8) -> bool {
+        if self.len == self.capacity {
+            return false;
+        }
+        self.values[self.len] = value;
+        self.len += 1;
+        true
+    }
+
+    fn pop_front(&mut self) -> Option<u8> {
+        if self.len == 0 {
+            return None;
+        }
+        let value = self.values[0];
+        for i in 1..self.len {
+            self.values[i - 1] = self.values[i];
+        }
+        self.len -= 1;
+        Some(value)
+    }
+
+    fn pop_back(&mut self) -> Option<u8> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        Some(self.values[self.len])
+    }
+
+    fn get(&self, index: usize) -> Option<u8> {
+        if index < self.len {
+            Some(self.values[index])
+        } else {
+            None
+        }
+    }
+}
+
+/// Runs an arbitrary sequence of operations on `deque` and `model`, which
+/// must start out the same.
+fn check_operations<S: Storage<u8>>(deque: &mut DequeBase<u8, S>, model: &mut Model) {
+    for _ in 0..STEPS {
+        match kani::any::<u8>() % 6 {
+            0 => {
+                let value = kani::any();
+                assert_eq!(deque.push_front(value).is_ok(), model.push_front(value));
+            }
+            1 => {
+                let value = kani::any();
+                assert_eq!(deque.push_back(value).is_ok(), model.push_back(value));
+            }
+            2 => assert_eq!(deque.pop_front(), model.pop_front()),
+            3 => assert_eq!(deque.pop_back(), model.pop_back()),
+            4 => {
+                let index = kani::any();
+                assert_eq!(deque.get(index).copied(), model.get(index));
+            }
+            _ => {
+                deque.clear();
+                model.len = 0;
+            }
+        }
+        deque.assert_invariants();
+        assert_eq!(deque.len(), model.len);
+        assert_eq!(deque.front().copied(), model.get(0));
+        assert_eq!(deque.back().copied(), model.get(model.len.wrapping_sub(1)));
+    }
+}
+
+/// Checks borrowed storage of every capacity up to `MAX_CAPACITY`,
+/// including zero.
+#[kani::proof]
+#[kani::unwind(7)]
+fn operations_any_capacity() {
+    let capacity: usize = kani::any();
+    kani::assume(capacity <= MAX_CAPACITY);
+    let mut buf = [MaybeUninit::uninit(); MAX_CAPACITY];
+    let mut deque = DequeView::new(&mut buf[..capacity]);
+    check_operations(&mut deque, &mut Model::new(capacity));
+}
+
+/// Checks inline storage, whose capacity is a constant.
+#[kani::proof]
+#[kani::unwind(7)]
+fn operations_inline() {
+    let mut deque: Deque<u8, 3> = Deque::new();
+    check_operations(&mut deque, &mut Model::new(3));
+}
+
+/// Checks that `get` finds every element wherever the contents start in
+/// the storage, including when they wrap around the end.
+#[kani::proof]
+#[kani::unwind(5)]
+fn get_after_rotation() {
+    let mut deque: Deque<u8, MAX_CAPACITY> = Deque::new();
+    let rotation: usize = kani::any();
+    kani::assume(rotation < MAX_CAPACITY);
+    for _ in 0..rotation {
+        deque.push_back(0).unwrap();
+        deque.pop_front().unwrap();
+    }
+    let len: usize = kani::any();
+    kani::assume(len <= MAX_CAPACITY);
+    for i in 0..len {
+        deque.push_back(i as u8).unwrap();
+    }
+    let index: usize = kani::any();
+    kani::assume(index < len);
+    assert_eq!(deque.get(index), Some(&(index as u8)));
+    assert_eq!(deque.get(len), None);
+}
@@ -0,0 +1,249 @@
+//! A bump allocator over a fixed region, for short-lived allocations.
+
+use core::{
+    alloc::Layout,
+    cell::{Cell, UnsafeCell},
+    mem::MaybeUninit,
+    ptr::NonNull,
+};
+
+/// An allocator that hands out memory from an `N` byte region by bumping an
+/// offset, for allocations that all go away together, e.g. while building
+/// one response packet.
+///
+/// Freeing the most recent allocation gives its space back, and once every
+/// allocation has been freed, allocating starts over from the beginning of
+/// the region, so a region reused once per cycle doesn't run out. Memory
+/// freed out of order is otherwise only reclaimed by
+/// [`reset`](Self::reset), which starts a new [epoch](Self::epoch).
+///
+/// With the `allocator-api` feature (which requires a nightly compiler),
+/// `&RegionAllocator` implements `core::alloc::Allocator`, so collections
+/// like `Vec::new_in` can allocate from it. Without it, [`alloc`] and
+/// [`dealloc`] can be called directly.
+///
+/// [`alloc`]: Self::alloc
+/// [`dealloc`]: Self::dealloc
+///
+/// # Examples
+///
+/// ```
+/// use core::alloc::Layout;
+/// use fullhouse::region::RegionAllocator;
+///
+/// let mut region: RegionAllocator<64> = RegionAllocator::new();
+/// let header = region.alloc(Layout::new::<[u8; 8]>()).unwrap();
+/// let body = region.alloc(Layout::new::<[u8; 32]>()).unwrap();
+/// assert_eq!(region.used(), 40);
+/// assert!(region.alloc(Layout::new::<[u8; 32]>()).is_none());
+///
+/// // Freeing everything starts over.
+/// unsafe {
+///     region.dealloc(header, Layout::new::<[u8; 8]>());
+///     region.dealloc(body, Layout::new::<[u8; 32]>());
+/// }
+/// assert_eq!(region.used(), 0);
+///
+/// // Or start a new epoch, forgetting everything allocated.
+/// region.alloc(Layout::new::<u64>()).unwrap();
+/// region.reset();
+/// assert_eq!((region.used(), region.epoch()), (0, 1));
+/// ```
+pub struct RegionAllocator<const N: usize> {
+    memory: UnsafeCell<[MaybeUninit<u8>; N]>,
+
+    /// The offset of the first free byte.
+    head: Cell<usize>,
+
+    /// The number of allocations not freed yet.
+    live: Cell<usize>,
+
+    epoch: Cell<u32>,
+}
+
+impl<const N: usize> RegionAllocator<N> {
+    /// Creates an allocator with nothing allocated.
+    pub const fn new() -> Self {
+        Self {
+            memory: UnsafeCell::new([MaybeUninit::uninit(); N]),
+            head: Cell::new(0),
+            live: Cell::new(0),
+            epoch: Cell::new(0),
+        }
+    }
+
+    /// The size of the region in bytes.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// The number of bytes from the start of the region to the end of the
+    /// last allocation, including padding and freed allocations not
+    /// reclaimed yet.
+    pub fn used(&self) -> usize {
+        self.head.get()
+    }
+
+    /// The number of allocations that haven't been freed.
+    pub fn live(&self) -> usize {
+        self.live.get()
+    }
+
+    /// The number of times the allocator has been [reset](Self::reset),
+    /// wrapping around on overflow.
+    pub fn epoch(&self) -> u32 {
+        self.epoch.get()
+    }
+
+    fn base(&self) -> *mut u8 {
+        self.memory.get().cast()
+    }
+
+    /// Allocates memory for `layout`, or returns `None` if it doesn't fit
+    /// in the rest of the region.
+    ///
+    /// The memory is uninitialized.
+    pub fn alloc(&self, layout: Layout) -> Option<NonNull<u8>> {
+        let head = self.head.get();
+        let addr = (self.base() as usize).checked_add(head)?;
+        let padding = addr.wrapping_neg() & (layout.align() - 1);
+        let start = head.checked_add(padding)?;
+        let end = start.checked_add(layout.size())?;
+        if end > N {
+            return None;
+        }
+        self.head.set(end);
+        self.live.set(self.live.get() + 1);
+        // Safety: `start` is at most `N`, so the pointer is in bounds (or one
+        // past the end, for a zero-sized allocation at the end).
+        NonNull::new(unsafe { self.base().add(start) })
+    }
+
+    /// Frees memory returned by [`alloc`](Self::alloc).
+    ///
+    /// If it was the most recent allocation, its space can be allocated
+    /// again, and once every allocation is freed, the whole region can.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by `alloc` with the same `layout`
+    /// during the current epoch, and not freed already.
+    pub unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
+        let live = self.live.get() - 1;
+        self.live.set(live);
+        let start = ptr.as_ptr().offset_from(self.base()) as usize;
+        if live == 0 {
+            self.head.set(0);
+        } else if start + layout.size() == self.head.get() {
+            self.head.set(start);
+        }
+    }
+
+    /// Resizes the allocation at `ptr` without moving it, which only works
+    /// for the most recent allocation. Returns `false` if it couldn't be
+    /// resized.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by `alloc` with `old_size` during the
+    /// current epoch, and not freed already.
+    #[cfg(feature = "allocator-api")]
+    unsafe fn resize_in_place(&self, ptr: NonNull<u8>, old_size: usize, new_size: usize) -> bool {
+        let start = ptr.as_ptr().offset_from(self.base()) as usize;
+        if start + old_size != self.head.get() || new_size > N - start {
+            return false;
+        }
+        self.head.set(start + new_size);
+        true
+    }
+
+    /// Forgets every allocation and starts a new epoch, making the whole
+    /// region available again.
+    ///
+    /// Taking `&mut self` ensures nothing borrowing the allocator (like a
+    /// collection allocating from it) is still around. Pointers returned by
+    /// [`alloc`](Self::alloc) must not be used after this.
+    pub fn reset(&mut self) {
+        self.head.set(0);
+        self.live.set(0);
+        self.epoch.set(self.epoch.get().wrapping_add(1));
+    }
+}
+
+impl<const N: usize> Default for RegionAllocator<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lets collections allocate from the region, e.g. with `Vec::new_in`.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(allocator_api)]
+///
+/// use fullhouse::region::RegionAllocator;
+///
+/// let mut region: RegionAllocator<256> = RegionAllocator::new();
+/// for seq in 0..3u8 {
+///     let mut packet = Vec::with_capacity_in(16, &region);
+///     packet.extend_from_slice(&[0xaa, seq]);
+///     packet.extend_from_slice(b"payload");
+///     assert_eq!(packet.len(), 9);
+///     drop(packet);
+///
+///     // Each cycle starts with an empty region.
+///     region.reset();
+/// }
+/// ```
+#[cfg(feature = "allocator-api")]
+unsafe impl<const N: usize> core::alloc::Allocator for &RegionAllocator<N> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, core::alloc::AllocError> {
+        let ptr = RegionAllocator::alloc(self, layout).ok_or(core::alloc::AllocError)?;
+        Ok(slice(ptr, layout.size()))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        RegionAllocator::dealloc(self, ptr, layout);
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, core::alloc::AllocError> {
+        let aligned = ptr.as_ptr() as usize % new_layout.align() == 0;
+        if aligned && self.resize_in_place(ptr, old_layout.size(), new_layout.size()) {
+            return Ok(slice(ptr, new_layout.size()));
+        }
+        let new = self.allocate(new_layout)?;
+        // Safety: the new allocation doesn't overlap the old one, which is
+        // still live, and is at least as big.
+        core::ptr::copy_nonoverlapping(ptr.as_ptr(), new.as_ptr().cast(), old_layout.size());
+        self.deallocate(ptr, old_layout);
+        Ok(new)
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, core::alloc::AllocError> {
+        let aligned = ptr.as_ptr() as usize % new_layout.align() == 0;
+        if !aligned {
+            return Err(core::alloc::AllocError);
+        }
+        // If this isn't the last allocation, the tail just goes unused.
+        self.resize_in_place(ptr, old_layout.size(), new_layout.size());
+        Ok(slice(ptr, new_layout.size()))
+    }
+}
+
+#[cfg(feature = "allocator-api")]
+fn slice(ptr: NonNull<u8>, len: usize) -> NonNull<[u8]> {
+    // Safety: `ptr` is not null.
+    unsafe { NonNull::new_unchecked(core::ptr::slice_from_raw_parts_mut(ptr.as_ptr(), len)) }
+}
@@ -0,0 +1,271 @@
+//! Streaming channels that are read directly out of target memory.
+
+use core::{cell::UnsafeCell, ptr};
+
+use crate::sync::{const_fn, AtomicBool, AtomicU32, Ordering};
+
+/// What [`UpWriter::write`] does when the channel doesn't have room for all
+/// of the data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum OverflowMode {
+    /// Write nothing.
+    Skip = 0,
+
+    /// Write as much as fits.
+    Trim = 1,
+
+    /// Wait until the reader makes room.
+    Block = 2,
+}
+
+/// The mask of [`OverflowMode`] in the flags word.
+const MODE_MASK: u32 = 0b11;
+
+/// A one-way byte channel from the target to a debug probe or another core,
+/// in the style of an RTT up buffer.
+///
+/// The channel is a `#[repr(C)]` header followed by the ring buffer, so a
+/// reader with access to the target's memory can consume it directly:
+///
+/// | Offset        | Field    | Description                                  |
+/// |---------------|----------|----------------------------------------------|
+/// | 0             | `name`   | Pointer to a NUL-terminated name.            |
+/// | 1 pointer     | `buffer` | Pointer to the ring buffer.                  |
+/// | 2 pointers    | `size`   | Size of the ring buffer (`u32`).             |
+/// | 2 pointers+4  | `write`  | Offset where the target writes next (`u32`). |
+/// | 2 pointers+8  | `read`   | Offset where the reader reads next (`u32`).  |
+/// | 2 pointers+12 | `flags`  | [`OverflowMode`] in bits 0-1 (`u32`).        |
+///
+/// This is the same as SEGGER RTT's up buffer descriptor. The buffer is
+/// empty when `read == write`, and full when `write` is one byte behind
+/// `read`, so it holds at most `N - 1` bytes. The target only ever updates
+/// `write`, and the reader only ever updates `read`.
+///
+/// Channels are declared with [`up_channel!`], which lets the header point
+/// at its own buffer. On the target, claim the writing end once with
+/// [`writer`](Self::writer). Another core can consume the channel with
+/// [`reader`](Self::reader).
+///
+/// [`up_channel!`]: crate::up_channel
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::up_channel;
+///
+/// up_channel!(TERMINAL: 64, "Terminal");
+///
+/// let mut writer = TERMINAL.writer().unwrap();
+/// writer.write(b"hello");
+///
+/// // Normally the probe does this:
+/// let mut reader = TERMINAL.reader().unwrap();
+/// let mut buf = [0; 64];
+/// let len = reader.read(&mut buf);
+/// assert_eq!(&buf[..len], b"hello");
+/// ```
+#[repr(C)]
+pub struct UpChannel<const N: usize> {
+    name: *const u8,
+    buffer: *mut u8,
+    size: u32,
+    write: AtomicU32,
+    read: AtomicU32,
+    flags: AtomicU32,
+    data: UnsafeCell<[u8; N]>,
+    writer_taken: AtomicBool,
+    reader_taken: AtomicBool,
+}
+
+// Safety: The pointers are only read by external readers. Access to `data`
+// is split between the single writer and the single reader by the offsets.
+unsafe impl<const N: usize> Sync for UpChannel<N> {}
+
+impl<const N: usize> UpChannel<N> {
+    const_fn! {
+        /// Creates a channel at the address `this`. Use [`up_channel!`]
+        /// instead.
+        ///
+        /// [`up_channel!`]: crate::up_channel
+        ///
+        /// # Safety
+        ///
+        /// - `name` must point to a NUL-terminated string that lives forever.
+        /// - The returned value must be stored at `this` and never moved, i.e.
+        ///   it must initialize a static located at `this`.
+        #[doc(hidden)]
+        pub unsafe fn new(name: *const u8, this: *const Self) -> Self {
+            assert!(N > 1 && N <= u32::MAX as usize, "Invalid UpChannel size");
+            Self {
+                name,
+                buffer: ptr::addr_of!((*this).data) as *mut u8,
+                size: N as u32,
+                write: AtomicU32::new(0),
+                read: AtomicU32::new(0),
+                flags: AtomicU32::new(OverflowMode::Skip as u32),
+                data: UnsafeCell::new([0; N]),
+                writer_taken: AtomicBool::new(false),
+                reader_taken: AtomicBool::new(false),
+            }
+        }
+    }
+
+    /// Claims the writing end of the channel, or returns `None` if it was
+    /// already claimed.
+    pub fn writer(&'static self) -> Option<UpWriter<N>> {
+        if self.writer_taken.swap(true, Ordering::Acquire) {
+            None
+        } else {
+            Some(UpWriter { channel: self })
+        }
+    }
+
+    /// Claims the reading end of the channel, or returns `None` if it was
+    /// already claimed.
+    ///
+    /// A debug probe doesn't need this; it reads the header directly. Don't
+    /// use both at once.
+    pub fn reader(&'static self) -> Option<UpReader<N>> {
+        if self.reader_taken.swap(true, Ordering::Acquire) {
+            None
+        } else {
+            Some(UpReader { channel: self })
+        }
+    }
+
+    /// The current overflow mode.
+    pub fn mode(&self) -> OverflowMode {
+        match self.flags.load(Ordering::Relaxed) & MODE_MASK {
+            0 => OverflowMode::Skip,
+            1 => OverflowMode::Trim,
+            _ => OverflowMode::Block,
+        }
+    }
+
+    /// Sets the overflow mode.
+    ///
+    /// A debug probe may also change the mode by writing the flags word.
+    pub fn set_mode(&self, mode: OverflowMode) {
+        let flags = self.flags.load(Ordering::Relaxed);
+        self.flags
+            .store((flags & !MODE_MASK) | mode as u32, Ordering::Relaxed);
+    }
+
+    /// The number of bytes that can be written before the buffer is full.
+    fn free(&self, write: usize) -> usize {
+        let read = self.read.load(Ordering::Acquire) as usize;
+        if read > write {
+            read - write - 1
+        } else {
+            N - 1 - write + read
+        }
+    }
+}
+
+/// The writing end of an [`UpChannel`].
+pub struct UpWriter<const N: usize> {
+    channel: &'static UpChannel<N>,
+}
+
+impl<const N: usize> UpWriter<N> {
+    /// Writes `data` to the channel, returning the number of bytes written.
+    ///
+    /// What happens when there isn't enough room depends on the channel's
+    /// [`OverflowMode`]. In [`Block`](OverflowMode::Block) mode, this waits
+    /// for the reader to make room, so it must not be used while nothing is
+    /// reading (e.g. no probe is attached).
+    pub fn write(&mut self, mut data: &[u8]) -> usize {
+        let channel = self.channel;
+        let mode = channel.mode();
+        let mut write = channel.write.load(Ordering::Relaxed) as usize;
+        let mut written = 0;
+
+        while !data.is_empty() {
+            let free = channel.free(write);
+            if mode == OverflowMode::Skip && free < data.len() {
+                break;
+            }
+            if free == 0 {
+                if mode == OverflowMode::Block {
+                    core::hint::spin_loop();
+                    continue;
+                }
+                break;
+            }
+
+            // Copy up to the end of the buffer; the rest goes at the start
+            // on the next iteration.
+            let len = data.len().min(free).min(N - write);
+            // Safety: `write..write + len` is in bounds, and is free space
+            // that the reader does not access until `write` is published.
+            unsafe {
+                let dst = (channel.data.get() as *mut u8).add(write);
+                ptr::copy_nonoverlapping(data.as_ptr(), dst, len);
+            }
+            write = (write + len) % N;
+            channel.write.store(write as u32, Ordering::Release);
+
+            data = &data[len..];
+            written += len;
+        }
+        written
+    }
+}
+
+/// The reading end of an [`UpChannel`], for a consumer running on the
+/// target itself or on another core.
+pub struct UpReader<const N: usize> {
+    channel: &'static UpChannel<N>,
+}
+
+impl<const N: usize> UpReader<N> {
+    /// Moves buffered bytes into `buf`, returning the number of bytes read.
+    pub fn read(&mut self, buf: &mut [u8]) -> usize {
+        let channel = self.channel;
+        let write = channel.write.load(Ordering::Acquire) as usize;
+        let mut read = channel.read.load(Ordering::Relaxed) as usize;
+        let mut count = 0;
+
+        while count < buf.len() && read != write {
+            let available = if write > read { write - read } else { N - read };
+            let len = available.min(buf.len() - count);
+            // Safety: `read..read + len` is in bounds, and holds data that
+            // the writer does not modify until `read` is published.
+            unsafe {
+                let src = (channel.data.get() as *const u8).add(read);
+                ptr::copy_nonoverlapping(src, buf[count..].as_mut_ptr(), len);
+            }
+            read = (read + len) % N;
+            count += len;
+        }
+        channel.read.store(read as u32, Ordering::Release);
+        count
+    }
+}
+
+/// Declares a `static` [`UpChannel`](crate::rtt::UpChannel).
+///
+/// `up_channel!(NAME: N, "label")` declares a static named `NAME` holding an
+/// `UpChannel<N>`, whose header names it `label`.
+///
+/// # Examples
+///
+/// ```
+/// fullhouse::up_channel!(DEFMT: 1024, "defmt");
+///
+/// DEFMT.set_mode(fullhouse::rtt::OverflowMode::Trim);
+/// ```
+#[macro_export]
+macro_rules! up_channel {
+    ($name:ident: $capacity:expr, $label:literal) => {
+        static $name: $crate::rtt::UpChannel<$capacity> = unsafe {
+            // Safety: The label is a NUL-terminated string literal, and the
+            // channel is constructed in place as the static itself.
+            $crate::rtt::UpChannel::new(
+                ::core::concat!($label, "\0").as_ptr(),
+                ::core::ptr::addr_of!($name),
+            )
+        };
+    };
+}
@@ -0,0 +1,111 @@
+//! Searching byte deques.
+
+use core::mem::size_of;
+
+use crate::{DequeBase, Storage};
+
+impl<S: Storage<u8>> DequeBase<u8, S> {
+    /// Returns the index of the first occurrence of `byte`, or `None` if the
+    /// deque doesn't contain it.
+    ///
+    /// This checks a whole machine word at a time, so it is much faster than
+    /// looking at each byte, e.g. when scanning for frame delimiters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut rx: Deque<u8, 64> = Deque::new();
+    /// rx.push_back_slice(b"AT+OK\r\nAT");
+    /// assert_eq!(rx.find_byte(b'\n'), Some(6));
+    /// assert_eq!(rx.find_byte(b'?'), None);
+    /// ```
+    pub fn find_byte(&self, byte: u8) -> Option<usize> {
+        self.find_byte_from(byte, 0)
+    }
+
+    /// Returns the index of the first occurrence of `byte` at or after
+    /// index `offset`.
+    ///
+    /// This lets a parser that is waiting for more data skip the part it has
+    /// already searched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut rx: Deque<u8, 64> = Deque::new();
+    /// rx.push_back_slice(b"a,b,c");
+    /// assert_eq!(rx.find_byte_from(b',', 2), Some(3));
+    /// ```
+    pub fn find_byte_from(&self, byte: u8, offset: usize) -> Option<usize> {
+        let (front, back) = self.as_slices();
+        if offset < front.len() {
+            if let Some(i) = memchr(byte, &front[offset..]) {
+                return Some(offset + i);
+            }
+        }
+        let skip = offset.saturating_sub(front.len());
+        let i = memchr(byte, back.get(skip..)?)?;
+        Some(front.len() + skip + i)
+    }
+
+    /// Returns an iterator over the indexes of all occurrences of `byte`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut rx: Deque<u8, 64> = Deque::new();
+    /// rx.push_back_slice(b"one\ntwo\nthr");
+    /// assert!(rx.byte_positions(b'\n').eq([3, 7]));
+    /// ```
+    pub fn byte_positions(&self, byte: u8) -> impl Iterator<Item = usize> + '_ {
+        let mut offset = 0;
+        core::iter::from_fn(move || {
+            let position = self.find_byte_from(byte, offset)?;
+            offset = position + 1;
+            Some(position)
+        })
+    }
+}
+
+/// `0x0101...01`
+const LO: usize = usize::MAX / 0xff;
+
+/// `0x8080...80`
+const HI: usize = LO * 0x80;
+
+/// Returns `true` if any byte of `word` is zero.
+///
+/// Subtracting 1 from each byte only borrows out of (and so sets the high
+/// bit of) a byte that was zero, or that had its high bit set already, which
+/// `!word` excludes.
+fn has_zero_byte(word: usize) -> bool {
+    word.wrapping_sub(LO) & !word & HI != 0
+}
+
+/// Returns the index of the first occurrence of `byte` in `haystack`.
+pub(crate) fn memchr(byte: u8, haystack: &[u8]) -> Option<usize> {
+    // Safety: Every bit pattern is a valid `usize`.
+    let (prefix, words, suffix) = unsafe { haystack.align_to::<usize>() };
+    if let Some(i) = prefix.iter().position(|&b| b == byte) {
+        return Some(i);
+    }
+
+    // XORing turns matching bytes into zero bytes.
+    let repeated = LO * usize::from(byte);
+    let start = words
+        .iter()
+        .position(|&word| has_zero_byte(word ^ repeated))
+        .map_or(haystack.len() - suffix.len(), |i| {
+            prefix.len() + i * size_of::<usize>()
+        });
+
+    // Find the byte within the matching word, or in the unaligned suffix.
+    let i = haystack[start..].iter().position(|&b| b == byte)?;
+    Some(start + i)
+}
@@ -0,0 +1,241 @@
+//! Waiting on whichever of several queues has an element first.
+//!
+//! A [`Select`] is built from a list of queues, each with a function that
+//! turns its elements into a common type, e.g. the variants of an event
+//! enum. [`try_select`](Select::try_select) takes the first available
+//! element without waiting, for a main loop that sleeps between polls, and
+//! awaiting the `Select` waits until one of the queues has an element.
+
+use core::{
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{AsyncDeque, CsDeque};
+
+/// A queue that elements can be taken from without waiting.
+pub trait TryReceive {
+    /// The type of the elements.
+    type Item;
+
+    /// Removes the next element, or returns `None` if there isn't one.
+    fn try_receive(&self) -> Option<Self::Item>;
+}
+
+/// A queue that can wake a task when it may have an element.
+pub trait Receive: TryReceive {
+    /// Registers the task in `cx` to be woken when the queue may have an
+    /// element.
+    fn register(&self, cx: &mut Context<'_>);
+}
+
+impl<T, const N: usize> TryReceive for CsDeque<T, N> {
+    type Item = T;
+
+    fn try_receive(&self) -> Option<T> {
+        self.pop_front()
+    }
+}
+
+impl<T, const N: usize, const W: usize> TryReceive for AsyncDeque<T, N, W> {
+    type Item = T;
+
+    fn try_receive(&self) -> Option<T> {
+        self.try_pop_front()
+    }
+}
+
+impl<T, const N: usize, const W: usize> Receive for AsyncDeque<T, N, W> {
+    fn register(&self, cx: &mut Context<'_>) {
+        self.register_not_empty(cx);
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for () {}
+
+    impl<A, R: ?Sized, F> Sealed for (A, super::Arm<'_, R, F>) {}
+}
+
+/// One queue of a [`Select`], and the function applied to its elements.
+pub struct Arm<'a, R: ?Sized, F> {
+    receiver: &'a R,
+    map: F,
+}
+
+/// The list of arms of a [`Select`] producing `O`.
+///
+/// This is implemented for the nested tuples built by [`Select::recv`], and
+/// can't be implemented outside this crate.
+pub trait Arms<O>: sealed::Sealed {
+    #[doc(hidden)]
+    fn try_select(&mut self) -> Option<O>;
+}
+
+/// The list of arms of a [`Select`] that can be awaited.
+pub trait AsyncArms<O>: Arms<O> {
+    #[doc(hidden)]
+    fn register(&self, cx: &mut Context<'_>);
+}
+
+impl<O> Arms<O> for () {
+    fn try_select(&mut self) -> Option<O> {
+        None
+    }
+}
+
+impl<O> AsyncArms<O> for () {
+    fn register(&self, _cx: &mut Context<'_>) {}
+}
+
+impl<O, A, R, F> Arms<O> for (A, Arm<'_, R, F>)
+where
+    A: Arms<O>,
+    R: TryReceive + ?Sized,
+    F: FnMut(R::Item) -> O,
+{
+    fn try_select(&mut self) -> Option<O> {
+        let (earlier, arm) = self;
+        earlier
+            .try_select()
+            .or_else(|| arm.receiver.try_receive().map(&mut arm.map))
+    }
+}
+
+impl<O, A, R, F> AsyncArms<O> for (A, Arm<'_, R, F>)
+where
+    A: AsyncArms<O>,
+    R: Receive + ?Sized,
+    F: FnMut(R::Item) -> O,
+{
+    fn register(&self, cx: &mut Context<'_>) {
+        self.0.register(cx);
+        self.1.receiver.register(cx);
+    }
+}
+
+/// Takes an element from whichever of several queues has one first.
+///
+/// Arms are added with [`recv`](Self::recv), and are checked in the order
+/// they were added, so earlier arms take priority when several queues have
+/// elements.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::{select::Select, CsDeque};
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Event {
+///     Radio(u8),
+///     Uart(u8),
+///     Tick,
+/// }
+///
+/// static RADIO: CsDeque<u8, 4> = CsDeque::new();
+/// static UART: CsDeque<u8, 16> = CsDeque::new();
+/// static TIMER: CsDeque<(), 1> = CsDeque::new();
+///
+/// UART.push_back(b'a').unwrap();
+/// TIMER.push_back(()).unwrap();
+///
+/// let mut select = Select::new()
+///     .recv(&RADIO, Event::Radio)
+///     .recv(&UART, Event::Uart)
+///     .recv(&TIMER, |()| Event::Tick);
+/// assert_eq!(select.try_select(), Some(Event::Uart(b'a')));
+/// assert_eq!(select.try_select(), Some(Event::Tick));
+/// assert_eq!(select.try_select(), None);
+/// ```
+///
+/// Awaiting a `Select` whose queues are all [`AsyncDeque`]s (or other
+/// [`Receive`] implementations) waits for the first element:
+///
+/// ```
+/// # async fn example() {
+/// use fullhouse::{select::Select, AsyncDeque};
+///
+/// static COMMANDS: AsyncDeque<u32, 8> = AsyncDeque::new();
+/// static SAMPLES: AsyncDeque<u16, 8> = AsyncDeque::new();
+///
+/// loop {
+///     let event = Select::new()
+///         .recv(&COMMANDS, Ok)
+///         .recv(&SAMPLES, Err)
+///         .await;
+///     match event {
+///         Ok(command) => { /* ... */ }
+///         Err(sample) => { /* ... */ }
+///     }
+/// }
+/// # }
+/// ```
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Select<O, A> {
+    arms: A,
+    _output: PhantomData<fn() -> O>,
+}
+
+impl<O> Select<O, ()> {
+    /// Creates a `Select` with no arms.
+    pub const fn new() -> Self {
+        Self {
+            arms: (),
+            _output: PhantomData,
+        }
+    }
+}
+
+impl<O> Default for Select<O, ()> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<O, A> Select<O, A> {
+    /// Adds an arm that takes elements from `receiver`, and turns them into
+    /// the output with `map`.
+    pub fn recv<R, F>(self, receiver: &R, map: F) -> Select<O, (A, Arm<'_, R, F>)>
+    where
+        R: TryReceive + ?Sized,
+        F: FnMut(R::Item) -> O,
+    {
+        Select {
+            arms: (self.arms, Arm { receiver, map }),
+            _output: PhantomData,
+        }
+    }
+
+    /// Takes an element from the first queue that has one, without waiting.
+    pub fn try_select(&mut self) -> Option<O>
+    where
+        A: Arms<O>,
+    {
+        self.arms.try_select()
+    }
+}
+
+// Nothing is pinned.
+impl<O, A> Unpin for Select<O, A> {}
+
+impl<O, A: AsyncArms<O>> Future for Select<O, A> {
+    type Output = O;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<O> {
+        let this = self.get_mut();
+        if let Some(output) = this.arms.try_select() {
+            return Poll::Ready(output);
+        }
+        this.arms.register(cx);
+        // Check again, in case an element arrived before the task was
+        // registered.
+        match this.arms.try_select() {
+            Some(output) => Poll::Ready(output),
+            None => Poll::Pending,
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! A counting semaphore for credit-based flow control.
+
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{
+    sync::{const_fn, AtomicUsize, Ordering},
+    AtomicWaker,
+};
+
+/// A counting semaphore that can be shared between interrupts and tasks.
+///
+/// The semaphore holds a number of permits (or "credits"). [`try_acquire`]
+/// takes permits if enough are available and never blocks, and [`release`]
+/// returns them. Both are lock-free and can be called from any context,
+/// including interrupt handlers.
+///
+/// A single task may also wait for permits asynchronously with [`acquire`].
+/// Only one waker is stored at a time, so if several tasks wait on the same
+/// semaphore, only the most recent one is guaranteed to be woken.
+///
+/// [`try_acquire`]: Self::try_acquire
+/// [`release`]: Self::release
+/// [`acquire`]: Self::acquire
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::Semaphore;
+///
+/// // The consumer grants the producer 2 credits for buffer slots:
+/// static CREDITS: Semaphore = Semaphore::new(2);
+///
+/// assert!(CREDITS.try_acquire());
+/// assert!(CREDITS.try_acquire());
+/// assert!(!CREDITS.try_acquire());
+///
+/// // The consumer frees up a slot:
+/// CREDITS.release();
+/// assert!(CREDITS.try_acquire());
+/// ```
+pub struct Semaphore {
+    permits: AtomicUsize,
+    waker: AtomicWaker,
+}
+
+impl Semaphore {
+    const_fn! {
+        /// Creates a semaphore holding the given number of permits.
+        pub fn new(permits: usize) -> Self {
+            Self {
+                permits: AtomicUsize::new(permits),
+                waker: AtomicWaker::new(),
+            }
+        }
+    }
+
+    /// The number of permits currently available.
+    ///
+    /// This is only a snapshot; it may already be outdated by the time it is
+    /// returned if other contexts are acquiring or releasing permits.
+    pub fn available(&self) -> usize {
+        self.permits.load(Ordering::Acquire)
+    }
+
+    /// Takes one permit, returning `false` if none are available.
+    pub fn try_acquire(&self) -> bool {
+        self.try_acquire_many(1)
+    }
+
+    /// Takes `count` permits at once, returning `false` (and taking none) if
+    /// fewer than `count` are available.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Semaphore;
+    ///
+    /// let sem = Semaphore::new(3);
+    /// assert!(!sem.try_acquire_many(4));
+    /// assert!(sem.try_acquire_many(3));
+    /// assert_eq!(sem.available(), 0);
+    /// ```
+    pub fn try_acquire_many(&self, count: usize) -> bool {
+        self.permits
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |permits| {
+                permits.checked_sub(count)
+            })
+            .is_ok()
+    }
+
+    /// Returns one permit to the semaphore, waking the waiting task if any.
+    pub fn release(&self) {
+        self.release_many(1)
+    }
+
+    /// Returns `count` permits to the semaphore, waking the waiting task if
+    /// any.
+    ///
+    /// The total number of permits must not exceed `usize::MAX`.
+    pub fn release_many(&self, count: usize) {
+        self.permits.fetch_add(count, Ordering::AcqRel);
+        self.waker.wake();
+    }
+
+    /// Attempts to take one permit, registering the task in `cx` to be woken
+    /// when permits are released if none are available.
+    pub fn poll_acquire(&self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.try_acquire() {
+            return Poll::Ready(());
+        }
+        self.waker.register(cx.waker());
+
+        // Check again, in case a permit was released before the waker was
+        // registered.
+        if self.try_acquire() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Waits until a permit is available and takes it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() {
+    /// use fullhouse::Semaphore;
+    ///
+    /// static CREDITS: Semaphore = Semaphore::new(0);
+    ///
+    /// // In the consumer task:
+    /// CREDITS.acquire().await;
+    /// # }
+    /// ```
+    pub fn acquire(&self) -> Acquire<'_> {
+        Acquire { semaphore: self }
+    }
+}
+
+/// Future returned by [`Semaphore::acquire`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Acquire<'a> {
+    semaphore: &'a Semaphore,
+}
+
+impl Future for Acquire<'_> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.semaphore.poll_acquire(cx)
+    }
+}
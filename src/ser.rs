@@ -0,0 +1,17 @@
+//! Serialization of deques as sequences.
+
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+use crate::{DequeBase, Storage};
+
+/// Serializes the elements as a sequence, from front to back.
+impl<T: Serialize, S: Storage<T>> Serialize for DequeBase<T, S> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        let (front, back) = self.as_slices();
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for element in front.iter().chain(back) {
+            seq.serialize_element(element)?;
+        }
+        seq.end()
+    }
+}
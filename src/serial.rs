@@ -0,0 +1,137 @@
+//! Moving data between deques and `embedded-hal` serial ports.
+//!
+//! These are meant to be called whenever the port may be ready, e.g. from
+//! the UART interrupt handler and after the application pushes to the
+//! transmit deque. They move as many words as the port accepts without
+//! blocking, and leave the rest for the next call.
+
+use embedded_hal_nb::{
+    nb,
+    serial::{Read, Write},
+};
+
+use crate::{DequeBase, Storage};
+
+/// Writes words from the front of `ring` to `tx` until `tx` would block or
+/// `ring` is empty, returning the number of words written.
+///
+/// A word is only removed from `ring` once `tx` has accepted it, so nothing
+/// is lost when the port would block or returns an error.
+///
+/// # Examples
+///
+/// ```
+/// use core::convert::Infallible;
+/// use embedded_hal_nb::{
+///     nb,
+///     serial::{ErrorType, Write},
+/// };
+/// use fullhouse::{serial, Deque};
+///
+/// /// A transmitter with a 2-byte FIFO.
+/// struct Uart(Vec<u8>);
+///
+/// impl ErrorType for Uart {
+///     type Error = Infallible;
+/// }
+///
+/// impl Write for Uart {
+///     fn write(&mut self, word: u8) -> nb::Result<(), Infallible> {
+///         if self.0.len() == 2 {
+///             return Err(nb::Error::WouldBlock);
+///         }
+///         self.0.push(word);
+///         Ok(())
+///     }
+///
+///     fn flush(&mut self) -> nb::Result<(), Infallible> {
+///         Ok(())
+///     }
+/// }
+///
+/// let mut tx_ring: Deque<u8, 16> = Deque::new();
+/// for &byte in b"abc" {
+///     tx_ring.push_back(byte).unwrap();
+/// }
+///
+/// let mut uart = Uart(Vec::new());
+/// assert_eq!(serial::drain_to(&mut tx_ring, &mut uart), Ok(2));
+/// assert_eq!(uart.0, b"ab");
+/// assert_eq!(tx_ring.front(), Some(&b'c'));
+/// ```
+pub fn drain_to<W, S, Tx>(ring: &mut DequeBase<W, S>, tx: &mut Tx) -> Result<usize, Tx::Error>
+where
+    W: Copy,
+    S: Storage<W>,
+    Tx: Write<W> + ?Sized,
+{
+    let mut count = 0;
+    while let Some(&word) = ring.front() {
+        match tx.write(word) {
+            Ok(()) => {
+                ring.pop_front();
+                count += 1;
+            }
+            Err(nb::Error::WouldBlock) => break,
+            Err(nb::Error::Other(error)) => return Err(error),
+        }
+    }
+    Ok(count)
+}
+
+/// Reads words from `rx` into the back of `ring` until `rx` would block or
+/// `ring` is full, returning the number of words read.
+///
+/// When `ring` is full, no more words are read, so they stay in the port's
+/// receive buffer (where the hardware may report an overrun). If `rx`
+/// returns an error, the words read before it stay in `ring`.
+///
+/// # Examples
+///
+/// ```
+/// use core::convert::Infallible;
+/// use embedded_hal_nb::{
+///     nb,
+///     serial::{ErrorType, Read},
+/// };
+/// use fullhouse::{serial, Deque};
+///
+/// struct Uart(&'static [u8]);
+///
+/// impl ErrorType for Uart {
+///     type Error = Infallible;
+/// }
+///
+/// impl Read for Uart {
+///     fn read(&mut self) -> nb::Result<u8, Infallible> {
+///         let (&first, rest) = self.0.split_first().ok_or(nb::Error::WouldBlock)?;
+///         self.0 = rest;
+///         Ok(first)
+///     }
+/// }
+///
+/// let mut rx_ring: Deque<u8, 2> = Deque::new();
+/// let mut uart = Uart(b"abc");
+/// assert_eq!(serial::fill_from(&mut rx_ring, &mut uart), Ok(2));
+/// assert_eq!(uart.0, b"c");
+/// ```
+pub fn fill_from<W, S, Rx>(ring: &mut DequeBase<W, S>, rx: &mut Rx) -> Result<usize, Rx::Error>
+where
+    W: Copy,
+    S: Storage<W>,
+    Rx: Read<W> + ?Sized,
+{
+    let mut count = 0;
+    while !ring.is_full() {
+        match rx.read() {
+            Ok(word) => {
+                // Not full, checked above.
+                let _ = ring.push_back(word);
+                count += 1;
+            }
+            Err(nb::Error::WouldBlock) => break,
+            Err(nb::Error::Other(error)) => return Err(error),
+        }
+    }
+    Ok(count)
+}
@@ -0,0 +1,191 @@
+//! Scatter-gather views of a deque's storage.
+//!
+//! The elements of a deque (and its free space) occupy at most two
+//! contiguous regions of its storage, because they may wrap around the end.
+//! [`DequeBase::occupied_segments`] and [`DequeBase::free_segments`] describe
+//! those regions as a list of pointer and length pairs, which can be turned
+//! into a DMA scatter-gather list or passed to a vectored write.
+//!
+//! Once the transfer is done, [`DequeBase::advance_front`] removes the
+//! elements that were sent, and [`DequeBase::advance_back`] adds the
+//! elements that were received.
+//!
+//! # Examples
+//!
+//! ```
+//! use fullhouse::Deque;
+//!
+//! let mut rx: Deque<u8, 8> = Deque::new();
+//! let segments = rx.free_segments();
+//! assert_eq!(segments.len(), 1);
+//!
+//! // The DMA controller receives 3 bytes:
+//! unsafe {
+//!     segments[0].ptr.copy_from_nonoverlapping(b"abc".as_ptr(), 3);
+//!     rx.advance_back(3);
+//! }
+//! assert_eq!(rx.as_slices().0, b"abc");
+//! ```
+
+use core::{
+    mem,
+    ops::{Deref, Range},
+};
+
+use crate::{drop_ranges, DequeBase, Storage};
+
+/// A contiguous region of a deque's storage, in the same layout as a POSIX
+/// `struct iovec`.
+#[derive(Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct Segment<T> {
+    /// A pointer to the first element of the region.
+    pub ptr: *mut T,
+
+    /// The number of elements in the region.
+    pub len: usize,
+}
+
+// Not derived, because that would require `T: Copy`.
+impl<T> Clone for Segment<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Segment<T> {}
+
+/// A list of up to two non-empty [`Segment`]s, in order.
+///
+/// This dereferences to a slice of the segments.
+#[derive(Debug)]
+pub struct SegmentList<T> {
+    segments: [Segment<T>; 2],
+    count: usize,
+}
+
+impl<T> Clone for SegmentList<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for SegmentList<T> {}
+
+impl<T> SegmentList<T> {
+    /// The total number of elements in all segments.
+    pub fn total_len(&self) -> usize {
+        self.iter().map(|segment| segment.len).sum()
+    }
+}
+
+impl<T> Deref for SegmentList<T> {
+    type Target = [Segment<T>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.segments[..self.count]
+    }
+}
+
+impl<T, S: Storage<T>> DequeBase<T, S> {
+    /// Describes the regions of storage holding the deque's elements, from
+    /// front to back.
+    ///
+    /// The pointers are valid for reads until the deque is next modified or
+    /// moved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut tx: Deque<u8, 4> = Deque::new();
+    /// tx.push_back_slice(b"abc");
+    /// tx.pop_front();
+    /// tx.push_back_slice(b"de");
+    ///
+    /// let segments = tx.occupied_segments();
+    /// assert_eq!(segments.len(), 2);
+    /// assert_eq!(segments.total_len(), 4);
+    ///
+    /// // Once the DMA controller has sent everything:
+    /// tx.advance_front(segments.total_len());
+    /// assert!(tx.is_empty());
+    /// ```
+    pub fn occupied_segments(&mut self) -> SegmentList<T> {
+        let (first, second) = self.ranges();
+        self.segment_list(first, second)
+    }
+
+    /// Describes the regions of free storage, in the order that elements
+    /// are added to the back of the deque.
+    ///
+    /// The pointers are valid for writes until the deque is next modified
+    /// or moved. After writing elements to the start of the list, add them
+    /// to the deque with [`advance_back`](Self::advance_back).
+    pub fn free_segments(&mut self) -> SegmentList<T> {
+        if self.is_empty() {
+            // Offer the whole storage as one segment.
+            self.start = 0;
+            self.end = 0;
+        }
+        let (first, second) = self.free_ranges();
+        self.segment_list(first, second)
+    }
+
+    /// Removes up to `count` elements from the front of the deque.
+    ///
+    /// If dropping an element panics, the rest of the `count` elements are
+    /// still dropped, and the deque is left without them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<u8, 4> = Deque::new();
+    /// d.push_back_slice(b"abc");
+    /// d.advance_front(2);
+    /// assert_eq!(d.front(), Some(&b'c'));
+    /// ```
+    pub fn advance_front(&mut self, count: usize) {
+        let count = count.min(self.len);
+        if count == 0 {
+            return;
+        }
+        let (first, second) = self.front_ranges(count);
+
+        // Shrink the region first, like `pop_front`.
+        self.start = self.wrap_add(self.start, count);
+        self.len -= count;
+        self.check_invariants();
+
+        if mem::needs_drop::<T>() {
+            // Safety: `first` and `second` are the first `count` valid
+            // values, which are no longer part of the region.
+            unsafe { drop_ranges(self.as_mut_ptr(), first.clone(), second.clone()) };
+        }
+        self.scrub(first);
+        self.scrub(second);
+    }
+
+    fn segment_list(&mut self, first: Range<usize>, second: Range<usize>) -> SegmentList<T> {
+        let base = self.as_mut_ptr();
+        let segment = |range: Range<usize>| Segment {
+            // Safety: The range is within the storage.
+            ptr: unsafe { base.add(range.start) },
+            len: range.len(),
+        };
+        let mut list = SegmentList {
+            segments: [segment(first), segment(second)],
+            count: 0,
+        };
+        for i in 0..2 {
+            if list.segments[i].len > 0 {
+                list.segments[list.count] = list.segments[i];
+                list.count += 1;
+            }
+        }
+        list
+    }
+}
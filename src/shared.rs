@@ -0,0 +1,324 @@
+//! Deques shared between interrupt handlers and the main program.
+
+use core::cell::{Cell, RefCell};
+
+use critical_section::Mutex;
+
+use crate::Deque;
+
+/// A [`Deque`] guarded by a [`critical_section`], so it can be used from
+/// interrupt handlers through a shared reference, e.g. in a `static`.
+///
+/// Each method enters a critical section, so every operation is atomic
+/// with respect to interrupts. To do several operations at once (e.g. check
+/// the front and then pop it), use [`with`](Self::with).
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::CsDeque;
+///
+/// static RX: CsDeque<u8, 16> = CsDeque::new();
+///
+/// // In the UART interrupt handler:
+/// RX.push_back(b'a').unwrap();
+///
+/// // In the main loop:
+/// assert_eq!(RX.pop_front(), Some(b'a'));
+/// assert!(RX.is_empty());
+/// ```
+pub struct CsDeque<T, const N: usize> {
+    inner: Mutex<RefCell<Deque<T, N>>>,
+}
+
+impl<T, const N: usize> CsDeque<T, N> {
+    /// Creates an empty deque.
+    pub const fn new() -> Self {
+        Self {
+            inner: Mutex::new(RefCell::new(Deque::new())),
+        }
+    }
+
+    /// Calls `f` with exclusive access to the deque, inside a single
+    /// critical section.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from within `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::CsDeque;
+    ///
+    /// let queue: CsDeque<u32, 4> = CsDeque::new();
+    /// queue.push_back(1).unwrap();
+    ///
+    /// // Only pop the front if it is odd.
+    /// let popped = queue.with(|d| match d.front() {
+    ///     Some(&x) if x % 2 == 1 => d.pop_front(),
+    ///     _ => None,
+    /// });
+    /// assert_eq!(popped, Some(1));
+    /// ```
+    pub fn with<R>(&self, f: impl FnOnce(&mut Deque<T, N>) -> R) -> R {
+        critical_section::with(|cs| f(&mut self.inner.borrow_ref_mut(cs)))
+    }
+
+    /// The maximum number of elements the deque can hold.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// The number of elements in the deque.
+    pub fn len(&self) -> usize {
+        self.with(|d| d.len())
+    }
+
+    /// Returns `true` if the deque contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.with(|d| d.is_empty())
+    }
+
+    /// Returns `true` if the deque is at capacity.
+    pub fn is_full(&self) -> bool {
+        self.with(|d| d.is_full())
+    }
+
+    /// Removes all elements from the deque.
+    pub fn clear(&self) {
+        self.with(|d| d.clear())
+    }
+
+    /// Prepends an element to the deque.
+    ///
+    /// Returns `Err(value)` if the deque is full.
+    pub fn push_front(&self, value: T) -> Result<(), T> {
+        self.with(|d| d.push_front(value))
+    }
+
+    /// Appends an element to the deque.
+    ///
+    /// Returns `Err(value)` if the deque is full.
+    pub fn push_back(&self, value: T) -> Result<(), T> {
+        self.with(|d| d.push_back(value))
+    }
+
+    /// Calls `f` with the contiguous slices of elements, and removes as many
+    /// as it reports consumed, inside a single critical section.
+    ///
+    /// See [`DequeBase::consume`](crate::DequeBase::consume).
+    pub fn consume(&self, f: impl FnMut(&[T]) -> usize) -> usize {
+        self.with(|d| d.consume(f))
+    }
+
+    /// Removes the first element and returns it, or `None` if the deque is
+    /// empty.
+    pub fn pop_front(&self) -> Option<T> {
+        self.with(|d| d.pop_front())
+    }
+
+    /// Removes the last element and returns it, or `None` if the deque is
+    /// empty.
+    pub fn pop_back(&self) -> Option<T> {
+        self.with(|d| d.pop_back())
+    }
+}
+
+impl<T: Copy, const N: usize> CsDeque<T, N> {
+    /// Returns a copy of the first element, or `None` if the deque is empty.
+    pub fn front(&self) -> Option<T> {
+        self.with(|d| d.front().copied())
+    }
+
+    /// Returns a copy of the last element, or `None` if the deque is empty.
+    pub fn back(&self) -> Option<T> {
+        self.with(|d| d.back().copied())
+    }
+
+    /// Appends as many elements from `data` as fit, returning how many were
+    /// appended.
+    pub fn push_back_slice(&self, data: &[T]) -> usize {
+        self.with(|d| d.push_back_slice(data))
+    }
+
+    /// Removes elements from the front of the deque into `buf`, returning
+    /// how many were removed.
+    pub fn pop_front_slice(&self, buf: &mut [T]) -> usize {
+        self.with(|d| d.pop_front_slice(buf))
+    }
+}
+
+impl<T, const N: usize> Default for CsDeque<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A queue guarded by a [`critical_section`], which is only used through
+/// [`Producer`] handles that push and a single [`Consumer`] that pops.
+///
+/// Unlike a [`CsDeque`], the queue itself has no methods that change it, so
+/// holding the consumer guarantees that nothing else removes elements, even
+/// though the queue is in a `static` that all the producers can see.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::CsQueue;
+///
+/// static EVENTS: CsQueue<u8, 8> = CsQueue::new();
+///
+/// let uart = EVENTS.producer();
+/// let timer = uart;
+/// let mut consumer = EVENTS.take_consumer().unwrap();
+/// assert!(EVENTS.take_consumer().is_none());
+///
+/// // In the interrupt handlers:
+/// uart.push(1).unwrap();
+/// timer.push(2).unwrap();
+///
+/// // In the main loop:
+/// assert_eq!(consumer.pop(), Some(1));
+/// assert_eq!(consumer.pop(), Some(2));
+/// assert_eq!(consumer.pop(), None);
+/// ```
+pub struct CsQueue<T, const N: usize> {
+    deque: CsDeque<T, N>,
+    consumer_taken: Mutex<Cell<bool>>,
+}
+
+impl<T, const N: usize> CsQueue<T, N> {
+    /// Creates an empty queue.
+    pub const fn new() -> Self {
+        Self {
+            deque: CsDeque::new(),
+            consumer_taken: Mutex::new(Cell::new(false)),
+        }
+    }
+
+    /// Returns a handle that can only push to the back of the queue.
+    ///
+    /// Any number of producers can exist at once, e.g. one for each
+    /// interrupt handler that feeds the queue.
+    pub const fn producer(&self) -> Producer<'_, T, N> {
+        Producer { deque: &self.deque }
+    }
+
+    /// Returns the handle that pops from the front of the queue, or `None`
+    /// if it has already been taken.
+    ///
+    /// Only one consumer is ever handed out, and nothing else can pop, so
+    /// whoever holds it knows that nothing else is removing elements.
+    pub fn take_consumer(&self) -> Option<Consumer<'_, T, N>> {
+        let taken = critical_section::with(|cs| self.consumer_taken.borrow(cs).replace(true));
+        if taken {
+            None
+        } else {
+            Some(Consumer { deque: &self.deque })
+        }
+    }
+
+    /// The maximum number of elements the queue can hold.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// The number of elements in the queue.
+    pub fn len(&self) -> usize {
+        self.deque.len()
+    }
+
+    /// Returns `true` if the queue contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.deque.is_empty()
+    }
+
+    /// Returns `true` if the queue is at capacity.
+    pub fn is_full(&self) -> bool {
+        self.deque.is_full()
+    }
+}
+
+impl<T, const N: usize> Default for CsQueue<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A handle that can only push to a [`CsQueue`].
+///
+/// Producers are `Copy`, so one can be stashed in each interrupt handler
+/// that feeds the queue. Returned by [`CsQueue::producer`].
+pub struct Producer<'a, T, const N: usize> {
+    deque: &'a CsDeque<T, N>,
+}
+
+impl<'a, T, const N: usize> Producer<'a, T, N> {
+    /// Appends an element to the queue.
+    ///
+    /// Returns `Err(value)` if the queue is full.
+    pub fn push(&self, value: T) -> Result<(), T> {
+        self.deque.push_back(value)
+    }
+
+    /// Returns `true` if the queue is at capacity.
+    pub fn is_full(&self) -> bool {
+        self.deque.is_full()
+    }
+}
+
+impl<T: Copy, const N: usize> Producer<'_, T, N> {
+    /// Appends as many elements from `data` as fit, returning how many were
+    /// appended.
+    pub fn push_slice(&self, data: &[T]) -> usize {
+        self.deque.push_back_slice(data)
+    }
+}
+
+impl<T, const N: usize> Clone for Producer<'_, T, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, const N: usize> Copy for Producer<'_, T, N> {}
+
+/// The handle that pops from a [`CsQueue`].
+///
+/// There is at most one consumer for each queue. Returned by
+/// [`CsQueue::take_consumer`].
+pub struct Consumer<'a, T, const N: usize> {
+    deque: &'a CsDeque<T, N>,
+}
+
+impl<'a, T, const N: usize> Consumer<'a, T, N> {
+    /// The number of elements in the queue.
+    pub fn len(&self) -> usize {
+        self.deque.len()
+    }
+
+    /// Returns `true` if the queue contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.deque.is_empty()
+    }
+
+    /// Removes the first element and returns it, or `None` if the queue is
+    /// empty.
+    pub fn pop(&mut self) -> Option<T> {
+        self.deque.pop_front()
+    }
+}
+
+impl<T: Copy, const N: usize> Consumer<'_, T, N> {
+    /// Returns a copy of the first element, or `None` if the queue is empty.
+    pub fn peek(&self) -> Option<T> {
+        self.deque.front()
+    }
+
+    /// Removes elements from the front of the queue into `buf`, returning
+    /// how many were removed.
+    pub fn pop_slice(&mut self, buf: &mut [T]) -> usize {
+        self.deque.pop_front_slice(buf)
+    }
+}
@@ -0,0 +1,150 @@
+//! Implementations of `futures_sink::Sink` for the sending sides of the
+//! async queues.
+//!
+//! The queues can't set space aside for a value before it is sent, so each
+//! sink holds on to one value: [`start_send`](Sink::start_send) stores it,
+//! and the next [`poll_ready`](Sink::poll_ready) or
+//! [`poll_flush`](Sink::poll_flush) waits until it has been pushed.
+
+use core::{
+    convert::Infallible,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_sink::Sink;
+
+use crate::{
+    async_deque::{AsyncDeque, PushSlot},
+    priority_channel::{self, PriorityChannel},
+};
+
+impl<T, const N: usize, const W: usize> AsyncDeque<T, N, W> {
+    /// Creates a sink that appends elements to the deque, waiting for space
+    /// in turn with the other tasks pushing to it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::AsyncDeque;
+    /// use futures_util::{FutureExt, SinkExt};
+    ///
+    /// let deque: AsyncDeque<u8, 1> = AsyncDeque::new();
+    /// let mut sink = deque.push_sink();
+    /// assert_eq!(sink.send(1).now_or_never(), Some(Ok(())));
+    ///
+    /// // The deque is full, so the sink holds on to the element.
+    /// assert_eq!(sink.send(2).now_or_never(), None);
+    /// assert_eq!(deque.try_pop_front(), Some(1));
+    /// assert_eq!(sink.flush().now_or_never(), Some(Ok(())));
+    /// assert_eq!(deque.try_pop_front(), Some(2));
+    /// ```
+    pub fn push_sink(&self) -> PushSink<'_, T, N, W> {
+        PushSink {
+            slot: self.push_slot(),
+            value: None,
+        }
+    }
+}
+
+/// Sink returned by [`AsyncDeque::push_sink`].
+pub struct PushSink<'a, T, const N: usize, const W: usize = 4> {
+    slot: PushSlot<'a, T, N, W>,
+
+    /// The element waiting to be pushed.
+    value: Option<T>,
+}
+
+// The value is never pinned.
+impl<T, const N: usize, const W: usize> Unpin for PushSink<'_, T, N, W> {}
+
+impl<T, const N: usize, const W: usize> Sink<T> for PushSink<'_, T, N, W> {
+    type Error = Infallible;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        self.poll_flush(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Infallible> {
+        self.get_mut().value = Some(item);
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        let this = self.get_mut();
+        if this.value.is_none() {
+            return Poll::Ready(Ok(()));
+        }
+        this.slot.poll_push(cx, &mut this.value).map(Ok)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        self.poll_flush(cx)
+    }
+}
+
+impl<T, P: Ord, const N: usize, const W: usize> PriorityChannel<T, P, N, W> {
+    /// Creates a sink that sends `(priority, message)` pairs, waiting until
+    /// there is space for each.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() {
+    /// use fullhouse::priority_channel::PriorityChannel;
+    /// use futures_util::SinkExt;
+    ///
+    /// static COMMANDS: PriorityChannel<u32, u8, 16> = PriorityChannel::new();
+    ///
+    /// let mut commands = COMMANDS.send_sink();
+    /// commands.send((1, 0x1234)).await.unwrap();
+    /// # }
+    /// ```
+    pub fn send_sink(&self) -> SendSink<'_, T, P, N, W> {
+        SendSink {
+            channel: self,
+            send: None,
+        }
+    }
+}
+
+/// Sink returned by [`PriorityChannel::send_sink`].
+pub struct SendSink<'a, T, P, const N: usize, const W: usize = 4> {
+    channel: &'a PriorityChannel<T, P, N, W>,
+
+    /// The message waiting to be sent.
+    send: Option<priority_channel::Send<'a, T, P, N, W>>,
+}
+
+// The message is never pinned.
+impl<T, P, const N: usize, const W: usize> Unpin for SendSink<'_, T, P, N, W> {}
+
+impl<T, P: Ord, const N: usize, const W: usize> Sink<(P, T)> for SendSink<'_, T, P, N, W> {
+    type Error = Infallible;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        self.poll_flush(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, (priority, value): (P, T)) -> Result<(), Infallible> {
+        let this = self.get_mut();
+        this.send = Some(this.channel.send(priority, value));
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        let this = self.get_mut();
+        if let Some(send) = &mut this.send {
+            if Pin::new(send).poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+            this.send = None;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        self.poll_flush(cx)
+    }
+}
@@ -0,0 +1,177 @@
+//! SLIP framing over byte deques.
+//!
+//! [SLIP] (RFC 1055) ends each frame with an `END` byte, and escapes `END`
+//! and `ESC` bytes inside the frame as two-byte sequences.
+//!
+//! This has the same shape as the [`cobs`](crate::cobs) module: [`encode`]
+//! and [`encode_from`] append an escaped, delimited frame to a byte deque,
+//! and [`next_frame`] finds the next complete frame in a receive buffer and
+//! unescapes it in place. Escape sequences that straddle the end of the
+//! deque's storage are handled by making the frame contiguous first.
+//!
+//! [SLIP]: https://datatracker.ietf.org/doc/html/rfc1055
+//!
+//! # Examples
+//!
+//! ```
+//! use fullhouse::{slip, Deque};
+//!
+//! let mut wire: Deque<u8, 32> = Deque::new();
+//! slip::encode(&[0x01, slip::END, 0x02], &mut wire).unwrap();
+//! assert_eq!(wire.as_slices().0, &[0x01, 0xDB, 0xDC, 0x02, 0xC0]);
+//!
+//! let frame = slip::next_frame(&mut wire).unwrap().unwrap();
+//! assert_eq!(&*frame, &[0x01, slip::END, 0x02]);
+//! drop(frame);
+//!
+//! assert!(wire.is_empty());
+//! ```
+
+use crate::{frames::next_decoded_frame, DequeBase, Storage};
+
+pub use crate::frames::DecodedFrame as Frame;
+
+/// The byte that ends each frame.
+pub const END: u8 = 0xC0;
+
+/// The byte that starts an escape sequence.
+pub const ESC: u8 = 0xDB;
+
+/// Follows [`ESC`] to encode an [`END`] byte in the data.
+pub const ESC_END: u8 = 0xDC;
+
+/// Follows [`ESC`] to encode an [`ESC`] byte in the data.
+pub const ESC_ESC: u8 = 0xDD;
+
+/// An error from encoding or decoding SLIP frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlipError {
+    /// The destination does not have room for the encoded frame. Nothing was
+    /// written.
+    Full,
+
+    /// A frame contained an invalid escape sequence. It has been removed
+    /// from the deque.
+    Malformed,
+
+    /// The deque filled up without an `END` byte, so the frame is longer
+    /// than the deque can hold. The partial frame has been removed from the
+    /// deque.
+    TooLong,
+}
+
+/// Encodes `data` as one frame, including the `END` byte, and appends it to
+/// `dst`.
+pub fn encode<S: Storage<u8>>(data: &[u8], dst: &mut DequeBase<u8, S>) -> Result<(), SlipError> {
+    encode_bytes(data.iter().copied(), dst)
+}
+
+/// Encodes the whole contents of `src` as one frame, including the `END`
+/// byte, and appends it to `dst`.
+///
+/// On success, `src` is left empty. If `dst` does not have room for the
+/// frame, both deques are left unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::{slip, Deque};
+///
+/// let mut message: Deque<u8, 8> = Deque::new();
+/// message.push_back(slip::ESC).unwrap();
+///
+/// let mut wire: Deque<u8, 8> = Deque::new();
+/// slip::encode_from(&mut message, &mut wire).unwrap();
+/// assert!(message.is_empty());
+/// assert_eq!(wire.as_slices().0, &[slip::ESC, slip::ESC_ESC, slip::END]);
+/// ```
+pub fn encode_from<S: Storage<u8>, D: Storage<u8>>(
+    src: &mut DequeBase<u8, S>,
+    dst: &mut DequeBase<u8, D>,
+) -> Result<(), SlipError> {
+    let (front, back) = src.as_slices();
+    encode_bytes(front.iter().chain(back).copied(), dst)?;
+    src.clear();
+    Ok(())
+}
+
+/// Encodes the bytes of `data`, which is iterated twice.
+fn encode_bytes<S: Storage<u8>>(
+    data: impl Iterator<Item = u8> + Clone,
+    dst: &mut DequeBase<u8, S>,
+) -> Result<(), SlipError> {
+    // Check the exact encoded size first, so a frame is never partially
+    // written.
+    let encoded_len = data
+        .clone()
+        .map(|byte| if byte == END || byte == ESC { 2 } else { 1 })
+        .sum::<usize>()
+        + 1;
+    if encoded_len > dst.capacity() - dst.len() {
+        return Err(SlipError::Full);
+    }
+
+    // There is enough space for all of the pushes, checked above.
+    for byte in data {
+        let _ = match byte {
+            END => dst.push_back(ESC).and_then(|_| dst.push_back(ESC_END)),
+            ESC => dst.push_back(ESC).and_then(|_| dst.push_back(ESC_ESC)),
+            _ => dst.push_back(byte),
+        };
+    }
+    let _ = dst.push_back(END);
+    Ok(())
+}
+
+/// Decodes the next complete frame at the front of `deque`.
+///
+/// Returns `None` if the deque does not contain a complete frame yet. Empty
+/// frames (consecutive `END` bytes) are skipped.
+///
+/// The frame is decoded in place and borrowed from the deque; it is removed
+/// from the deque when the returned [`Frame`] is dropped. If the encoded
+/// frame wraps around the end of the deque's storage, the contents are moved
+/// to make it contiguous first.
+///
+/// If the deque is full and contains no `END` byte, its contents are
+/// discarded and [`SlipError::TooLong`] is returned, so that reception can
+/// resynchronize at the next `END`.
+pub fn next_frame<S: Storage<u8>>(
+    deque: &mut DequeBase<u8, S>,
+) -> Option<Result<Frame<'_, S>, SlipError>> {
+    next_decoded_frame(
+        deque,
+        END,
+        decode_in_place,
+        SlipError::TooLong,
+        SlipError::Malformed,
+    )
+}
+
+/// Unescapes a SLIP frame without its `END` byte, writing the result to the
+/// start of the same buffer.
+///
+/// Returns the length of the decoded data, or `None` if the frame contains an
+/// invalid escape sequence.
+fn decode_in_place(buf: &mut [u8]) -> Option<usize> {
+    let mut read = 0;
+    let mut write = 0;
+    while read < buf.len() {
+        let byte = match buf[read] {
+            ESC => {
+                read += 1;
+                match buf.get(read) {
+                    Some(&ESC_END) => END,
+                    Some(&ESC_ESC) => ESC,
+                    _ => return None,
+                }
+            }
+            byte => byte,
+        };
+        // `write <= read`, because escape sequences only ever shrink.
+        buf[write] = byte;
+        read += 1;
+        write += 1;
+    }
+    Some(write)
+}
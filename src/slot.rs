@@ -0,0 +1,172 @@
+//! A lock-free allocator of slot indices.
+
+use crate::sync::{const_fn, AtomicUsize, Ordering};
+
+/// The number of slots tracked by each bitmap word.
+const BITS: usize = usize::BITS as usize;
+
+/// A lock-free allocator handing out indices into a fixed set of slots.
+///
+/// Slot ownership is tracked in a bitmap of `WORDS` atomic words, so the
+/// allocator manages up to `WORDS * usize::BITS` slots (fewer if created with
+/// [`with_slots`](Self::with_slots)). Claiming and releasing are lock-free and
+/// may be done concurrently from any context, including interrupt handlers,
+/// which makes this a building block for sharing a static array of buffers:
+/// whoever claims index `i` has exclusive use of buffer `i` until it is
+/// released.
+///
+/// The bitmap size is given in words rather than slots because stable Rust
+/// cannot yet compute an array length from a const generic parameter.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::SlotAllocator;
+///
+/// static SLOTS: SlotAllocator<1> = SlotAllocator::with_slots(3);
+///
+/// let a = SLOTS.claim().unwrap();
+/// let b = SLOTS.claim().unwrap();
+/// let c = SLOTS.claim().unwrap();
+/// assert_ne!(a, b);
+/// assert_eq!(SLOTS.claim(), None);
+///
+/// SLOTS.release(b);
+/// assert_eq!(SLOTS.claim(), Some(b));
+/// # let _ = (a, c);
+/// ```
+pub struct SlotAllocator<const WORDS: usize> {
+    /// One bit per slot, set if the slot is claimed.
+    ///
+    /// Bits past `slots` are permanently set so they are never handed out.
+    words: [AtomicUsize; WORDS],
+
+    /// The number of slots managed by this allocator.
+    slots: usize,
+}
+
+impl<const WORDS: usize> SlotAllocator<WORDS> {
+    const_fn! {
+        /// Creates an allocator managing `WORDS * usize::BITS` slots, all free.
+        pub fn new() -> Self {
+            Self::with_slots(WORDS * BITS)
+        }
+    }
+
+    const_fn! {
+        /// Creates an allocator managing the first `slots` slots, all free.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `slots` is larger than `WORDS * usize::BITS`.
+        pub fn with_slots(slots: usize) -> Self {
+            assert!(slots <= WORDS * BITS, "Too many slots for the bitmap size");
+
+            let mut words = Self::free_words();
+
+            // Mark all of the bits past the end as claimed:
+            let mut word = slots / BITS;
+            while word < WORDS {
+                let valid_bits = if word == slots / BITS {
+                    slots % BITS
+                } else {
+                    0
+                };
+                words[word] = AtomicUsize::new(!0 << valid_bits);
+                word += 1;
+            }
+
+            Self { words, slots }
+        }
+    }
+
+    #[cfg(not(all(loom, feature = "loom")))]
+    const fn free_words() -> [AtomicUsize; WORDS] {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const FREE: AtomicUsize = AtomicUsize::new(0);
+        [FREE; WORDS]
+    }
+
+    /// Loom's atomics can't be created in constants, so the words are
+    /// created one by one. Loom itself needs a newer compiler than the
+    /// crate, so `array::from_fn` is fine here.
+    #[cfg(all(loom, feature = "loom"))]
+    #[clippy::msrv = "1.63"]
+    fn free_words() -> [AtomicUsize; WORDS] {
+        core::array::from_fn(|_| AtomicUsize::new(0))
+    }
+
+    /// The number of slots managed by this allocator.
+    pub const fn capacity(&self) -> usize {
+        self.slots
+    }
+
+    /// Claims a free slot, returning its index, or `None` if all slots are
+    /// claimed.
+    ///
+    /// The lowest free index is preferred, but this is not guaranteed when
+    /// other contexts are claiming concurrently.
+    pub fn claim(&self) -> Option<usize> {
+        for (word_idx, word) in self.words.iter().enumerate() {
+            let mut current = word.load(Ordering::Relaxed);
+            while current != !0 {
+                let bit = (!current).trailing_zeros() as usize;
+                match word.compare_exchange_weak(
+                    current,
+                    current | (1 << bit),
+                    Ordering::Acquire,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => return Some(word_idx * BITS + bit),
+                    Err(actual) => current = actual,
+                }
+            }
+        }
+        None
+    }
+
+    /// Releases a slot previously returned by [`claim`](Self::claim), making
+    /// it available to be claimed again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slot` is out of range. In debug builds, also panics if the
+    /// slot was not claimed.
+    pub fn release(&self, slot: usize) {
+        assert!(slot < self.slots, "Slot index out of range");
+        let mask = 1 << (slot % BITS);
+        let previous = self.words[slot / BITS].fetch_and(!mask, Ordering::Release);
+        debug_assert!(previous & mask != 0, "Released a slot that was not claimed");
+    }
+
+    /// Returns `true` if the given slot is currently claimed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slot` is out of range.
+    pub fn is_claimed(&self, slot: usize) -> bool {
+        assert!(slot < self.slots, "Slot index out of range");
+        let mask = 1 << (slot % BITS);
+        self.words[slot / BITS].load(Ordering::Acquire) & mask != 0
+    }
+
+    /// The number of slots currently claimed.
+    ///
+    /// This is only a snapshot if other contexts are claiming or releasing
+    /// concurrently.
+    pub fn claimed(&self) -> usize {
+        let reserved = WORDS * BITS - self.slots;
+        let set: usize = self
+            .words
+            .iter()
+            .map(|word| word.load(Ordering::Relaxed).count_ones() as usize)
+            .sum();
+        set - reserved
+    }
+}
+
+impl<const WORDS: usize> Default for SlotAllocator<WORDS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,206 @@
+//! A deque that stores a few elements inline and spills to the heap.
+
+use alloc::collections::VecDeque;
+use core::ops::{Index, IndexMut};
+
+use crate::Deque;
+
+/// A growable deque that stores up to `N` elements inline, and moves them to
+/// a heap allocation when more are pushed.
+///
+/// Unlike [`Deque`], pushing never fails. As long as the deque has never held
+/// more than `N` elements, it does not allocate; once it spills, it behaves
+/// like a `VecDeque` and stays on the heap (even if elements are later
+/// removed).
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::SmallDeque;
+///
+/// let mut deque: SmallDeque<u32, 2> = SmallDeque::new();
+/// deque.push_back(1);
+/// deque.push_back(2);
+/// assert!(!deque.spilled());
+///
+/// deque.push_front(0);
+/// assert!(deque.spilled());
+/// assert_eq!(deque.pop_front(), Some(0));
+/// assert_eq!(deque.pop_front(), Some(1));
+/// assert_eq!(deque.pop_front(), Some(2));
+/// ```
+pub struct SmallDeque<T, const N: usize> {
+    inner: Inner<T, N>,
+}
+
+enum Inner<T, const N: usize> {
+    Inline(Deque<T, N>),
+    Heap(VecDeque<T>),
+}
+
+impl<T, const N: usize> SmallDeque<T, N> {
+    /// Creates an empty deque.
+    pub const fn new() -> Self {
+        Self {
+            inner: Inner::Inline(Deque::new()),
+        }
+    }
+
+    /// Returns `true` if the elements have been moved to the heap.
+    pub fn spilled(&self) -> bool {
+        matches!(self.inner, Inner::Heap(_))
+    }
+
+    /// The number of elements the deque can hold without (re)allocating.
+    pub fn capacity(&self) -> usize {
+        match &self.inner {
+            Inner::Inline(deque) => deque.capacity(),
+            Inner::Heap(deque) => deque.capacity(),
+        }
+    }
+
+    /// The number of elements currently in the deque.
+    pub fn len(&self) -> usize {
+        match &self.inner {
+            Inner::Inline(deque) => deque.len(),
+            Inner::Heap(deque) => deque.len(),
+        }
+    }
+
+    /// Returns `true` if the deque is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Clears the deque, removing all values.
+    ///
+    /// A spilled deque keeps its heap allocation.
+    pub fn clear(&mut self) {
+        match &mut self.inner {
+            Inner::Inline(deque) => deque.clear(),
+            Inner::Heap(deque) => deque.clear(),
+        }
+    }
+
+    /// Prepends an element to the deque, spilling to the heap if needed.
+    pub fn push_front(&mut self, value: T) {
+        match &mut self.inner {
+            Inner::Inline(deque) => {
+                if let Err(value) = deque.push_front(value) {
+                    self.spill().push_front(value);
+                }
+            }
+            Inner::Heap(deque) => deque.push_front(value),
+        }
+    }
+
+    /// Appends an element to the back of the deque, spilling to the heap if
+    /// needed.
+    pub fn push_back(&mut self, value: T) {
+        match &mut self.inner {
+            Inner::Inline(deque) => {
+                if let Err(value) = deque.push_back(value) {
+                    self.spill().push_back(value);
+                }
+            }
+            Inner::Heap(deque) => deque.push_back(value),
+        }
+    }
+
+    /// Removes the first element and returns it, or `None` if the deque is
+    /// empty.
+    pub fn pop_front(&mut self) -> Option<T> {
+        match &mut self.inner {
+            Inner::Inline(deque) => deque.pop_front(),
+            Inner::Heap(deque) => deque.pop_front(),
+        }
+    }
+
+    /// Removes the last element from the deque and returns it, or `None` if
+    /// it is empty.
+    pub fn pop_back(&mut self) -> Option<T> {
+        match &mut self.inner {
+            Inner::Inline(deque) => deque.pop_back(),
+            Inner::Heap(deque) => deque.pop_back(),
+        }
+    }
+
+    /// Provides a reference to the element at the given index.
+    ///
+    /// Element at index 0 is at the front of the queue.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        match &self.inner {
+            Inner::Inline(deque) => deque.get(index),
+            Inner::Heap(deque) => deque.get(index),
+        }
+    }
+
+    /// Provides a mutable reference to the element at the given index.
+    ///
+    /// Element at index 0 is at the front of the queue.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        match &mut self.inner {
+            Inner::Inline(deque) => deque.get_mut(index),
+            Inner::Heap(deque) => deque.get_mut(index),
+        }
+    }
+
+    /// Provides a reference to the front element, or `None` if the deque is
+    /// empty.
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// Provides a mutable reference to the front element, or `None` if the
+    /// deque is empty.
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(0)
+    }
+
+    /// Provides a reference to the back element, or `None` if the deque is
+    /// empty.
+    pub fn back(&self) -> Option<&T> {
+        self.get(self.len().wrapping_sub(1))
+    }
+
+    /// Provides a mutable reference to the back element, or `None` if the
+    /// deque is empty.
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(self.len().wrapping_sub(1))
+    }
+
+    /// Moves the inline elements to the heap, returning the heap deque.
+    fn spill(&mut self) -> &mut VecDeque<T> {
+        if let Inner::Inline(inline) = &mut self.inner {
+            let mut heap = VecDeque::with_capacity(2 * N.max(1));
+            while let Some(value) = inline.pop_front() {
+                heap.push_back(value);
+            }
+            self.inner = Inner::Heap(heap);
+        }
+        match &mut self.inner {
+            Inner::Heap(heap) => heap,
+            Inner::Inline(_) => unreachable!(),
+        }
+    }
+}
+
+impl<T, const N: usize> Default for SmallDeque<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Index<usize> for SmallDeque<T, N> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("Out of bounds access")
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for SmallDeque<T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).expect("Out of bounds access")
+    }
+}
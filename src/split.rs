@@ -0,0 +1,157 @@
+//! Splitting a deque into parts that can be used independently.
+
+use core::ops::{Index, IndexMut};
+
+use crate::{
+    iter::{Iter, IterMut},
+    DequeBase, Storage,
+};
+
+/// A mutable view of a run of consecutive elements of a deque.
+///
+/// Like the deque itself, the run may wrap around the end of the storage, so
+/// it is made of up to two slices. Views returned by
+/// [`DequeBase::split_at_mut`] don't overlap, so each can be handed to a
+/// different part of the program (e.g. one to a DMA completion handler and
+/// the other to the main loop).
+#[derive(Debug)]
+pub struct ViewMut<'a, T> {
+    front: &'a mut [T],
+    back: &'a mut [T],
+}
+
+impl<'a, T> ViewMut<'a, T> {
+    /// The number of elements in the view.
+    pub fn len(&self) -> usize {
+        self.front.len() + self.back.len()
+    }
+
+    /// Returns `true` if the view has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Provides a reference to the element at the given index, counting from
+    /// the start of the view.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        match index.checked_sub(self.front.len()) {
+            None => self.front.get(index),
+            Some(index) => self.back.get(index),
+        }
+    }
+
+    /// Provides a mutable reference to the element at the given index,
+    /// counting from the start of the view.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        match index.checked_sub(self.front.len()) {
+            None => self.front.get_mut(index),
+            Some(index) => self.back.get_mut(index),
+        }
+    }
+
+    /// Returns the elements of the view, in order, as a pair of slices.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        (self.front, self.back)
+    }
+
+    /// Returns the elements of the view, in order, as a pair of mutable
+    /// slices.
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        (self.front, self.back)
+    }
+
+    /// Consumes the view, returning its slices with the full lifetime of the
+    /// borrow of the deque.
+    pub fn into_slices(self) -> (&'a mut [T], &'a mut [T]) {
+        (self.front, self.back)
+    }
+
+    /// Returns an iterator over the elements of the view.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::from_slices(self.front, self.back)
+    }
+
+    /// Returns an iterator over mutable references to the elements of the
+    /// view.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut::from_slices(self.front, self.back)
+    }
+}
+
+impl<T> Index<usize> for ViewMut<'_, T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("Out of bounds access")
+    }
+}
+
+impl<T> IndexMut<usize> for ViewMut<'_, T> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).expect("Out of bounds access")
+    }
+}
+
+impl<'a, T> IntoIterator for ViewMut<'a, T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IterMut::from_slices(self.front, self.back)
+    }
+}
+
+impl<T, S: Storage<T>> DequeBase<T, S> {
+    /// Divides the elements into two views at `index`.
+    ///
+    /// The first view holds the elements before `index`, and the second
+    /// holds the rest. Either may wrap around the end of the storage.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<u32, 4> = Deque::new();
+    /// d.push_back(2).unwrap();
+    /// d.push_back(3).unwrap();
+    /// d.push_front(1).unwrap();
+    ///
+    /// let (mut done, mut pending) = d.split_at_mut(1);
+    /// done[0] = 10;
+    /// for x in pending.iter_mut() {
+    ///     *x += 1;
+    /// }
+    /// assert!(d.iter().eq(&[10, 3, 4]));
+    /// ```
+    pub fn split_at_mut(&mut self, index: usize) -> (ViewMut<'_, T>, ViewMut<'_, T>) {
+        assert!(index <= self.len(), "split index out of bounds");
+        let (front, back) = self.as_mut_slices();
+        if index <= front.len() {
+            let (before, after) = front.split_at_mut(index);
+            (
+                ViewMut {
+                    front: before,
+                    back: &mut [],
+                },
+                ViewMut { front: after, back },
+            )
+        } else {
+            let (before, after) = back.split_at_mut(index - front.len());
+            (
+                ViewMut {
+                    front,
+                    back: before,
+                },
+                ViewMut {
+                    front: after,
+                    back: &mut [],
+                },
+            )
+        }
+    }
+}
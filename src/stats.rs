@@ -0,0 +1,160 @@
+//! Statistics over a sliding window of samples.
+
+use crate::Deque;
+
+/// The mean and variance of the last `N` samples pushed, updated in
+/// constant time per sample.
+///
+/// The running sums use Welford's method, adjusted for samples leaving the
+/// window, so they stay accurate when the samples are large compared to how
+/// much they vary (e.g. a pressure in pascals). Samples are stored as `f32`,
+/// and the sums are kept as `f64`.
+///
+/// # Examples
+///
+/// Flagging readings more than three standard deviations from the recent
+/// mean:
+///
+/// ```
+/// use fullhouse::stats::RollingStats;
+///
+/// let mut window: RollingStats<16> = RollingStats::new();
+/// for i in 0..16 {
+///     window.push(101_325.0 + (i % 4) as f32);
+/// }
+/// assert_eq!(window.mean(), Some(101_326.5));
+/// assert_eq!(window.variance(), Some(1.25));
+///
+/// let reading = 101_340.0;
+/// let (mean, stddev) = (window.mean().unwrap(), window.stddev().unwrap());
+/// assert!((reading - mean).abs() > 3.0 * stddev);
+/// ```
+pub struct RollingStats<const N: usize> {
+    samples: Deque<f32, N>,
+    mean: f64,
+
+    /// The sum of squared differences from the mean.
+    m2: f64,
+}
+
+impl<const N: usize> RollingStats<N> {
+    /// Creates an empty window.
+    pub const fn new() -> Self {
+        Self {
+            samples: Deque::new(),
+            mean: 0.0,
+            m2: 0.0,
+        }
+    }
+
+    /// The number of samples in the window, at most `N`.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Returns `true` if no samples have been pushed.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Returns `true` if the window holds `N` samples.
+    pub fn is_full(&self) -> bool {
+        self.samples.is_full()
+    }
+
+    /// Removes all samples.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.mean = 0.0;
+        self.m2 = 0.0;
+    }
+
+    /// Adds a sample, pushing the oldest one out of the window if it is
+    /// full. Returns the sample that was pushed out.
+    pub fn push(&mut self, sample: f32) -> Option<f32> {
+        if N == 0 {
+            return Some(sample);
+        }
+        let evicted = if self.samples.is_full() {
+            self.samples.pop_front()
+        } else {
+            None
+        };
+        // Can't fail, since there is room now.
+        let _ = self.samples.push_back(sample);
+
+        let new = f64::from(sample);
+        match evicted {
+            Some(old) => {
+                // Replace the oldest sample's contribution with the new one's.
+                let old = f64::from(old);
+                let mean = self.mean + (new - old) / N as f64;
+                self.m2 += (new - old) * (new - mean + old - self.mean);
+                self.mean = mean;
+            }
+            None => {
+                let delta = new - self.mean;
+                self.mean += delta / self.samples.len() as f64;
+                self.m2 += delta * (new - self.mean);
+            }
+        }
+        evicted
+    }
+
+    /// The mean of the samples, or `None` if there are none.
+    pub fn mean(&self) -> Option<f32> {
+        (!self.is_empty()).then(|| self.mean as f32)
+    }
+
+    /// The variance of the samples (dividing by their count), or `None` if
+    /// there are none.
+    pub fn variance(&self) -> Option<f32> {
+        let n = self.samples.len();
+        (n > 0).then(|| (self.m2.max(0.0) / n as f64) as f32)
+    }
+
+    /// The variance of the samples as an estimate of the variance of what
+    /// they were drawn from (dividing by one less than their count), or
+    /// `None` if there are fewer than two.
+    pub fn sample_variance(&self) -> Option<f32> {
+        let n = self.samples.len();
+        (n > 1).then(|| (self.m2.max(0.0) / (n - 1) as f64) as f32)
+    }
+
+    /// The standard deviation of the samples, the square root of
+    /// [`variance`](Self::variance), or `None` if there are none.
+    pub fn stddev(&self) -> Option<f32> {
+        let n = self.samples.len();
+        (n > 0).then(|| sqrt(self.m2.max(0.0) / n as f64) as f32)
+    }
+}
+
+impl<const N: usize> Default for RollingStats<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Clone for RollingStats<N> {
+    fn clone(&self) -> Self {
+        Self {
+            samples: self.samples.clone(),
+            mean: self.mean,
+            m2: self.m2,
+        }
+    }
+}
+
+/// The square root of a non-negative `x`, since `core` has no `f64::sqrt`.
+fn sqrt(x: f64) -> f64 {
+    if x == 0.0 || !x.is_finite() {
+        return x;
+    }
+    // Halving the exponent gets within a few percent, and each Newton step
+    // doubles the number of correct bits.
+    let mut y = f64::from_bits((x.to_bits() >> 1) + (1023 << 51));
+    for _ in 0..5 {
+        y = 0.5 * (y + x / y);
+    }
+    y
+}
@@ -0,0 +1,55 @@
+//! Implementations of the standard library's I/O traits for byte deques.
+
+use std::io::{self, BufRead, Read, Write};
+
+use crate::{DequeBase, Storage};
+
+/// Reading removes bytes from the front of the deque.
+///
+/// Reading from an empty deque returns `Ok(0)`.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{Read, Write};
+/// use fullhouse::Deque;
+///
+/// let mut deque: Deque<u8, 8> = Deque::new();
+/// deque.write_all(b"hello").unwrap();
+///
+/// let mut text = String::new();
+/// deque.read_to_string(&mut text).unwrap();
+/// assert_eq!(text, "hello");
+/// ```
+impl<S: Storage<u8>> Read for DequeBase<u8, S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Ok(self.pop_front_slice(buf))
+    }
+}
+
+/// The buffer returned by `fill_buf` is the first contiguous part of the
+/// deque, so it may be shorter than [`len`](DequeBase::len) when the contents
+/// wrap around.
+impl<S: Storage<u8>> BufRead for DequeBase<u8, S> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(self.as_slices().0)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.advance_front(amt)
+    }
+}
+
+/// Writing appends bytes to the back of the deque.
+///
+/// Like writing to a `&mut [u8]`, writes are short if the deque does not have
+/// room for all of `buf`, and writing to a full deque returns `Ok(0)`.
+impl<S: Storage<u8>> Write for DequeBase<u8, S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Ok(self.push_back_slice(buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
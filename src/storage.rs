@@ -0,0 +1,71 @@
+//! Backing memory for deques.
+
+use core::mem::MaybeUninit;
+
+/// A region of memory that can hold the elements of a
+/// [`DequeBase`](crate::DequeBase).
+///
+/// This lets the same deque implementation work with different kinds of
+/// buffers:
+///
+/// - `[MaybeUninit<T>; N]` stores the elements inline, inside the deque
+///   itself. This is what [`Deque`](crate::Deque) uses.
+///
+/// - `&mut [MaybeUninit<T>]` borrows a buffer that lives elsewhere, such as a
+///   `&'static mut` region placed in a dedicated memory section, while the
+///   deque's indexes stay wherever the deque value itself is (e.g. on the
+///   stack). This is what [`DequeView`](crate::DequeView) uses.
+///
+/// The storage never holds initialized elements on its own; the deque keeps
+/// track of which slots are in use, and drops them when it is dropped. Any
+/// contents of the buffer when it is handed to a deque are ignored.
+///
+/// # Safety
+///
+/// Implementations must always return slices of the same length from
+/// [`as_slice`](Self::as_slice) and [`as_mut_slice`](Self::as_mut_slice), and
+/// values written through `as_mut_slice` must be visible through later calls
+/// to either method for as long as the storage value exists (even if it is
+/// moved). If [`capacity`](Self::capacity) is overridden, it must return
+/// that same length, because the deque relies on it to index the slices
+/// without bounds checks.
+pub unsafe trait Storage<T> {
+    /// Provides a view of the entire buffer.
+    fn as_slice(&self) -> &[MaybeUninit<T>];
+
+    /// Provides a mutable view of the entire buffer.
+    fn as_mut_slice(&mut self) -> &mut [MaybeUninit<T>];
+
+    /// The number of elements the buffer can hold.
+    fn capacity(&self) -> usize {
+        self.as_slice().len()
+    }
+}
+
+// Safety: The array is always the same length, and moves its contents along
+// with it.
+unsafe impl<T, const N: usize> Storage<T> for [MaybeUninit<T>; N] {
+    fn as_slice(&self) -> &[MaybeUninit<T>] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [MaybeUninit<T>] {
+        self
+    }
+
+    fn capacity(&self) -> usize {
+        N
+    }
+}
+
+// Safety: The slice reference always points to the same memory, which is
+// exclusively borrowed for as long as the reference exists.
+unsafe impl<T> Storage<T> for &mut [MaybeUninit<T>] {
+    fn as_slice(&self) -> &[MaybeUninit<T>] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [MaybeUninit<T>] {
+        self
+    }
+}
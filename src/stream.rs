@@ -0,0 +1,97 @@
+//! Implementations of `futures_core::Stream` for the receiving sides of the
+//! async queues.
+
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_core::Stream;
+
+use crate::{
+    async_deque::PopSlot,
+    channel::{Receiver, TryRecvError},
+    priority_channel::PriorityChannel,
+};
+
+/// Yields the elements popped from the front of the deque, waiting for each
+/// in turn with the other tasks popping from it. The stream never ends.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() {
+/// use fullhouse::AsyncDeque;
+/// use futures_util::StreamExt;
+///
+/// static SAMPLES: AsyncDeque<u16, 32> = AsyncDeque::new();
+///
+/// let mut samples = SAMPLES.pop_slot();
+/// while let Some(sample) = samples.next().await {
+///     // Process the sample.
+/// }
+/// # }
+/// ```
+impl<T, const N: usize, const W: usize> Stream for PopSlot<'_, T, N, W> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.get_mut().poll_pop(cx).map(Some)
+    }
+}
+
+/// Yields the messages in the order they were sent, and ends once all
+/// senders were dropped and the queued messages are used up.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::channel::Channel;
+/// use futures_util::{FutureExt, StreamExt};
+///
+/// static CHANNEL: Channel<u8, 4> = Channel::new();
+///
+/// let (tx, mut rx) = CHANNEL.split().unwrap();
+/// tx.try_send(1).unwrap();
+/// assert_eq!(rx.next().now_or_never(), Some(Some(1)));
+/// assert_eq!(rx.next().now_or_never(), None);
+/// drop(tx);
+/// assert_eq!(rx.next().now_or_never(), Some(None));
+/// ```
+impl<T, const N: usize> Stream for Receiver<'_, T, N> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        match self.get_mut().poll_recv(cx) {
+            Ok(message) => Poll::Ready(Some(message)),
+            Err(TryRecvError::Disconnected) => Poll::Ready(None),
+            Err(TryRecvError::Empty) => Poll::Pending,
+        }
+    }
+}
+
+/// Yields the message with the highest priority each time one is
+/// available. The stream never ends.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() {
+/// use fullhouse::priority_channel::PriorityChannel;
+/// use futures_util::StreamExt;
+///
+/// static COMMANDS: PriorityChannel<u32, u8, 16> = PriorityChannel::new();
+///
+/// let mut commands = &COMMANDS;
+/// while let Some(command) = commands.next().await {
+///     // Handle the command.
+/// }
+/// # }
+/// ```
+impl<T, P: Ord, const N: usize, const W: usize> Stream for &PriorityChannel<T, P, N, W> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.poll_recv(cx).map(|(_, value)| Some(value))
+    }
+}
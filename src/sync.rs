@@ -0,0 +1,64 @@
+//! The atomics and cells behind the lock-free types.
+//!
+//! These are the ones from `core`, except when building with
+//! `RUSTFLAGS="--cfg loom"` and the `loom` feature, which swaps in loom's
+//! model-checked versions. The models in `tests/loom.rs` then run the
+//! interleavings of the threads (and the reorderings that the memory
+//! orderings allow) to check that no wake-up or permit is lost, that slots
+//! are claimed exclusively, and that trace events are never torn:
+//!
+//! ```text
+//! RUSTFLAGS="--cfg loom" cargo test --release --features loom --test loom
+//! ```
+
+#[cfg(not(all(loom, feature = "loom")))]
+pub(crate) use core::sync::atomic::{
+    fence, AtomicBool, AtomicU32, AtomicU8, AtomicUsize, Ordering,
+};
+#[cfg(all(loom, feature = "loom"))]
+pub(crate) use loom::sync::atomic::{
+    fence, AtomicBool, AtomicU32, AtomicU8, AtomicUsize, Ordering,
+};
+
+#[cfg(all(loom, feature = "loom"))]
+pub(crate) use loom::cell::UnsafeCell;
+
+/// An `UnsafeCell` with the same API as loom's, which tracks accesses
+/// through closures.
+#[cfg(not(all(loom, feature = "loom")))]
+pub(crate) struct UnsafeCell<T>(core::cell::UnsafeCell<T>);
+
+#[cfg(not(all(loom, feature = "loom")))]
+impl<T> UnsafeCell<T> {
+    pub(crate) const fn new(value: T) -> Self {
+        Self(core::cell::UnsafeCell::new(value))
+    }
+
+    /// Calls `f` with a pointer to the value, which may only be written
+    /// while nothing else accesses it.
+    #[inline(always)]
+    pub(crate) fn with_mut<R>(&self, f: impl FnOnce(*mut T) -> R) -> R {
+        f(self.0.get())
+    }
+}
+
+/// Declares a function that is `const`, except under loom, whose atomics
+/// can't be created in constants.
+macro_rules! const_fn {
+    ($(#[$attr:meta])* $vis:vis unsafe fn $($rest:tt)*) => {
+        #[cfg(not(all(loom, feature = "loom")))]
+        $(#[$attr])* $vis const unsafe fn $($rest)*
+
+        #[cfg(all(loom, feature = "loom"))]
+        $(#[$attr])* $vis unsafe fn $($rest)*
+    };
+    ($(#[$attr:meta])* $vis:vis fn $($rest:tt)*) => {
+        #[cfg(not(all(loom, feature = "loom")))]
+        $(#[$attr])* $vis const fn $($rest)*
+
+        #[cfg(all(loom, feature = "loom"))]
+        $(#[$attr])* $vis fn $($rest)*
+    };
+}
+
+pub(crate) use const_fn;
@@ -0,0 +1,252 @@
+//! A log ring shared by several sources, read back one source at a time.
+
+use core::{
+    cell::RefCell,
+    fmt::{self, Write},
+};
+
+use critical_section::Mutex;
+
+use crate::Deque;
+
+/// The size of a record's header: the source ID and a 16-bit length.
+const HEADER_LEN: usize = 3;
+
+/// A ring of `N` bytes holding log records, each tagged with the ID of the
+/// source that wrote it.
+///
+/// Each task or driver gets a [`Source`] handle with its own ID, and writes
+/// whole records through it, so records from different sources never
+/// interleave. When the ring is full, the oldest records are discarded to
+/// make room, like a flight recorder. The reader can take the records in
+/// order with [`pop`](Self::pop), or only those of one source with
+/// [`pop_source`](Self::pop_source), leaving the rest in place.
+///
+/// Each record takes 3 bytes of overhead, and its message is truncated to
+/// fit in the ring (or in 65535 bytes). Access to the ring is guarded by a
+/// [`critical_section`], so sources can be used from interrupt handlers.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::tagged::TaggedLog;
+///
+/// static LOG: TaggedLog<128> = TaggedLog::new();
+///
+/// let motor = LOG.source(1);
+/// let radio = LOG.source(2);
+/// motor.write(b"stall");
+/// write!(radio, "rssi={}", -70).unwrap();
+/// motor.write(b"restart");
+///
+/// let mut buf = [0; 32];
+/// assert_eq!(LOG.pop_source(2, &mut buf), Some(8));
+/// assert_eq!(&buf[..8], b"rssi=-70");
+///
+/// assert_eq!(LOG.pop(&mut buf), Some((1, 5)));
+/// assert_eq!(LOG.pop(&mut buf), Some((1, 7)));
+/// assert_eq!(LOG.pop(&mut buf), None);
+/// ```
+pub struct TaggedLog<const N: usize> {
+    ring: Mutex<RefCell<Deque<u8, N>>>,
+}
+
+impl<const N: usize> TaggedLog<N> {
+    /// The longest message a record can hold.
+    const MAX_MESSAGE: usize = {
+        let max = N.saturating_sub(HEADER_LEN);
+        if max < u16::MAX as usize {
+            max
+        } else {
+            u16::MAX as usize
+        }
+    };
+
+    /// Creates an empty log.
+    pub const fn new() -> Self {
+        Self {
+            ring: Mutex::new(RefCell::new(Deque::new())),
+        }
+    }
+
+    /// Returns a handle for writing records tagged with `id`.
+    pub const fn source(&self, id: u8) -> Source<'_, N> {
+        Source { log: self, id }
+    }
+
+    /// Returns `true` if there are no records.
+    pub fn is_empty(&self) -> bool {
+        critical_section::with(|cs| self.ring.borrow_ref(cs).is_empty())
+    }
+
+    /// Appends a record, with its message written by `f`.
+    fn append(
+        &self,
+        id: u8,
+        f: impl FnOnce(&mut RecordWriter<'_, N>) -> fmt::Result,
+    ) -> fmt::Result {
+        if Self::MAX_MESSAGE == 0 {
+            return Ok(());
+        }
+        critical_section::with(|cs| {
+            let mut ring = self.ring.borrow_ref_mut(cs);
+            while ring.capacity() - ring.len() < HEADER_LEN {
+                evict(&mut ring);
+            }
+            // There is room, made above. The length is filled in below.
+            ring.push_back_slice(&[id, 0, 0]);
+
+            let mut writer = RecordWriter {
+                ring: &mut ring,
+                written: 0,
+            };
+            let result = f(&mut writer);
+            let written = writer.written;
+
+            let header = ring.len() - written - HEADER_LEN;
+            let [lo, hi] = (written as u16).to_le_bytes();
+            ring[header + 1] = lo;
+            ring[header + 2] = hi;
+            result
+        })
+    }
+
+    /// Removes the oldest record whose source matches `filter`, copying as
+    /// much of its message as fits into `buf`.
+    ///
+    /// Returns the source ID and the number of bytes copied, or `None` if
+    /// there is no such record. Records that don't match are kept in order.
+    ///
+    /// The critical section is held for the whole search, so skipping over
+    /// many records takes correspondingly long.
+    pub fn pop_matching(
+        &self,
+        mut filter: impl FnMut(u8) -> bool,
+        buf: &mut [u8],
+    ) -> Option<(u8, usize)> {
+        critical_section::with(|cs| {
+            let mut ring = self.ring.borrow_ref_mut(cs);
+
+            let mut offset = 0;
+            while offset < ring.len() {
+                let (id, len) = header_at(&ring, offset);
+                let record_len = HEADER_LEN + len;
+                if !filter(id) {
+                    offset += record_len;
+                    continue;
+                }
+
+                let copy_len = len.min(buf.len());
+                let copied = ring.read_at(offset + HEADER_LEN, &mut buf[..copy_len]);
+
+                // Close the hole by moving the earlier records up to it.
+                for i in (0..offset).rev() {
+                    ring[i + record_len] = ring[i];
+                }
+                ring.advance_front(record_len);
+                return Some((id, copied));
+            }
+            None
+        })
+    }
+
+    /// Removes the oldest record, copying as much of its message as fits into
+    /// `buf`.
+    ///
+    /// Returns the source ID and the number of bytes copied, or `None` if
+    /// there are no records.
+    pub fn pop(&self, buf: &mut [u8]) -> Option<(u8, usize)> {
+        self.pop_matching(|_| true, buf)
+    }
+
+    /// Removes the oldest record from source `id`, copying as much of its
+    /// message as fits into `buf`.
+    ///
+    /// Returns the number of bytes copied, or `None` if there are no records
+    /// from that source.
+    pub fn pop_source(&self, id: u8, buf: &mut [u8]) -> Option<usize> {
+        self.pop_matching(|source| source == id, buf)
+            .map(|(_, len)| len)
+    }
+
+    /// Removes all records from source `id`.
+    pub fn clear_source(&self, id: u8) {
+        while self.pop_source(id, &mut []).is_some() {}
+    }
+}
+
+impl<const N: usize> Default for TaggedLog<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the source ID and message length of the record at `offset`.
+fn header_at<const N: usize>(ring: &Deque<u8, N>, offset: usize) -> (u8, usize) {
+    let len = u16::from_le_bytes([ring[offset + 1], ring[offset + 2]]);
+    (ring[offset], len as usize)
+}
+
+/// Removes the oldest record.
+fn evict<const N: usize>(ring: &mut Deque<u8, N>) {
+    let (_, len) = header_at(ring, 0);
+    ring.advance_front(HEADER_LEN + len);
+}
+
+/// Appends the message of the record at the back of the ring, evicting older
+/// records to make room and truncating it to the maximum length.
+struct RecordWriter<'a, const N: usize> {
+    ring: &'a mut Deque<u8, N>,
+    written: usize,
+}
+
+impl<const N: usize> RecordWriter<'_, N> {
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        let room = TaggedLog::<N>::MAX_MESSAGE - self.written;
+        let bytes = &bytes[..bytes.len().min(room)];
+        // Only other records can be evicted; truncating to the maximum
+        // length guarantees that this makes enough room.
+        while self.ring.capacity() - self.ring.len() < bytes.len() {
+            evict(self.ring);
+        }
+        self.written += self.ring.push_back_slice(bytes);
+    }
+}
+
+impl<const N: usize> Write for RecordWriter<'_, N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_bytes(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// A handle for writing records from one source to a [`TaggedLog`].
+///
+/// Returned by [`TaggedLog::source`].
+#[derive(Clone, Copy)]
+pub struct Source<'a, const N: usize> {
+    log: &'a TaggedLog<N>,
+    id: u8,
+}
+
+impl<const N: usize> Source<'_, N> {
+    /// The ID that records from this source are tagged with.
+    pub const fn id(&self) -> u8 {
+        self.id
+    }
+
+    /// Appends a record with the given message.
+    pub fn write(&self, message: &[u8]) {
+        let _ = self.log.append(self.id, |writer| {
+            writer.write_bytes(message);
+            Ok(())
+        });
+    }
+
+    /// Appends a record with a formatted message, e.g. with `write!`.
+    ///
+    /// The whole message goes into one record.
+    pub fn write_fmt(&self, args: fmt::Arguments<'_>) -> fmt::Result {
+        self.log.append(self.id, |writer| writer.write_fmt(args))
+    }
+}
@@ -0,0 +1,131 @@
+//! A minimal cooperative task queue.
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+
+use crate::Deque;
+
+struct State<const N: usize> {
+    /// The tasks that have been woken, in the order they were woken.
+    ready: Deque<usize, N>,
+
+    /// Whether each task is in `ready`, so it's only queued once.
+    queued: [bool; N],
+}
+
+/// A run-to-completion scheduler for `N` tasks, for parts too small for an
+/// async executor.
+///
+/// Tasks are plain functions, declared up front and identified by their
+/// index. Waking a task (from an interrupt handler or another task) queues
+/// it to run once; waking it again before it runs has no further effect.
+/// The main loop calls [`run_once`](Self::run_once) or
+/// [`run_until_idle`](Self::run_until_idle) to run the woken tasks in the
+/// order they were woken, and sleeps when there's nothing to do.
+///
+/// The ready queue is guarded by a [`critical_section`], which is only held
+/// while queueing and dequeueing, never while a task runs.
+///
+/// # Examples
+///
+/// ```
+/// use core::sync::atomic::{AtomicU32, Ordering};
+/// use fullhouse::task::TaskQueue;
+///
+/// const BUTTON: usize = 0;
+/// const LED: usize = 1;
+///
+/// static PRESSES: AtomicU32 = AtomicU32::new(0);
+///
+/// fn button() {
+///     PRESSES.fetch_add(1, Ordering::Relaxed);
+///     TASKS.wake(LED);
+/// }
+///
+/// fn led() {
+///     // Toggle the LED.
+/// }
+///
+/// static TASKS: TaskQueue<2> = TaskQueue::new([button, led]);
+///
+/// // In the button interrupt handler:
+/// TASKS.wake(BUTTON);
+/// TASKS.wake(BUTTON);
+///
+/// // In the main loop:
+/// assert_eq!(TASKS.run_until_idle(), 2);
+/// assert_eq!(PRESSES.load(Ordering::Relaxed), 1);
+/// assert!(!TASKS.run_once());
+/// ```
+pub struct TaskQueue<const N: usize> {
+    tasks: [fn(); N],
+    state: Mutex<RefCell<State<N>>>,
+}
+
+impl<const N: usize> TaskQueue<N> {
+    /// Creates a queue for the given tasks, none of which are woken.
+    pub const fn new(tasks: [fn(); N]) -> Self {
+        Self {
+            tasks,
+            state: Mutex::new(RefCell::new(State {
+                ready: Deque::new(),
+                queued: [false; N],
+            })),
+        }
+    }
+
+    /// Queues task `id` to run, unless it's already queued.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not less than `N`.
+    pub fn wake(&self, id: usize) {
+        assert!(id < N, "task id out of range");
+        critical_section::with(|cs| {
+            let mut state = self.state.borrow_ref_mut(cs);
+            if !state.queued[id] {
+                state.queued[id] = true;
+                // There is room, since each task is queued at most once.
+                let _ = state.ready.push_back(id);
+            }
+        });
+    }
+
+    /// Returns `true` if no task is waiting to run.
+    pub fn is_idle(&self) -> bool {
+        critical_section::with(|cs| self.state.borrow_ref(cs).ready.is_empty())
+    }
+
+    /// Runs the task that was woken first, returning `false` if there was
+    /// none.
+    ///
+    /// The task is dequeued before it runs, so it can be woken again while
+    /// it's running (even by itself), and will then run again later.
+    pub fn run_once(&self) -> bool {
+        let next = critical_section::with(|cs| {
+            let mut state = self.state.borrow_ref_mut(cs);
+            let id = state.ready.pop_front()?;
+            state.queued[id] = false;
+            Some(id)
+        });
+        match next {
+            Some(id) => {
+                (self.tasks[id])();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Runs tasks until none are waiting, returning the number of tasks run.
+    ///
+    /// A task that keeps waking itself keeps this from returning.
+    pub fn run_until_idle(&self) -> usize {
+        let mut count = 0;
+        while self.run_once() {
+            count += 1;
+        }
+        count
+    }
+}
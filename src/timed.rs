@@ -0,0 +1,149 @@
+//! A deque whose elements are stamped with the time they were pushed.
+
+use crate::Deque;
+
+/// A deque of up to `N` elements, each stamped with a tick of type `I` when
+/// it is pushed, so old elements can be expired from the front.
+///
+/// Ticks come from the caller (e.g. a millisecond counter), and are
+/// expected not to decrease from one push to the next, so the oldest
+/// element is always at the front. Expiring stops at the first element
+/// that is new enough; if ticks do go backwards, older elements behind it
+/// stay until it is gone.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::timed::TimedDeque;
+///
+/// let mut recent: TimedDeque<&str, u32, 8> = TimedDeque::new();
+/// recent.push_back(100, "a").unwrap();
+/// recent.push_back(150, "b").unwrap();
+/// recent.push_back(400, "c").unwrap();
+///
+/// // At tick 500, drop everything older than 300 ticks.
+/// assert_eq!(recent.expire_older_than(500 - 300), 2);
+/// assert_eq!(recent.pop_front(), Some((400, "c")));
+/// ```
+pub struct TimedDeque<T, I, const N: usize> {
+    entries: Deque<(I, T), N>,
+}
+
+impl<T, I: Copy + Ord, const N: usize> TimedDeque<T, I, N> {
+    /// Creates an empty deque.
+    pub const fn new() -> Self {
+        Self {
+            entries: Deque::new(),
+        }
+    }
+
+    /// The maximum number of elements the deque can hold.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// The number of elements in the deque.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the deque contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns `true` if the deque is at capacity.
+    pub fn is_full(&self) -> bool {
+        self.entries.is_full()
+    }
+
+    /// Removes all elements.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Appends an element pushed at `tick`.
+    ///
+    /// Returns `Err(value)` if the deque is full.
+    pub fn push_back(&mut self, tick: I, value: T) -> Result<(), T> {
+        self.entries
+            .push_back((tick, value))
+            .map_err(|(_, value)| value)
+    }
+
+    /// Removes the oldest element, along with its tick.
+    pub fn pop_front(&mut self) -> Option<(I, T)> {
+        self.entries.pop_front()
+    }
+
+    /// Returns the oldest element, along with its tick.
+    pub fn front(&self) -> Option<(I, &T)> {
+        self.entries.front().map(|(tick, value)| (*tick, value))
+    }
+
+    /// Returns the tick of the oldest element.
+    pub fn front_tick(&self) -> Option<I> {
+        self.entries.front().map(|&(tick, _)| tick)
+    }
+
+    /// Returns the element at `index` from the front, along with its tick.
+    pub fn get(&self, index: usize) -> Option<(I, &T)> {
+        self.entries.get(index).map(|(tick, value)| (*tick, value))
+    }
+
+    /// Returns an iterator over the elements and their ticks, oldest first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (I, &T)> + '_ {
+        self.entries.iter().map(|(tick, value)| (*tick, value))
+    }
+
+    /// Removes the oldest element if it was pushed before `deadline`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::timed::TimedDeque;
+    ///
+    /// let mut retries: TimedDeque<u16, u32, 4> = TimedDeque::new();
+    /// retries.push_back(10, 0xbeef).unwrap();
+    ///
+    /// assert_eq!(retries.pop_front_if_older(10), None);
+    /// assert_eq!(retries.pop_front_if_older(11), Some((10, 0xbeef)));
+    /// ```
+    pub fn pop_front_if_older(&mut self, deadline: I) -> Option<(I, T)> {
+        match self.entries.front() {
+            Some((tick, _)) if *tick < deadline => self.entries.pop_front(),
+            _ => None,
+        }
+    }
+
+    /// Removes the elements pushed before `deadline`, returning how many
+    /// were removed.
+    pub fn expire_older_than(&mut self, deadline: I) -> usize {
+        self.expire_older_than_with(deadline, drop)
+    }
+
+    /// Removes the elements pushed before `deadline`, passing each one to
+    /// `f`, oldest first. Returns how many were removed.
+    pub fn expire_older_than_with(&mut self, deadline: I, mut f: impl FnMut((I, T))) -> usize {
+        let mut count = 0;
+        while let Some(entry) = self.pop_front_if_older(deadline) {
+            f(entry);
+            count += 1;
+        }
+        count
+    }
+}
+
+impl<T, I: Copy + Ord, const N: usize> Default for TimedDeque<T, I, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, I: Clone, const N: usize> Clone for TimedDeque<T, I, N> {
+    fn clone(&self) -> Self {
+        Self {
+            entries: self.entries.clone(),
+        }
+    }
+}
@@ -0,0 +1,305 @@
+//! A lock-free flight recorder for trace events.
+
+use crate::sync::{const_fn, fence, AtomicBool, AtomicU32, AtomicUsize, Ordering};
+
+/// A single trace record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceEvent {
+    /// Identifies the kind of event; the meaning is up to the application.
+    pub id: u16,
+
+    /// The time at which the event was recorded, from the tracer's clock.
+    pub timestamp: u32,
+
+    /// Event-specific data.
+    pub payload: u32,
+}
+
+/// An item yielded by a [`TraceReader`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceItem {
+    /// The next recorded event.
+    Event(TraceEvent),
+
+    /// The given number of events were overwritten before they could be
+    /// read, or dropped because their slot was still busy.
+    Lost(usize),
+}
+
+/// A ring buffer slot, protected by a sequence lock.
+struct Slot {
+    /// `busy(ticket)` (which is odd) while the event with that ticket is
+    /// being written, and `done(ticket)` once it is complete. Zero if never
+    /// written. Only the writer that set it to `busy` writes the fields.
+    seq: AtomicUsize,
+    id: AtomicU32,
+    timestamp: AtomicU32,
+    payload: AtomicU32,
+}
+
+impl Slot {
+    const_fn! {
+        fn new() -> Self {
+            Self {
+                seq: AtomicUsize::new(0),
+                id: AtomicU32::new(0),
+                timestamp: AtomicU32::new(0),
+                payload: AtomicU32::new(0),
+            }
+        }
+    }
+
+    #[cfg(not(all(loom, feature = "loom")))]
+    const fn array<const N: usize>() -> [Self; N] {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const EMPTY: Slot = Slot::new();
+        [EMPTY; N]
+    }
+
+    /// Loom's atomics can't be created in constants, so the slots are
+    /// created one by one. Loom itself needs a newer compiler than the
+    /// crate, so `array::from_fn` is fine here.
+    #[cfg(all(loom, feature = "loom"))]
+    #[clippy::msrv = "1.63"]
+    fn array<const N: usize>() -> [Self; N] {
+        core::array::from_fn(|_| Self::new())
+    }
+}
+
+fn busy(ticket: usize) -> usize {
+    ticket.wrapping_mul(2).wrapping_add(1)
+}
+
+fn done(ticket: usize) -> usize {
+    ticket.wrapping_mul(2).wrapping_add(2)
+}
+
+/// Returns `true` if `seq` is zero or `done` of a ticket before `ticket`,
+/// so that the event with `ticket` may claim the slot.
+fn is_older(seq: usize, ticket: usize) -> bool {
+    // Tickets are compared modulo half the range of `seq`, which is twice
+    // the ticket.
+    let age = done(ticket).wrapping_sub(seq);
+    seq & 1 == 0 && age != 0 && age <= usize::MAX / 2
+}
+
+/// A flight recorder that keeps the last `N` trace events.
+///
+/// Events are fixed-size records of an ID, a timestamp and a payload word.
+/// [`trace`](Self::trace) can be called concurrently from any context,
+/// including interrupt handlers of any priority: it claims a slot with an
+/// atomic increment and never blocks. When the ring is full,
+/// the oldest events are overwritten.
+///
+/// Events are read back through a [`TraceReader`], e.g. by a low-priority
+/// task that dumps them over RTT or a UART. Readers never block writers;
+/// each slot is protected by a sequence number, so a reader that races with
+/// a writer (or falls behind) detects it and reports the affected events as
+/// lost instead of returning a torn record.
+///
+/// If a writer is preempted for long enough that the whole ring wraps
+/// around before it finishes writing, the newer events that land on its slot
+/// in the meantime are dropped, and reported as lost. If it is preempted
+/// before it starts writing, and a newer event takes its slot, its own event
+/// is dropped instead.
+///
+/// `N` must be a power of two.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::trace::{TraceItem, Tracer};
+///
+/// fn now() -> u32 {
+///     // Read a hardware timer...
+///     # 0
+/// }
+///
+/// static TRACE: Tracer<64> = Tracer::new(now);
+///
+/// const EV_IRQ_ENTER: u16 = 1;
+/// TRACE.trace(EV_IRQ_ENTER, 42);
+///
+/// let mut reader = TRACE.reader();
+/// match reader.next() {
+///     Some(TraceItem::Event(event)) => assert_eq!(event.payload, 42),
+///     other => panic!("unexpected {:?}", other),
+/// }
+/// assert_eq!(reader.next(), None);
+/// ```
+pub struct Tracer<const N: usize> {
+    /// The ticket to be claimed by the next event.
+    head: AtomicUsize,
+
+    /// Whether every slot has been claimed at least once, so that the `N`
+    /// tickets before `head` are all real events, even after `head` wraps.
+    filled: AtomicBool,
+    slots: [Slot; N],
+    clock: fn() -> u32,
+}
+
+impl<const N: usize> Tracer<N> {
+    /// Mask that reduces a ticket to a slot index.
+    const MASK: usize = {
+        assert!(
+            N.is_power_of_two(),
+            "Tracer capacity must be a power of two"
+        );
+        N - 1
+    };
+
+    const_fn! {
+        /// Creates an empty tracer that timestamps events with `clock`.
+        pub fn new(clock: fn() -> u32) -> Self {
+            // Force evaluation of the capacity check.
+            let _ = Self::MASK;
+
+            Self {
+                head: AtomicUsize::new(0),
+                filled: AtomicBool::new(false),
+                slots: Slot::array(),
+                clock,
+            }
+        }
+    }
+
+    /// Records an event with the current time.
+    pub fn trace(&self, id: u16, payload: u32) {
+        self.record(TraceEvent {
+            id,
+            timestamp: (self.clock)(),
+            payload,
+        });
+    }
+
+    /// Records an event with an explicit timestamp.
+    pub fn record(&self, event: TraceEvent) {
+        let ticket = self.head.fetch_add(1, Ordering::Relaxed);
+        let slot = &self.slots[ticket & Self::MASK];
+        if ticket & Self::MASK == Self::MASK {
+            self.filled.store(true, Ordering::Release);
+        }
+
+        // Claim the slot, unless another writer is still busy with it: one
+        // that was preempted for long enough for the ring to wrap around. The
+        // fields can't be shared, so this event is dropped instead, and
+        // readers report it as lost. The same goes if this writer was the
+        // one preempted, and a newer event has already been written to the
+        // slot: it must not be overwritten with an older one.
+        //
+        // Acquiring the previous writer's `done` orders its writes to the
+        // fields before this one's, so a reader can't see a mix of both.
+        let seq = slot.seq.load(Ordering::Relaxed);
+        if !is_older(seq, ticket)
+            || slot
+                .seq
+                .compare_exchange(seq, busy(ticket), Ordering::Acquire, Ordering::Relaxed)
+                .is_err()
+        {
+            return;
+        }
+        fence(Ordering::Release);
+        slot.id.store(u32::from(event.id), Ordering::Relaxed);
+        slot.timestamp.store(event.timestamp, Ordering::Relaxed);
+        slot.payload.store(event.payload, Ordering::Relaxed);
+        slot.seq.store(done(ticket), Ordering::Release);
+    }
+
+    /// The total number of events recorded so far (including ones that have
+    /// since been overwritten), modulo `usize::MAX + 1`.
+    pub fn recorded(&self) -> usize {
+        self.head.load(Ordering::Relaxed)
+    }
+
+    /// Creates a reader that starts at the oldest event still in the ring.
+    pub fn reader(&self) -> TraceReader<'_, N> {
+        // Before the ring has filled, the tickets before the first event
+        // were never claimed. If it fills in the meantime, the reader
+        // reports the overwritten events as lost. Acquiring `filled` first
+        // makes sure `head` is then at least `N`.
+        let filled = self.filled.load(Ordering::Acquire);
+        let head = self.head.load(Ordering::Acquire);
+        let next = if filled { head.wrapping_sub(N) } else { 0 };
+        TraceReader { tracer: self, next }
+    }
+
+    /// Creates a reader that starts at the next event to be recorded, skipping
+    /// all of the current contents.
+    pub fn reader_from_now(&self) -> TraceReader<'_, N> {
+        TraceReader {
+            tracer: self,
+            next: self.head.load(Ordering::Acquire),
+        }
+    }
+}
+
+/// Reads events from a [`Tracer`] in the order they were recorded.
+///
+/// Any number of readers can exist at the same time, each with its own
+/// position. As an iterator, it yields items until it has caught up with the
+/// writers, and can be resumed later to pick up newer events.
+pub struct TraceReader<'a, const N: usize> {
+    tracer: &'a Tracer<N>,
+
+    /// The ticket of the next event to read.
+    next: usize,
+}
+
+impl<const N: usize> TraceReader<'_, N> {
+    /// The number of events that have been recorded but not read yet
+    /// (including ones that may already be lost).
+    pub fn pending(&self) -> usize {
+        self.tracer
+            .head
+            .load(Ordering::Acquire)
+            .wrapping_sub(self.next)
+    }
+}
+
+impl<const N: usize> Iterator for TraceReader<'_, N> {
+    type Item = TraceItem;
+
+    fn next(&mut self) -> Option<TraceItem> {
+        let head = self.tracer.head.load(Ordering::Acquire);
+        let behind = head.wrapping_sub(self.next);
+        if behind == 0 {
+            return None;
+        }
+        if behind > N {
+            // The oldest unread events have been overwritten.
+            let lost = behind - N;
+            self.next = self.next.wrapping_add(lost);
+            return Some(TraceItem::Lost(lost));
+        }
+
+        let slot = &self.tracer.slots[self.next & Tracer::<N>::MASK];
+        let seq = slot.seq.load(Ordering::Acquire);
+        let event = TraceEvent {
+            id: slot.id.load(Ordering::Relaxed) as u16,
+            timestamp: slot.timestamp.load(Ordering::Relaxed),
+            payload: slot.payload.load(Ordering::Relaxed),
+        };
+        fence(Ordering::Acquire);
+        let seq_after = slot.seq.load(Ordering::Relaxed);
+
+        if seq == done(self.next) && seq_after == seq {
+            self.next = self.next.wrapping_add(1);
+            return Some(TraceItem::Event(event));
+        }
+
+        let head = self.tracer.head.load(Ordering::Acquire);
+        if head.wrapping_sub(self.next) > N {
+            // A newer event has claimed the slot.
+            self.next = self.next.wrapping_add(1);
+            Some(TraceItem::Lost(1))
+        } else if seq == busy(self.next) {
+            // The event has been claimed, but is still being written.
+            None
+        } else {
+            // An older writer is still busy with the slot, or has finished
+            // with it after this event was dropped.
+            self.next = self.next.wrapping_add(1);
+            Some(TraceItem::Lost(1))
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! A small key-value cache whose entries expire.
+
+struct Entry<K, V, I> {
+    key: K,
+    value: V,
+    expires: I,
+}
+
+/// A cache of up to `N` key-value pairs, each stored with the tick of type
+/// `I` at which it expires.
+///
+/// Lookups take the current tick, and treat an entry as missing from the
+/// tick it expires at onward. Expired entries are reclaimed when their slot
+/// is needed by [`insert`](Self::insert), or all at once by
+/// [`purge_expired`](Self::purge_expired). When the cache is full of live
+/// entries, inserting evicts the one that would expire soonest.
+///
+/// Lookups scan every slot, which is fast for the handful of entries a
+/// firmware cache usually holds. Ticks are compared with [`Ord`], so they
+/// should not wrap around (e.g. a `u64` of milliseconds).
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::ttl::TtlCache;
+///
+/// let mut dns: TtlCache<&str, [u8; 4], u64, 4> = TtlCache::new();
+/// let now = 1_000;
+/// dns.insert("time.example", [192, 0, 2, 1], now + 300, now);
+///
+/// assert_eq!(dns.get(&"time.example", 1_200), Some(&[192, 0, 2, 1]));
+/// assert_eq!(dns.get(&"time.example", 1_300), None);
+/// ```
+pub struct TtlCache<K, V, I, const N: usize> {
+    entries: [Option<Entry<K, V, I>>; N],
+}
+
+impl<K: Eq, V, I: Copy + Ord, const N: usize> TtlCache<K, V, I, N> {
+    const VACANT: Option<Entry<K, V, I>> = None;
+
+    /// Creates an empty cache.
+    pub const fn new() -> Self {
+        Self {
+            entries: [Self::VACANT; N],
+        }
+    }
+
+    /// The maximum number of entries the cache can hold.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// The number of entries that haven't expired at `now`.
+    pub fn len(&self, now: I) -> usize {
+        self.live(now).count()
+    }
+
+    /// Returns `true` if every entry has expired at `now`.
+    pub fn is_empty(&self, now: I) -> bool {
+        self.live(now).next().is_none()
+    }
+
+    fn live(&self, now: I) -> impl Iterator<Item = &Entry<K, V, I>> {
+        self.entries
+            .iter()
+            .flatten()
+            .filter(move |e| e.expires > now)
+    }
+
+    fn position(&self, key: &K) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|e| e.as_ref().map_or(false, |e| e.key == *key))
+    }
+
+    /// Returns the value for `key`, if it hasn't expired at `now`.
+    pub fn get(&self, key: &K, now: I) -> Option<&V> {
+        self.live(now).find(|e| e.key == *key).map(|e| &e.value)
+    }
+
+    /// Returns the value for `key` mutably, if it hasn't expired at `now`.
+    pub fn get_mut(&mut self, key: &K, now: I) -> Option<&mut V> {
+        self.entries
+            .iter_mut()
+            .flatten()
+            .find(|e| e.key == *key && e.expires > now)
+            .map(|e| &mut e.value)
+    }
+
+    /// Returns `true` if there is a value for `key` that hasn't expired at
+    /// `now`.
+    pub fn contains_key(&self, key: &K, now: I) -> bool {
+        self.get(key, now).is_some()
+    }
+
+    /// Returns the tick at which the entry for `key` expires, if it hasn't
+    /// expired at `now`.
+    pub fn expires_at(&self, key: &K, now: I) -> Option<I> {
+        self.live(now).find(|e| e.key == *key).map(|e| e.expires)
+    }
+
+    /// Stores `value` for `key` until the tick `expires`, returning the
+    /// previous value for `key` if it hadn't expired at `now`.
+    ///
+    /// If the cache is full, this takes the slot of an expired entry, or,
+    /// if none have expired, evicts the entry that expires soonest.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::ttl::TtlCache;
+    ///
+    /// let mut cache: TtlCache<u8, u16, u32, 2> = TtlCache::new();
+    /// cache.insert(1, 100, 50, 0);
+    /// cache.insert(2, 200, 10, 0);
+    ///
+    /// // Full, so the entry expiring soonest makes room.
+    /// cache.insert(3, 300, 30, 0);
+    /// assert_eq!(cache.get(&2, 0), None);
+    /// assert_eq!(cache.get(&1, 0), Some(&100));
+    /// assert_eq!(cache.get(&3, 0), Some(&300));
+    /// ```
+    pub fn insert(&mut self, key: K, value: V, expires: I, now: I) -> Option<V> {
+        let entry = Entry {
+            key,
+            value,
+            expires,
+        };
+        let index = match self.position(&entry.key) {
+            Some(index) => index,
+            None => self.vacancy(now),
+        };
+        let old = self.entries[index].replace(entry)?;
+        (old.expires > now).then(|| old.value)
+    }
+
+    /// Finds the slot for a new key: an empty one, an expired one, or the
+    /// one expiring soonest.
+    fn vacancy(&self, now: I) -> usize {
+        let mut soonest: Option<(usize, I)> = None;
+        for (i, slot) in self.entries.iter().enumerate() {
+            let expires = match slot {
+                None => return i,
+                Some(e) if e.expires <= now => return i,
+                Some(e) => e.expires,
+            };
+            if soonest.map_or(true, |(_, s)| expires < s) {
+                soonest = Some((i, expires));
+            }
+        }
+        soonest.expect("cache has no slots").0
+    }
+
+    /// Removes the entry for `key`, returning its value if it hadn't
+    /// expired at `now`.
+    pub fn remove(&mut self, key: &K, now: I) -> Option<V> {
+        let old = self.entries[self.position(key)?].take()?;
+        (old.expires > now).then(|| old.value)
+    }
+
+    /// Drops the entries that have expired at `now`, returning how many
+    /// were dropped.
+    pub fn purge_expired(&mut self, now: I) -> usize {
+        let mut count = 0;
+        for slot in &mut self.entries {
+            if slot.as_ref().map_or(false, |e| e.expires <= now) {
+                *slot = None;
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Removes every entry.
+    pub fn clear(&mut self) {
+        for slot in &mut self.entries {
+            *slot = None;
+        }
+    }
+
+    /// Returns an iterator over the keys and values that haven't expired at
+    /// `now`, in no particular order.
+    pub fn iter(&self, now: I) -> impl Iterator<Item = (&K, &V)> {
+        self.live(now).map(|e| (&e.key, &e.value))
+    }
+}
+
+impl<K: Eq, V, I: Copy + Ord, const N: usize> Default for TtlCache<K, V, I, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
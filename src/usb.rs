@@ -0,0 +1,187 @@
+//! Buffers for USB bulk and interrupt endpoints.
+//!
+//! These sit between a USB class implementation (e.g. on `usb-device`) and
+//! the application. The endpoint itself is accessed through closures, so
+//! they work with any USB stack: pass something like
+//! `|packet| ep.write(packet)` or `|buf| ep.read(buf)`.
+
+use crate::Deque;
+
+/// Buffers data for an IN endpoint and splits it into packets of at most
+/// `MPS` bytes (the endpoint's max packet size).
+///
+/// The host considers a transfer finished when it receives a packet shorter
+/// than `MPS`. If the buffered data runs out exactly at a packet boundary,
+/// the next [`send_with`](Self::send_with) sends a zero-length packet, so
+/// the host doesn't keep waiting for more.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::usb::InBuffer;
+///
+/// let mut tx: InBuffer<64, 4> = InBuffer::new();
+/// tx.write(b"abcdefgh");
+///
+/// let mut packets = Vec::new();
+/// while let Some(result) = tx.send_with(|packet| {
+///     packets.push(packet.to_vec());
+///     Ok::<_, ()>(packet.len())
+/// }) {
+///     result.unwrap();
+/// }
+/// assert_eq!(packets, [&b"abcd"[..], b"efgh", b""]);
+/// ```
+pub struct InBuffer<const N: usize, const MPS: usize> {
+    data: Deque<u8, N>,
+
+    /// The last packet sent was full, so the transfer is not finished yet.
+    zlp_pending: bool,
+}
+
+impl<const N: usize, const MPS: usize> InBuffer<N, MPS> {
+    /// Creates an empty buffer.
+    pub const fn new() -> Self {
+        Self {
+            data: Deque::new(),
+            zlp_pending: false,
+        }
+    }
+
+    /// The number of bytes waiting to be sent.
+    pub const fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if no bytes are waiting to be sent.
+    ///
+    /// A zero-length packet may still be pending.
+    pub const fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Appends as much of `data` as fits, returning the number of bytes
+    /// appended.
+    pub fn write(&mut self, data: &[u8]) -> usize {
+        self.data.push_back_slice(data)
+    }
+
+    /// Sends the next packet, if there is one, by calling `write` with it.
+    ///
+    /// `write` should write the packet to the endpoint and return the number
+    /// of bytes written, which is then removed from the buffer. If it returns
+    /// an error (e.g. because the endpoint is still busy), nothing is
+    /// removed, and the same packet is offered again next time.
+    ///
+    /// Returns `None` if there is nothing to send.
+    pub fn send_with<E>(
+        &mut self,
+        write: impl FnOnce(&[u8]) -> Result<usize, E>,
+    ) -> Option<Result<usize, E>> {
+        if self.data.is_empty() {
+            if !self.zlp_pending {
+                return None;
+            }
+            return Some(write(&[]).map(|_| {
+                self.zlp_pending = false;
+                0
+            }));
+        }
+
+        let len = self.data.len().min(MPS);
+        if self.data.as_slices().0.len() < len {
+            self.data.make_contiguous();
+        }
+        let result = write(&self.data.as_slices().0[..len]);
+        Some(result.map(|written| {
+            let written = written.min(len);
+            self.data.advance_front(written);
+            self.zlp_pending = written == MPS;
+            written
+        }))
+    }
+}
+
+impl<const N: usize, const MPS: usize> Default for InBuffer<N, MPS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Accumulates packets received on an OUT endpoint with a max packet size
+/// of `MPS` bytes.
+///
+/// A packet is only read from the endpoint when there is room for a full
+/// packet, so the endpoint NAKs the host instead of data being dropped.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::usb::OutBuffer;
+///
+/// let mut rx: OutBuffer<8, 4> = OutBuffer::new();
+/// let result = rx.receive_with(|buf| {
+///     buf[..3].copy_from_slice(b"abc");
+///     Ok::<_, ()>(3)
+/// });
+/// assert_eq!(result, Some(Ok(3)));
+///
+/// let mut data = [0; 8];
+/// assert_eq!(rx.read(&mut data), 3);
+/// assert_eq!(&data[..3], b"abc");
+/// ```
+pub struct OutBuffer<const N: usize, const MPS: usize> {
+    data: Deque<u8, N>,
+}
+
+impl<const N: usize, const MPS: usize> OutBuffer<N, MPS> {
+    /// Creates an empty buffer.
+    pub const fn new() -> Self {
+        Self { data: Deque::new() }
+    }
+
+    /// The number of received bytes waiting to be read.
+    pub const fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if there are no received bytes waiting to be read.
+    pub const fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns `true` if there is room for another full packet.
+    pub fn can_receive(&self) -> bool {
+        N - self.data.len() >= MPS
+    }
+
+    /// Receives a packet by calling `read` with a buffer of `MPS` bytes.
+    ///
+    /// `read` should read a packet from the endpoint into the buffer and
+    /// return its length. On success, the packet is appended to the buffer.
+    ///
+    /// Returns `None` without calling `read` if there is no room for a full
+    /// packet.
+    pub fn receive_with<E>(
+        &mut self,
+        read: impl FnOnce(&mut [u8]) -> Result<usize, E>,
+    ) -> Option<Result<usize, E>> {
+        if !self.can_receive() {
+            return None;
+        }
+        let mut packet = [0; MPS];
+        Some(read(&mut packet).map(|len| self.data.push_back_slice(&packet[..len.min(MPS)])))
+    }
+
+    /// Moves received bytes into `buf`, returning the number of bytes
+    /// written.
+    pub fn read(&mut self, buf: &mut [u8]) -> usize {
+        self.data.pop_front_slice(buf)
+    }
+}
+
+impl<const N: usize, const MPS: usize> Default for OutBuffer<N, MPS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
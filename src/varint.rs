@@ -0,0 +1,165 @@
+//! LEB128 variable-length integers in byte deques.
+//!
+//! Each byte holds 7 bits of the value, least significant first, with the
+//! high bit set on every byte but the last. This is the encoding used by
+//! Protocol Buffers and postcard for integers, where small values take a
+//! single byte. Signed values are [zigzag] encoded first, so that small
+//! negative values are short too.
+//!
+//! [zigzag]: https://protobuf.dev/programming-guides/encoding/#signed-ints
+
+use crate::{DequeBase, Storage};
+
+/// The maximum length of an encoded `u64`.
+pub const MAX_LEN: usize = 10;
+
+/// The varint at the front of the deque doesn't fit in a `u64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+pub(crate) fn encode(mut value: u64, buf: &mut [u8; MAX_LEN]) -> usize {
+    let mut len = 0;
+    while value >= 0x80 {
+        buf[len] = value as u8 | 0x80;
+        value >>= 7;
+        len += 1;
+    }
+    buf[len] = value as u8;
+    len + 1
+}
+
+/// Decodes the varint at the start of `bytes`, returning its value and
+/// length.
+///
+/// Returns `Ok(None)` if `bytes` ends before the varint does, and
+/// `Err(Overflow)` if its first [`MAX_LEN`] bytes don't hold a whole `u64`.
+pub(crate) fn decode(bytes: &[u8]) -> Result<Option<(u64, usize)>, Overflow> {
+    let mut value = 0;
+    for (i, &byte) in bytes.iter().take(MAX_LEN).enumerate() {
+        // The last byte may only hold the top bit of a `u64`.
+        if i == MAX_LEN - 1 && byte > 1 {
+            return Err(Overflow);
+        }
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok(Some((value, i + 1)));
+        }
+    }
+    if bytes.len() >= MAX_LEN {
+        Err(Overflow)
+    } else {
+        Ok(None)
+    }
+}
+
+/// The number of bytes `value` takes as a varint.
+#[cfg(feature = "postcard-max-size")]
+pub(crate) const fn encoded_len(mut value: u64) -> usize {
+    let mut len = 1;
+    while value >= 0x80 {
+        value >>= 7;
+        len += 1;
+    }
+    len
+}
+
+pub(crate) fn zigzag_encode(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+pub(crate) fn zigzag_decode(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+impl<S: Storage<u8>> DequeBase<u8, S> {
+    /// Appends `value` as a varint.
+    ///
+    /// Returns `Err(value)` without writing anything if the deque doesn't
+    /// have room for all of it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut tx: Deque<u8, 4> = Deque::new();
+    /// tx.write_varint(300).unwrap();
+    /// assert_eq!(tx.as_slices().0, &[0xac, 0x02]);
+    /// assert_eq!(tx.write_varint(u64::MAX), Err(u64::MAX));
+    /// ```
+    pub fn write_varint(&mut self, value: u64) -> Result<(), u64> {
+        let mut buf = [0; MAX_LEN];
+        let len = encode(value, &mut buf);
+        if self.capacity() - self.len() < len {
+            return Err(value);
+        }
+        self.push_back_slice(&buf[..len]);
+        Ok(())
+    }
+
+    /// Removes a varint from the front of the deque and returns its value.
+    ///
+    /// Returns `Ok(None)` without removing anything if the varint isn't
+    /// complete yet. If it's too long to fit in a `u64`, the first
+    /// [`MAX_LEN`] bytes of it are removed and `Err(Overflow)` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut rx: Deque<u8, 16> = Deque::new();
+    /// rx.push_back_slice(&[0xac]);
+    /// assert_eq!(rx.read_varint(), Ok(None));
+    ///
+    /// rx.push_back_slice(&[0x02, 0x01]);
+    /// assert_eq!(rx.read_varint(), Ok(Some(300)));
+    /// assert_eq!(rx.read_varint(), Ok(Some(1)));
+    /// assert!(rx.is_empty());
+    /// ```
+    pub fn read_varint(&mut self) -> Result<Option<u64>, Overflow> {
+        let mut buf = [0; MAX_LEN];
+        let len = self.read_at(0, &mut buf);
+        match decode(&buf[..len]) {
+            Ok(Some((value, len))) => {
+                self.advance_front(len);
+                Ok(Some(value))
+            }
+            Ok(None) => Ok(None),
+            Err(Overflow) => {
+                self.advance_front(MAX_LEN);
+                Err(Overflow)
+            }
+        }
+    }
+
+    /// Appends `value` as a zigzag-encoded varint.
+    ///
+    /// Returns `Err(value)` without writing anything if the deque doesn't
+    /// have room for all of it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<u8, 16> = Deque::new();
+    /// d.write_varint_zigzag(-2).unwrap();
+    /// assert_eq!(d.as_slices().0, &[0x03]);
+    /// d.write_varint_zigzag(i64::MIN).unwrap();
+    ///
+    /// assert_eq!(d.read_varint_zigzag(), Ok(Some(-2)));
+    /// assert_eq!(d.read_varint_zigzag(), Ok(Some(i64::MIN)));
+    /// ```
+    pub fn write_varint_zigzag(&mut self, value: i64) -> Result<(), i64> {
+        self.write_varint(zigzag_encode(value)).map_err(|_| value)
+    }
+
+    /// Removes a zigzag-encoded varint from the front of the deque and
+    /// returns its value.
+    ///
+    /// See [`read_varint`](Self::read_varint).
+    pub fn read_varint_zigzag(&mut self) -> Result<Option<i64>, Overflow> {
+        Ok(self.read_varint()?.map(zigzag_decode))
+    }
+}
@@ -0,0 +1,64 @@
+//! Conversions to `Vec`.
+
+use alloc::vec::Vec;
+
+use crate::{DequeBase, Storage};
+
+impl<T: Clone, S: Storage<T>> DequeBase<T, S> {
+    /// Copies the elements into a new `Vec`, from front to back.
+    ///
+    /// For `Copy` types, this is one or two `memcpy`s.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<u8, 4> = Deque::new();
+    /// d.push_back(2).unwrap();
+    /// d.push_front(1).unwrap();
+    /// assert_eq!(d.to_vec(), [1, 2]);
+    /// ```
+    pub fn to_vec(&self) -> Vec<T> {
+        let (front, back) = self.as_slices();
+        let mut vec = Vec::with_capacity(self.len());
+        vec.extend_from_slice(front);
+        vec.extend_from_slice(back);
+        vec
+    }
+}
+
+impl<T, S: Storage<T>> DequeBase<T, S> {
+    /// Moves the elements into a new `Vec`, from front to back.
+    ///
+    /// The elements are moved with one or two `memcpy`s.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fullhouse::Deque;
+    ///
+    /// let mut d: Deque<String, 4> = Deque::new();
+    /// d.push_back("b".to_string()).unwrap();
+    /// d.push_front("a".to_string()).unwrap();
+    /// assert_eq!(d.into_vec(), ["a", "b"]);
+    /// ```
+    pub fn into_vec(mut self) -> Vec<T> {
+        let len = self.len();
+        let mut vec = Vec::with_capacity(len);
+        // Safety: The vector has room for `len` elements, which now belong
+        // to it; the deque forgets them below.
+        unsafe {
+            self.copy_out(0, vec.as_mut_ptr(), len);
+            vec.set_len(len);
+        }
+        self.forget_all();
+        vec
+    }
+}
+
+impl<T, S: Storage<T>> From<DequeBase<T, S>> for Vec<T> {
+    fn from(deque: DequeBase<T, S>) -> Self {
+        deque.into_vec()
+    }
+}
@@ -0,0 +1,135 @@
+//! Waker storage that can be shared between a task and an interrupt.
+
+use core::task::Waker;
+
+use crate::sync::{const_fn, AtomicU8, Ordering, UnsafeCell};
+
+/// No registration or wake is in progress.
+const IDLE: u8 = 0;
+
+/// A task is in the middle of storing its waker.
+const REGISTERING: u8 = 1;
+
+/// A wake is in progress, or was requested while a registration was in
+/// progress.
+const WAKING: u8 = 2;
+
+/// A slot holding a single [`Waker`], which can be registered by one task and
+/// woken from any context, including interrupt handlers.
+///
+/// This follows the same protocol as the `AtomicWaker` in `futures`: the slot
+/// is guarded by a small state machine instead of a lock, so [`wake`] never
+/// blocks and never waits for a concurrent [`register`] to finish. If a wake
+/// races with a registration, the registering task is woken immediately
+/// instead.
+///
+/// Only one waker is stored at a time; registering a new waker replaces the
+/// previous one.
+///
+/// [`wake`]: Self::wake
+/// [`register`]: Self::register
+pub struct AtomicWaker {
+    state: AtomicU8,
+    waker: UnsafeCell<Option<Waker>>,
+}
+
+// Safety: Access to `waker` is serialized by the `state` machine; only the
+// context that moves the state out of `IDLE` touches the cell.
+unsafe impl Send for AtomicWaker {}
+unsafe impl Sync for AtomicWaker {}
+
+impl AtomicWaker {
+    const_fn! {
+        /// Creates an empty waker slot.
+        pub fn new() -> Self {
+            Self {
+                state: AtomicU8::new(IDLE),
+                waker: UnsafeCell::new(None),
+            }
+        }
+    }
+
+    /// Stores `waker` to be woken by the next call to [`wake`](Self::wake).
+    ///
+    /// Should only be called from one task at a time. If another context
+    /// registers concurrently, one of the two registrations is woken
+    /// immediately so that it can retry.
+    pub fn register(&self, waker: &Waker) {
+        match self
+            .state
+            .compare_exchange(IDLE, REGISTERING, Ordering::Acquire, Ordering::Acquire)
+        {
+            Ok(_) => {
+                // Safety: We hold the `REGISTERING` state, which excludes all
+                // other accesses to the cell.
+                self.waker.with_mut(|slot| {
+                    let slot = unsafe { &mut *slot };
+                    match slot {
+                        Some(old) if old.will_wake(waker) => {}
+                        _ => *slot = Some(waker.clone()),
+                    }
+                });
+
+                if let Err(actual) = self.state.compare_exchange(
+                    REGISTERING,
+                    IDLE,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                ) {
+                    // A wake arrived while we were registering; it could not
+                    // take the waker, so deliver it ourselves.
+                    debug_assert_eq!(actual, REGISTERING | WAKING);
+
+                    // Safety: The state still has `REGISTERING` set, so no one
+                    // else touches the cell until we reset it below.
+                    let waker = self.waker.with_mut(|slot| unsafe { (*slot).take() });
+                    self.state.swap(IDLE, Ordering::AcqRel);
+                    if let Some(waker) = waker {
+                        waker.wake();
+                    }
+                }
+            }
+            Err(WAKING) => {
+                // A wake is currently in progress; make sure this registration
+                // is not lost by waking it right away.
+                waker.wake_by_ref();
+            }
+            Err(_) => {
+                // Concurrent registration from another context; this is
+                // misuse, but fail safe by waking the caller.
+                waker.wake_by_ref();
+            }
+        }
+    }
+
+    /// Wakes the registered waker, if any, and clears the slot.
+    pub fn wake(&self) {
+        if let Some(waker) = self.take() {
+            waker.wake();
+        }
+    }
+
+    /// Removes and returns the registered waker, if any.
+    pub fn take(&self) -> Option<Waker> {
+        match self.state.fetch_or(WAKING, Ordering::AcqRel) {
+            IDLE => {
+                // Safety: We moved the state from `IDLE` to `WAKING`, which
+                // excludes all other accesses to the cell.
+                let waker = self.waker.with_mut(|slot| unsafe { (*slot).take() });
+                self.state.fetch_and(!WAKING, Ordering::Release);
+                waker
+            }
+            _ => {
+                // Either a registration is in progress (and will observe the
+                // `WAKING` bit we just set), or another wake is in progress.
+                None
+            }
+        }
+    }
+}
+
+impl Default for AtomicWaker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,98 @@
+//! JavaScript bindings for byte deques.
+//!
+//! This lets a simulator running in a browser use the same buffering as the
+//! firmware. In JavaScript, `write` takes a `Uint8Array`, and `read` fills
+//! one:
+//!
+//! ```js
+//! const ring = new ByteDeque(64);
+//! ring.write(new TextEncoder().encode("hello"));
+//!
+//! const out = new Uint8Array(ring.len());
+//! ring.read(out);
+//! ```
+
+use alloc::vec::Vec;
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::BoxDeque;
+
+/// A byte deque with a fixed capacity, exported to JavaScript as
+/// `ByteDeque`.
+///
+/// # Examples
+///
+/// ```
+/// use fullhouse::wasm::ByteDeque;
+///
+/// let mut ring = ByteDeque::new(4);
+/// assert_eq!(ring.write(b"hello"), 4);
+///
+/// let mut out = [0; 2];
+/// assert_eq!(ring.read(&mut out), 2);
+/// assert_eq!(&out, b"he");
+/// assert_eq!(ring.read_all(), b"ll");
+/// ```
+#[wasm_bindgen]
+pub struct ByteDeque {
+    inner: BoxDeque<u8>,
+}
+
+#[wasm_bindgen]
+impl ByteDeque {
+    /// Creates an empty deque that holds up to `capacity` bytes.
+    #[wasm_bindgen(constructor)]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: BoxDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Appends as many bytes from `data` as fit, returning the number of
+    /// bytes appended.
+    pub fn write(&mut self, data: &[u8]) -> usize {
+        self.inner.push_back_slice(data)
+    }
+
+    /// Removes bytes from the front of the deque into `buf`, returning the
+    /// number of bytes removed.
+    pub fn read(&mut self, buf: &mut [u8]) -> usize {
+        self.inner.pop_front_slice(buf)
+    }
+
+    /// Removes all bytes from the deque and returns them.
+    #[wasm_bindgen(js_name = readAll)]
+    pub fn read_all(&mut self) -> Vec<u8> {
+        let mut data = alloc::vec![0; self.inner.len()];
+        self.inner.pop_front_slice(&mut data);
+        data
+    }
+
+    /// Copies bytes from the front of the deque into `buf` without removing
+    /// them, returning the number of bytes copied.
+    pub fn peek(&self, buf: &mut [u8]) -> usize {
+        self.inner.read_at(0, buf)
+    }
+
+    /// The number of bytes in the deque.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if the deque contains no bytes.
+    #[wasm_bindgen(js_name = isEmpty)]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// The maximum number of bytes the deque can hold.
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    /// Removes all bytes from the deque.
+    pub fn clear(&mut self) {
+        self.inner.clear()
+    }
+}
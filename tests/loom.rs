@@ -0,0 +1,190 @@
+//! Loom models of the lock-free types.
+//!
+//! Run with:
+//!
+//! ```text
+//! RUSTFLAGS="--cfg loom" cargo test --release --features loom --test loom
+//! ```
+
+#![cfg(all(loom, feature = "loom"))]
+
+use core::{future::poll_fn, task::Poll};
+
+use fullhouse::{
+    trace::{TraceEvent, TraceItem, Tracer},
+    AtomicWaker, Semaphore, SlotAllocator,
+};
+use loom::{
+    cell::UnsafeCell,
+    future::block_on,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+/// A wake that races with a registration is never lost.
+#[test]
+fn atomic_waker_wake_is_not_lost() {
+    loom::model(|| {
+        let waker = Arc::new(AtomicWaker::new());
+        let ready = Arc::new(AtomicBool::new(false));
+
+        let waking = thread::spawn({
+            let waker = waker.clone();
+            let ready = ready.clone();
+            move || {
+                ready.store(true, Ordering::Release);
+                waker.wake();
+            }
+        });
+
+        block_on(poll_fn(|cx| {
+            waker.register(cx.waker());
+            if ready.load(Ordering::Acquire) {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        }));
+        waking.join().unwrap();
+    });
+}
+
+/// A waiting task is woken by a release from another thread, and every
+/// permit is accounted for.
+#[test]
+fn semaphore_release_wakes_acquire() {
+    loom::model(|| {
+        let semaphore = Arc::new(Semaphore::new(0));
+
+        let releasing = thread::spawn({
+            let semaphore = semaphore.clone();
+            move || semaphore.release_many(2)
+        });
+
+        block_on(semaphore.acquire());
+        releasing.join().unwrap();
+        assert_eq!(semaphore.available(), 1);
+    });
+}
+
+/// Concurrent acquires never take more permits than were available.
+#[test]
+fn semaphore_acquires_do_not_oversubscribe() {
+    loom::model(|| {
+        let semaphore = Arc::new(Semaphore::new(1));
+
+        let other = thread::spawn({
+            let semaphore = semaphore.clone();
+            move || semaphore.try_acquire()
+        });
+
+        let here = semaphore.try_acquire();
+        let there = other.join().unwrap();
+        assert!(here != there);
+        assert_eq!(semaphore.available(), 0);
+    });
+}
+
+/// A slot is only ever claimed by one thread at a time, and what the
+/// previous owner wrote to it is visible to the next one.
+#[test]
+fn slot_allocator_claims_are_exclusive() {
+    loom::model(|| {
+        let shared = Arc::new((SlotAllocator::<1>::with_slots(1), UnsafeCell::new(0)));
+
+        let bump = |(slots, buffer): &(SlotAllocator<1>, UnsafeCell<u32>)| {
+            if let Some(slot) = slots.claim() {
+                buffer.with_mut(|buffer| unsafe { *buffer += 1 });
+                slots.release(slot);
+            }
+        };
+
+        let other = thread::spawn({
+            let shared = shared.clone();
+            move || bump(&shared)
+        });
+        bump(&shared);
+        other.join().unwrap();
+
+        let (slots, _) = &*shared;
+        assert_eq!(slots.claimed(), 0);
+    });
+}
+
+/// Two writers race for a slot (the ring wraps around while the first one
+/// is still writing) and a reader races with both, and the reader never
+/// sees a torn event.
+#[test]
+fn tracer_reader_never_sees_torn_events() {
+    fn event(n: u16) -> TraceEvent {
+        TraceEvent {
+            id: n,
+            timestamp: n.into(),
+            payload: n.into(),
+        }
+    }
+
+    fn no_clock() -> u32 {
+        0
+    }
+
+    // Exploring every interleaving of three threads takes too long, and the
+    // races here only need a few preemptions.
+    let mut model = loom::model::Builder::new();
+    model.preemption_bound = Some(3);
+    model.check(|| {
+        let tracer = Arc::new(Tracer::<1>::new(no_clock));
+
+        let writers: [_; 2] = [1, 2].map(|n| {
+            thread::spawn({
+                let tracer = tracer.clone();
+                move || tracer.record(event(n))
+            })
+        });
+
+        for item in tracer.reader() {
+            if let TraceItem::Event(e) = item {
+                assert_eq!(e, event(e.id));
+            }
+        }
+        for writer in writers {
+            writer.join().unwrap();
+        }
+    });
+}
+
+/// A writer preempted before claiming its slot doesn't overwrite the newer
+/// event that claimed it first, and a reader started afterwards doesn't stall
+/// on the slot, whichever event was dropped.
+#[test]
+fn tracer_reader_does_not_stall_after_writers_race() {
+    fn no_clock() -> u32 {
+        0
+    }
+
+    loom::model(|| {
+        let tracer = Arc::new(Tracer::<1>::new(no_clock));
+
+        let writers: [_; 2] = [1, 2].map(|n| {
+            thread::spawn({
+                let tracer = tracer.clone();
+                move || tracer.trace(n, n.into())
+            })
+        });
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let mut reader = tracer.reader();
+        assert_eq!(reader.pending(), 1);
+        match reader.next() {
+            Some(TraceItem::Event(e)) => assert_eq!(e.payload, e.id.into()),
+            Some(TraceItem::Lost(1)) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(reader.pending(), 0);
+    });
+}
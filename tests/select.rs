@@ -0,0 +1,59 @@
+//! Wakeups of a `Select` over async deques.
+
+#![cfg(feature = "critical-section")]
+
+use core::{
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::{Context, Poll},
+};
+use std::{
+    sync::Arc,
+    task::{Wake, Waker},
+};
+
+use fullhouse::{select::Select, AsyncDeque};
+
+/// Counts how many times it was woken.
+#[derive(Default)]
+struct CountingWaker(AtomicUsize);
+
+impl CountingWaker {
+    fn count(&self) -> usize {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+impl Wake for CountingWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// A select that finds the only element granted to a parked popper is still
+/// woken by the next push.
+#[test]
+fn select_is_woken_after_granted_element_is_taken() {
+    let deque: AsyncDeque<u8, 4> = AsyncDeque::new();
+
+    let popper_waker = Arc::new(CountingWaker::default());
+    let popper_waker_ref = Waker::from(popper_waker.clone());
+    let mut popper_cx = Context::from_waker(&popper_waker_ref);
+    let mut pop = deque.pop_front();
+    assert_eq!(Pin::new(&mut pop).poll(&mut popper_cx), Poll::Pending);
+
+    deque.try_push_back(1).unwrap();
+    assert_eq!(popper_waker.count(), 1);
+
+    let select_waker = Arc::new(CountingWaker::default());
+    let select_waker_ref = Waker::from(select_waker.clone());
+    let mut select_cx = Context::from_waker(&select_waker_ref);
+    let mut select = Select::new().recv(&deque, |x| x);
+    assert_eq!(Pin::new(&mut select).poll(&mut select_cx), Poll::Pending);
+
+    assert_eq!(Pin::new(&mut pop).poll(&mut popper_cx), Poll::Ready(1));
+    deque.try_push_back(2).unwrap();
+    assert!(select_waker.count() > 0);
+    assert_eq!(Pin::new(&mut select).poll(&mut select_cx), Poll::Ready(2));
+}